@@ -5,7 +5,8 @@ use iced_native::{
     Element, Event, Length, ProgressBar, Row, Space, Text, TextInput,
 };
 use iced_tui::{
-    AnsiColor, Application, ButtonStyle, ProgressBarStyle, Style, TextInputStyle, TuiRenderer,
+    AnsiColor, Application, Border, ButtonStyle, ContainerStyle, ProgressBarStyle, Shadow, Style,
+    TextInputStyle, TuiRenderer,
 };
 use simplelog::{Config, LevelFilter, WriteLogger};
 
@@ -14,7 +15,9 @@ pub struct MyApp {
     text_state: text_input::State,
     input_value: String,
     button_state: button::State,
+    #[allow(dead_code)]
     scroll_state: scrollable::State,
+    #[allow(dead_code)]
     lines: Vec<String>,
 }
 
@@ -92,6 +95,7 @@ impl Application for MyApp {
                                             Style::new().bg(AnsiColor::Red).fg(AnsiColor::White),
                                         )
                                         .hover(Style::new().bg(AnsiColor::DarkRed))
+                                        .focused(Style::new().bg(AnsiColor::DarkRed).bold())
                                         .pressed(Style::new().bg(AnsiColor::Blue)),
                                 )
                                 .on_press(AppMessage::ButtonPressed),
@@ -115,6 +119,14 @@ impl Application for MyApp {
                 //    ),
                 //),
         )
+        .padding(1)
+        .style(
+            ContainerStyle::new()
+                .border(Border::Rounded)
+                .border_color(AnsiColor::DarkCyan)
+                .title("Interaction")
+                .shadow(Shadow::new()),
+        )
         .into()
     }
 
@@ -1,7 +1,10 @@
 use crate::constants::LOG_TARGET;
+use crate::renderer::primitives::Primitive;
 use crate::renderer::RenderResult;
+use crate::Click;
+use crate::Clipboard;
+use crate::Settings;
 use crate::TuiRenderer;
-use core::cell::RefCell;
 pub use crossterm::{
     cursor,
     event::{self, KeyCode, KeyEvent},
@@ -11,8 +14,8 @@ pub use crossterm::{
 };
 use iced_core::Point;
 use iced_core::Size;
-use iced_futures::futures::{self, channel::mpsc, Sink};
-use iced_native::clipboard;
+use iced_futures::futures::channel::mpsc;
+use iced_futures::time;
 use iced_native::keyboard;
 use iced_native::mouse;
 use iced_native::window;
@@ -23,7 +26,6 @@ use iced_native::Subscription;
 use iced_native::UserInterface;
 use iced_native::{Cache, Element};
 use std::fmt::Debug;
-use std::rc::Rc;
 use std::sync::atomic::AtomicU16;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -73,6 +75,16 @@ pub trait Application {
         None
     }
 
+    /// Returns the [`Settings`] of the runtime.
+    ///
+    /// By default, it returns [`Settings::default`].
+    fn settings() -> Settings
+    where
+        Self: Sized,
+    {
+        Settings::default()
+    }
+
     /// Launches the sandbox and takes ownership of the current thread.
     ///
     /// This should be the last thing you execute at the end of the entrypoint of
@@ -82,6 +94,7 @@ pub trait Application {
         Self: 'static + Sized + Send,
         <Self as Application>::Executor: Send,
     {
+        let settings = Self::settings();
         let (sender, mut receiver) = mpsc::unbounded::<UiMessage<Self::Message>>();
 
         let runtime_executor = Self::Executor::new().expect("Create executor");
@@ -126,12 +139,17 @@ pub trait Application {
                             .map(Event::Mouse)
                             .collect()
                     }
+                    event::Event::Paste(text) => map_paste_event(&text)
+                        .into_iter()
+                        .map(Event::Keyboard)
+                        .collect(),
                     event::Event::Resize(width, height) => {
                         vec![Event::Window(window::Event::Resized {
                             width: width as u32,
                             height: height as u32,
                         })]
                     }
+                    event::Event::FocusGained | event::Event::FocusLost => vec![],
                 };
 
                 if let Err(err) =
@@ -143,30 +161,23 @@ pub trait Application {
             }
         });
 
-        let mapper = AppMessageMapperSink::from_sender(sender);
-        let mut runtime = iced_futures::Runtime::new(runtime_executor, mapper);
+        let mut runtime = iced_futures::Runtime::new(runtime_executor, sender);
 
-        let (app, command) = runtime.enter(Self::new);
-        let application = Rc::new(RefCell::new(app));
+        let (mut application, command) = runtime.enter(Self::new);
 
-        runtime.spawn(command);
+        runtime.spawn(command.map(UiMessage::AppMessage));
 
-        let mut cache = Some(Cache::default());
-        let mut renderer = TuiRenderer::default();
+        let mut ui_runtime = UiRuntime::new(settings);
         let mut last_render: Option<RenderResult> = None;
-        let mut last_term_size = (0_u16, 0_u16);
 
         let mut stdout = std::io::stdout();
-        renderer.begin_screen(&mut stdout);
+        ui_runtime.renderer.begin_screen(&mut stdout);
 
         let mut ui_message: UiMessage<Self::Message> = UiMessage::RenderRequest;
 
         // event loop on main thread
         let exit_status_code: u8 = loop {
-            // TODO: Review logic about immeditate render when state is updated
-            let mut state_updated = false;
-            let current_ui_message = ui_message.clone();
-            let (commands, subscription, events, event_statuses) = runtime.enter(|| {
+            let (cycle, subscription) = runtime.enter(|| {
                 let term_size = terminal::size().unwrap();
                 let size = Size {
                     width: term_size.0 as f32,
@@ -178,86 +189,49 @@ pub trait Application {
                     y: last_mouse_position.1.load(Ordering::Relaxed) as f32,
                 };
 
-                // render and return messages
-                let mut app_bmut = application.borrow_mut();
-                let view_result = app_bmut.view();
-
-                let mut ui =
-                    UserInterface::build(view_result, size, cache.take().unwrap(), &mut renderer);
-
-                if ui_message.is_render_request() {
-                    let primitive = ui.draw(&mut renderer, cursor_position);
-                    last_render = Some(renderer.render(&mut stdout, primitive, last_render.take()));
-                }
-
-                let mut messages: Vec<Self::Message> = vec![];
-                let mut events: Vec<Event> = vec![];
-                let mut event_statuses: Vec<iced_native::event::Status> = vec![];
-                let mut ui_updated = false;
-
-                match current_ui_message {
-                    UiMessage::AppMessage(message) => messages.push(message),
-                    UiMessage::IcedEvents(iced_events) => {
-                        events = iced_events;
-
-                        if !events.is_empty() {
-                            event_statuses = ui.update(
-                                &events,
-                                cursor_position,
-                                &renderer,
-                                &mut clipboard::Null,
-                                &mut messages,
-                            );
-
-                            for status in &event_statuses {
-                                if status == &iced_native::event::Status::Captured {
-                                    ui_updated = true;
-                                    break;
-                                }
-                            }
-                        }
-                    }
-                    _ => (),
+                let cycle = ui_runtime.cycle(
+                    &mut application,
+                    size,
+                    cursor_position,
+                    ui_message,
+                    |renderer, primitive| {
+                        last_render =
+                            Some(renderer.render(&mut stdout, primitive, last_render.take()));
+                    },
+                );
+
+                let subscription = application.subscription().map(UiMessage::AppMessage);
+
+                // the animations drawn in the last frame are drawn again at each tick
+                let subscription = match ui_runtime.renderer.animation_interval() {
+                    Some(interval) => Subscription::batch(vec![
+                        subscription,
+                        time::every(interval).map(|_| UiMessage::RenderRequest),
+                    ]),
+                    None => subscription,
                 };
 
-                cache = Some(ui.into_cache());
-
-                // update state
-                let mut commands: Vec<Command<Self::Message>> = vec![];
-
-                for message in messages {
-                    commands.push(app_bmut.update(message));
-                    state_updated = true;
-                }
-
-                if ui_updated {
-                    state_updated = true;
-                }
-
-                let subscription = app_bmut.subscription();
-                last_term_size = term_size;
-
-                (commands, subscription, events, event_statuses)
+                (cycle, subscription)
             });
 
-            for command in commands {
-                runtime.spawn(command);
+            for command in cycle.commands {
+                runtime.spawn(command.map(UiMessage::AppMessage));
             }
 
-            for (event, event_status) in events.iter().zip(event_statuses) {
+            for (event, event_status) in cycle.events.iter().zip(cycle.event_statuses) {
                 runtime.broadcast((event.clone(), event_status));
             }
 
             runtime.track(subscription);
 
-            if let Some(status_code) = application.borrow().should_exit() {
+            if let Some(status_code) = application.should_exit() {
                 break status_code;
             }
 
             ui_message = loop {
-                match receiver.try_next() {
-                    Ok(Some(m)) => break m,
-                    Ok(None) => {
+                match receiver.try_recv() {
+                    Ok(m) => break m,
+                    Err(mpsc::TryRecvError::Closed) => {
                         log::error!(
                             target: LOG_TARGET,
                             "{:?} - Channel closed",
@@ -266,8 +240,8 @@ pub trait Application {
                         std::thread::sleep(poll_rate);
                         continue;
                     }
-                    Err(_) => {
-                        if !state_updated {
+                    Err(mpsc::TryRecvError::Empty) => {
+                        if !cycle.state_updated {
                             std::thread::sleep(poll_rate);
                         } else {
                             break UiMessage::RenderRequest;
@@ -277,27 +251,197 @@ pub trait Application {
             };
         };
 
-        renderer.end_screen(&mut stdout);
+        ui_runtime.renderer.end_screen(&mut stdout);
 
         std::process::exit(exit_status_code.into());
     }
 }
 
+/// The user interface of an [`Application`] between its cycles, shared by
+/// [`Application::run`] and the [`Headless`](crate::Headless) runtime.
+pub(crate) struct UiRuntime {
+    pub(crate) settings: Settings,
+    pub(crate) renderer: TuiRenderer,
+    clipboard: Clipboard,
+    cache: Option<Cache>,
+}
+
+/// What a cycle of the user interface produced.
+pub(crate) struct Cycle<M> {
+    pub(crate) commands: Vec<Command<M>>,
+    pub(crate) events: Vec<Event>,
+    pub(crate) event_statuses: Vec<iced_native::event::Status>,
+    /// Whether the user interface must be drawn again.
+    pub(crate) state_updated: bool,
+}
+
+impl UiRuntime {
+    pub(crate) fn new(settings: Settings) -> Self {
+        let mut renderer = TuiRenderer::default();
+        renderer.graphics_protocol = settings.graphics_protocol;
+
+        Self {
+            clipboard: Clipboard::new(settings.clipboard),
+            settings,
+            renderer,
+            cache: Some(Cache::default()),
+        }
+    }
+
+    /// Builds the view of the application, draws it when it's a render request, and
+    /// updates the application with the events or the message.
+    pub(crate) fn cycle<A>(
+        &mut self,
+        application: &mut A,
+        size: Size,
+        cursor_position: Point,
+        ui_message: UiMessage<A::Message>,
+        on_draw: impl FnOnce(&TuiRenderer, Primitive),
+    ) -> Cycle<A::Message>
+    where
+        A: Application,
+    {
+        // TODO: Review logic about immeditate render when state is updated
+        let mut state_updated = false;
+        let renderer = &mut self.renderer;
+        let clipboard = &mut self.clipboard;
+
+        // render and return messages
+        let view_result = application.view();
+
+        let mut ui = UserInterface::build(view_result, size, self.cache.take().unwrap(), renderer);
+
+        if ui_message.is_render_request() {
+            renderer.begin_frame();
+            let primitive = ui.draw(renderer, cursor_position);
+            on_draw(renderer, primitive);
+
+            // a widget grabbed the focus, draw again with the focus moved to it
+            if renderer.end_frame() {
+                state_updated = true;
+            }
+        }
+
+        let mut messages: Vec<A::Message> = vec![];
+        let mut events: Vec<Event> = vec![];
+        let mut event_statuses: Vec<iced_native::event::Status> = vec![];
+        let mut ui_updated = false;
+
+        match ui_message {
+            UiMessage::AppMessage(message) => messages.push(message),
+            UiMessage::IcedEvents(iced_events) => {
+                let mut clicks = vec![];
+                events = iced_events;
+
+                if self.settings.focus_traversal {
+                    let (remaining_events, focus_clicks) =
+                        renderer.focus_chain.split_events(events, cursor_position);
+                    events = remaining_events;
+                    clicks = focus_clicks;
+                }
+
+                for event in &events {
+                    if let Event::Mouse(mouse::Event::ButtonPressed(button)) = event {
+                        let now = renderer.now();
+                        renderer.set_last_click(Click::at(
+                            now,
+                            cursor_position,
+                            *button,
+                            renderer.last_click(),
+                            self.settings.double_click_interval,
+                        ));
+                    }
+                }
+
+                if !events.is_empty() {
+                    event_statuses =
+                        ui.update(&events, cursor_position, renderer, clipboard, &mut messages);
+
+                    for status in &event_statuses {
+                        if status == &iced_native::event::Status::Captured {
+                            ui_updated = true;
+                            break;
+                        }
+                    }
+                }
+
+                // the focused widgets are updated by clicking them
+                for click in clicks {
+                    ui.update(
+                        &[
+                            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+                            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)),
+                        ],
+                        click,
+                        renderer,
+                        clipboard,
+                        &mut messages,
+                    );
+                    ui_updated = true;
+                }
+            }
+            _ => (),
+        };
+
+        self.cache = Some(ui.into_cache());
+
+        // the copies are written to the terminal with the next frame
+        let clipboard_output = clipboard.take_pending_output();
+        if !clipboard_output.is_empty() {
+            renderer.queue_output(&clipboard_output);
+            state_updated = true;
+        }
+
+        // update state
+        let mut commands: Vec<Command<A::Message>> = vec![];
+
+        for message in messages {
+            commands.push(application.update(message));
+            state_updated = true;
+        }
+
+        if ui_updated {
+            state_updated = true;
+        }
+
+        Cycle {
+            commands,
+            events,
+            event_statuses,
+            state_updated,
+        }
+    }
+}
+
 fn map_keycode_event(
     term_keycode: event::KeyCode,
     term_keymod: event::KeyModifiers,
 ) -> Vec<keyboard::Event> {
     let iced_keycode = term_keycode_to_iced(term_keycode);
 
-    let modifiers = term_keymod_to_iced(term_keymod);
+    let mut modifiers = term_keymod_to_iced(term_keymod);
+
+    // terminals report shift+tab as a distinct key instead of a modifier
+    if term_keycode == event::KeyCode::BackTab {
+        modifiers.shift = true;
+    }
+
+    let mut events: Vec<keyboard::Event> = vec![];
+
+    // terminals only report the modifiers along with the keys, so they are pressed just
+    // for the key (widgets only read them from these events, e.g. for Ctrl+C or Shift+Left)
+    let has_modifiers = modifiers != keyboard::Modifiers::default();
+
+    if has_modifiers {
+        events.push(keyboard::Event::ModifiersChanged(modifiers));
+    }
 
-    let mut events: Vec<keyboard::Event> = match iced_keycode {
-        Some(key_code) => vec![keyboard::Event::KeyPressed {
+    if let Some(key_code) = iced_keycode {
+        events.push(keyboard::Event::KeyPressed {
             key_code,
             modifiers,
-        }],
-        None => vec![],
-    };
+        });
+    }
 
     if !modifiers.control {
         if let event::KeyCode::Char(c) = term_keycode {
@@ -308,13 +452,29 @@ fn map_keycode_event(
     if let Some(keycode) = iced_keycode {
         events.push(keyboard::Event::KeyReleased {
             key_code: keycode,
-            modifiers: term_keymod_to_iced(term_keymod),
+            modifiers,
         });
     }
 
+    if has_modifiers {
+        events.push(keyboard::Event::ModifiersChanged(
+            keyboard::Modifiers::default(),
+        ));
+    }
+
     events
 }
 
+/// Maps a bracketed paste to the characters it contains, the line breaks being received
+/// as `'\n'`. Single-line inputs ignore them, like the other control characters.
+fn map_paste_event(text: &str) -> Vec<keyboard::Event> {
+    text.replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .chars()
+        .map(keyboard::Event::CharacterReceived)
+        .collect()
+}
+
 fn term_keycode_to_iced(term_keycode: event::KeyCode) -> Option<keyboard::KeyCode> {
     match term_keycode {
         event::KeyCode::Backspace => Some(keyboard::KeyCode::Backspace),
@@ -344,11 +504,31 @@ fn term_keycode_to_iced(term_keycode: event::KeyCode) -> Option<keyboard::KeyCod
             10 => Some(keyboard::KeyCode::F10),
             11 => Some(keyboard::KeyCode::F11),
             12 => Some(keyboard::KeyCode::F12),
+            13 => Some(keyboard::KeyCode::F13),
+            14 => Some(keyboard::KeyCode::F14),
+            15 => Some(keyboard::KeyCode::F15),
+            16 => Some(keyboard::KeyCode::F16),
+            17 => Some(keyboard::KeyCode::F17),
+            18 => Some(keyboard::KeyCode::F18),
+            19 => Some(keyboard::KeyCode::F19),
+            20 => Some(keyboard::KeyCode::F20),
+            21 => Some(keyboard::KeyCode::F21),
+            22 => Some(keyboard::KeyCode::F22),
+            23 => Some(keyboard::KeyCode::F23),
+            24 => Some(keyboard::KeyCode::F24),
             _ => None,
         },
         event::KeyCode::Char(c) => keycode_from_char(c),
         event::KeyCode::Null => None,
         event::KeyCode::Esc => Some(keyboard::KeyCode::Escape),
+        event::KeyCode::CapsLock => Some(keyboard::KeyCode::Capital),
+        event::KeyCode::ScrollLock => Some(keyboard::KeyCode::Scroll),
+        event::KeyCode::NumLock => Some(keyboard::KeyCode::Numlock),
+        event::KeyCode::PrintScreen => Some(keyboard::KeyCode::Snapshot),
+        event::KeyCode::Pause => Some(keyboard::KeyCode::Pause),
+        event::KeyCode::Menu => Some(keyboard::KeyCode::Apps),
+        event::KeyCode::KeypadBegin => None,
+        event::KeyCode::Media(_) | event::KeyCode::Modifier(_) => None,
     }
 }
 
@@ -385,9 +565,38 @@ fn keycode_from_char(c: char) -> Option<keyboard::KeyCode> {
         Some('T') => Some(keyboard::KeyCode::T),
         Some('U') => Some(keyboard::KeyCode::U),
         Some('V') => Some(keyboard::KeyCode::V),
+        Some('W') => Some(keyboard::KeyCode::W),
         Some('X') => Some(keyboard::KeyCode::X),
         Some('Y') => Some(keyboard::KeyCode::Y),
         Some('Z') => Some(keyboard::KeyCode::Z),
+        Some('0') => Some(keyboard::KeyCode::Key0),
+        Some('1') => Some(keyboard::KeyCode::Key1),
+        Some('2') => Some(keyboard::KeyCode::Key2),
+        Some('3') => Some(keyboard::KeyCode::Key3),
+        Some('4') => Some(keyboard::KeyCode::Key4),
+        Some('5') => Some(keyboard::KeyCode::Key5),
+        Some('6') => Some(keyboard::KeyCode::Key6),
+        Some('7') => Some(keyboard::KeyCode::Key7),
+        Some('8') => Some(keyboard::KeyCode::Key8),
+        Some('9') => Some(keyboard::KeyCode::Key9),
+        Some(' ') => Some(keyboard::KeyCode::Space),
+        Some('-') => Some(keyboard::KeyCode::Minus),
+        Some('=') => Some(keyboard::KeyCode::Equals),
+        Some('+') => Some(keyboard::KeyCode::Plus),
+        Some('*') => Some(keyboard::KeyCode::Asterisk),
+        Some('[') => Some(keyboard::KeyCode::LBracket),
+        Some(']') => Some(keyboard::KeyCode::RBracket),
+        Some(';') => Some(keyboard::KeyCode::Semicolon),
+        Some(':') => Some(keyboard::KeyCode::Colon),
+        Some('\'') => Some(keyboard::KeyCode::Apostrophe),
+        Some('`') => Some(keyboard::KeyCode::Grave),
+        Some('\\') => Some(keyboard::KeyCode::Backslash),
+        Some(',') => Some(keyboard::KeyCode::Comma),
+        Some('.') => Some(keyboard::KeyCode::Period),
+        Some('/') => Some(keyboard::KeyCode::Slash),
+        Some('@') => Some(keyboard::KeyCode::At),
+        Some('^') => Some(keyboard::KeyCode::Caret),
+        Some('_') => Some(keyboard::KeyCode::Underline),
         _ => None,
     }
 }
@@ -400,10 +609,11 @@ fn map_mouse_event(mouse_event: event::MouseEvent) -> Vec<mouse::Event> {
         event::MouseEventKind::Up(button) => {
             vec![mouse::Event::ButtonReleased(map_mouse_button(button))]
         }
-        event::MouseEventKind::Drag(_) => vec![],
-        event::MouseEventKind::Moved => vec![mouse::Event::CursorMoved {
-            position: Point::new(mouse_event.column as f32, mouse_event.row as f32),
-        }],
+        event::MouseEventKind::Drag(_) | event::MouseEventKind::Moved => {
+            vec![mouse::Event::CursorMoved {
+                position: Point::new(mouse_event.column as f32, mouse_event.row as f32),
+            }]
+        }
         event::MouseEventKind::ScrollDown => vec![mouse::Event::WheelScrolled {
             delta: mouse::ScrollDelta::Lines { x: 0_f32, y: 1_f32 },
         }],
@@ -425,7 +635,7 @@ fn map_mouse_button(button: event::MouseButton) -> mouse::Button {
 }
 
 #[derive(Debug, Clone)]
-enum UiMessage<M> {
+pub(crate) enum UiMessage<M> {
     RenderRequest,
     AppMessage(M),
     IcedEvents(Vec<Event>),
@@ -437,48 +647,321 @@ impl<M> UiMessage<M> {
     }
 }
 
-#[derive(Clone)]
-struct AppMessageMapperSink<M> {
-    sender: mpsc::UnboundedSender<UiMessage<M>>,
-}
+#[cfg(test)]
+mod tests {
+    use super::{
+        event, keyboard, map_keycode_event, map_mouse_event, map_paste_event, mouse, Point,
+    };
+    use event::KeyModifiers;
+    use keyboard::KeyCode;
+
+    fn modifiers(shift: bool, control: bool, alt: bool) -> keyboard::Modifiers {
+        keyboard::Modifiers {
+            shift,
+            control,
+            alt,
+            logo: false,
+        }
+    }
+
+    fn key_events(
+        key_code: KeyCode,
+        modifiers: keyboard::Modifiers,
+        character: Option<char>,
+    ) -> Vec<keyboard::Event> {
+        let has_modifiers = modifiers != keyboard::Modifiers::default();
+        let mut events = vec![];
 
-impl<M> AppMessageMapperSink<M> {
-    fn from_sender(sender: mpsc::UnboundedSender<UiMessage<M>>) -> Self {
-        Self { sender }
+        if has_modifiers {
+            events.push(keyboard::Event::ModifiersChanged(modifiers));
+        }
+
+        events.push(keyboard::Event::KeyPressed {
+            key_code,
+            modifiers,
+        });
+
+        if let Some(c) = character {
+            events.push(keyboard::Event::CharacterReceived(c));
+        }
+
+        events.push(keyboard::Event::KeyReleased {
+            key_code,
+            modifiers,
+        });
+
+        if has_modifiers {
+            events.push(keyboard::Event::ModifiersChanged(
+                keyboard::Modifiers::default(),
+            ));
+        }
+
+        events
     }
-}
 
-impl<M> Sink<M> for AppMessageMapperSink<M> {
-    type Error = mpsc::SendError;
+    #[test]
+    fn it_map_special_keys() {
+        let cases = [
+            (event::KeyCode::Backspace, KeyCode::Backspace),
+            (event::KeyCode::Enter, KeyCode::Enter),
+            (event::KeyCode::Left, KeyCode::Left),
+            (event::KeyCode::Right, KeyCode::Right),
+            (event::KeyCode::Up, KeyCode::Up),
+            (event::KeyCode::Down, KeyCode::Down),
+            (event::KeyCode::Home, KeyCode::Home),
+            (event::KeyCode::End, KeyCode::End),
+            (event::KeyCode::PageUp, KeyCode::PageUp),
+            (event::KeyCode::PageDown, KeyCode::PageDown),
+            (event::KeyCode::Tab, KeyCode::Tab),
+            (event::KeyCode::Delete, KeyCode::Delete),
+            (event::KeyCode::Insert, KeyCode::Insert),
+            (event::KeyCode::Esc, KeyCode::Escape),
+        ];
+
+        for (term_keycode, key_code) in cases {
+            assert_eq!(
+                map_keycode_event(term_keycode, KeyModifiers::NONE),
+                key_events(key_code, modifiers(false, false, false), None),
+                "{:?}",
+                term_keycode
+            );
+        }
 
-    fn poll_ready(
-        self: std::pin::Pin<&mut Self>,
-        ctx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<std::result::Result<(), <Self as futures::Sink<M>>::Error>> {
-        self.sender.poll_ready(ctx)
+        assert_eq!(
+            map_keycode_event(event::KeyCode::Null, KeyModifiers::NONE),
+            vec![]
+        );
     }
 
-    fn start_send(
-        self: std::pin::Pin<&mut Self>,
-        message: M,
-    ) -> std::result::Result<(), <Self as futures::Sink<M>>::Error> {
-        self.get_mut()
-            .sender
-            .start_send(UiMessage::AppMessage(message))
+    #[test]
+    fn it_map_backtab_as_shift_tab() {
+        assert_eq!(
+            map_keycode_event(event::KeyCode::BackTab, KeyModifiers::NONE),
+            key_events(KeyCode::Tab, modifiers(true, false, false), None)
+        );
+        assert_eq!(
+            map_keycode_event(event::KeyCode::BackTab, KeyModifiers::SHIFT),
+            key_events(KeyCode::Tab, modifiers(true, false, false), None)
+        );
     }
 
-    fn poll_flush(
-        self: std::pin::Pin<&mut Self>,
-        ctx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<std::result::Result<(), <Self as futures::Sink<M>>::Error>> {
-        std::pin::Pin::new(&mut self.get_mut().sender).poll_flush(ctx)
+    #[test]
+    fn it_map_function_keys() {
+        let function_keys = [
+            KeyCode::F1,
+            KeyCode::F2,
+            KeyCode::F3,
+            KeyCode::F4,
+            KeyCode::F5,
+            KeyCode::F6,
+            KeyCode::F7,
+            KeyCode::F8,
+            KeyCode::F9,
+            KeyCode::F10,
+            KeyCode::F11,
+            KeyCode::F12,
+            KeyCode::F13,
+            KeyCode::F14,
+            KeyCode::F15,
+            KeyCode::F16,
+            KeyCode::F17,
+            KeyCode::F18,
+            KeyCode::F19,
+            KeyCode::F20,
+            KeyCode::F21,
+            KeyCode::F22,
+            KeyCode::F23,
+            KeyCode::F24,
+        ];
+
+        for (index, key_code) in function_keys.iter().enumerate() {
+            assert_eq!(
+                map_keycode_event(event::KeyCode::F(index as u8 + 1), KeyModifiers::NONE),
+                key_events(*key_code, modifiers(false, false, false), None)
+            );
+        }
+
+        assert_eq!(
+            map_keycode_event(event::KeyCode::F(0), KeyModifiers::NONE),
+            vec![]
+        );
+        assert_eq!(
+            map_keycode_event(event::KeyCode::F(25), KeyModifiers::NONE),
+            vec![]
+        );
     }
 
-    fn poll_close(
-        self: std::pin::Pin<&mut Self>,
-        ctx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<std::result::Result<(), <Self as iced_futures::futures::Sink<M>>::Error>>
-    {
-        std::pin::Pin::new(&mut self.get_mut().sender).poll_close(ctx)
+    #[test]
+    fn it_map_characters() {
+        let letters = [
+            KeyCode::A,
+            KeyCode::B,
+            KeyCode::C,
+            KeyCode::D,
+            KeyCode::E,
+            KeyCode::F,
+            KeyCode::G,
+            KeyCode::H,
+            KeyCode::I,
+            KeyCode::J,
+            KeyCode::K,
+            KeyCode::L,
+            KeyCode::M,
+            KeyCode::N,
+            KeyCode::O,
+            KeyCode::P,
+            KeyCode::Q,
+            KeyCode::R,
+            KeyCode::S,
+            KeyCode::T,
+            KeyCode::U,
+            KeyCode::V,
+            KeyCode::W,
+            KeyCode::X,
+            KeyCode::Y,
+            KeyCode::Z,
+        ];
+        let digits = [
+            KeyCode::Key0,
+            KeyCode::Key1,
+            KeyCode::Key2,
+            KeyCode::Key3,
+            KeyCode::Key4,
+            KeyCode::Key5,
+            KeyCode::Key6,
+            KeyCode::Key7,
+            KeyCode::Key8,
+            KeyCode::Key9,
+        ];
+        let punctuation = [
+            (' ', KeyCode::Space),
+            ('-', KeyCode::Minus),
+            ('=', KeyCode::Equals),
+            ('+', KeyCode::Plus),
+            ('*', KeyCode::Asterisk),
+            ('[', KeyCode::LBracket),
+            (']', KeyCode::RBracket),
+            (';', KeyCode::Semicolon),
+            (':', KeyCode::Colon),
+            ('\'', KeyCode::Apostrophe),
+            ('`', KeyCode::Grave),
+            ('\\', KeyCode::Backslash),
+            (',', KeyCode::Comma),
+            ('.', KeyCode::Period),
+            ('/', KeyCode::Slash),
+            ('@', KeyCode::At),
+            ('^', KeyCode::Caret),
+            ('_', KeyCode::Underline),
+        ];
+
+        let mut cases: Vec<(char, KeyCode)> = vec![];
+        cases.extend(('a'..='z').zip(letters.iter().copied()));
+        cases.extend(('A'..='Z').zip(letters.iter().copied()));
+        cases.extend(('0'..='9').zip(digits.iter().copied()));
+        cases.extend(punctuation.iter().copied());
+
+        for (c, key_code) in cases {
+            assert_eq!(
+                map_keycode_event(event::KeyCode::Char(c), KeyModifiers::NONE),
+                key_events(key_code, modifiers(false, false, false), Some(c)),
+                "{:?}",
+                c
+            );
+        }
+    }
+
+    #[test]
+    fn it_map_unknown_characters_as_text_only() {
+        for c in ['!', '?', 'é', 'ç', '€'] {
+            assert_eq!(
+                map_keycode_event(event::KeyCode::Char(c), KeyModifiers::NONE),
+                vec![keyboard::Event::CharacterReceived(c)]
+            );
+        }
+    }
+
+    #[test]
+    fn it_map_modifiers() {
+        assert_eq!(
+            map_keycode_event(event::KeyCode::Char('w'), KeyModifiers::CONTROL),
+            key_events(KeyCode::W, modifiers(false, true, false), None)
+        );
+        assert_eq!(
+            map_keycode_event(event::KeyCode::Char('1'), KeyModifiers::CONTROL),
+            key_events(KeyCode::Key1, modifiers(false, true, false), None)
+        );
+        assert_eq!(
+            map_keycode_event(event::KeyCode::Char('x'), KeyModifiers::ALT),
+            key_events(KeyCode::X, modifiers(false, false, true), Some('x'))
+        );
+        assert_eq!(
+            map_keycode_event(event::KeyCode::Char('S'), KeyModifiers::SHIFT),
+            key_events(KeyCode::S, modifiers(true, false, false), Some('S'))
+        );
+        assert_eq!(
+            map_keycode_event(
+                event::KeyCode::Left,
+                KeyModifiers::SHIFT | KeyModifiers::CONTROL | KeyModifiers::ALT
+            ),
+            key_events(KeyCode::Left, modifiers(true, true, true), None)
+        );
+    }
+
+    #[test]
+    fn it_surround_modified_keys_with_modifiers_changed() {
+        let modifiers = modifiers(false, true, false);
+
+        assert_eq!(
+            map_keycode_event(event::KeyCode::Char('c'), KeyModifiers::CONTROL),
+            vec![
+                keyboard::Event::ModifiersChanged(modifiers),
+                keyboard::Event::KeyPressed {
+                    key_code: KeyCode::C,
+                    modifiers,
+                },
+                keyboard::Event::KeyReleased {
+                    key_code: KeyCode::C,
+                    modifiers,
+                },
+                keyboard::Event::ModifiersChanged(keyboard::Modifiers::default()),
+            ]
+        );
+
+        // keys without modifiers don't change them
+        assert_eq!(
+            map_keycode_event(event::KeyCode::Char('c'), KeyModifiers::NONE)[0],
+            keyboard::Event::KeyPressed {
+                key_code: KeyCode::C,
+                modifiers: keyboard::Modifiers::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn it_map_drag_to_cursor_moved() {
+        let mouse_event = event::MouseEvent {
+            kind: event::MouseEventKind::Drag(event::MouseButton::Left),
+            column: 7,
+            row: 3,
+            modifiers: event::KeyModifiers::NONE,
+        };
+
+        assert_eq!(
+            map_mouse_event(mouse_event),
+            vec![mouse::Event::CursorMoved {
+                position: Point::new(7., 3.),
+            }]
+        );
+    }
+
+    #[test]
+    fn it_map_paste_to_characters() {
+        let events = map_paste_event("https://example.com/\r\n?q=1\rend\n");
+        let expected: Vec<keyboard::Event> = "https://example.com/\n?q=1\nend\n"
+            .chars()
+            .map(keyboard::Event::CharacterReceived)
+            .collect();
+
+        assert_eq!(events, expected);
     }
 }
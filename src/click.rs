@@ -0,0 +1,158 @@
+use iced_native::{mouse, Point};
+use std::time::{Duration, Instant};
+
+/// The kind of a mouse [`Click`], based on the number of consecutive clicks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClickKind {
+    Single,
+    Double,
+    Triple,
+}
+
+impl ClickKind {
+    fn next(&self) -> Self {
+        match self {
+            Self::Single => Self::Double,
+            Self::Double => Self::Triple,
+            Self::Triple => Self::Single,
+        }
+    }
+}
+
+/// A mouse click, tracked by the runtime.
+///
+/// Consecutive clicks of the same button, on the same cell and within the double click
+/// interval of the [`Settings`](crate::Settings), are detected as double and triple clicks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Click {
+    kind: ClickKind,
+    position: Point,
+    button: mouse::Button,
+    time: Instant,
+}
+
+impl Click {
+    pub fn new(
+        position: Point,
+        button: mouse::Button,
+        previous: Option<Click>,
+        interval: Duration,
+    ) -> Self {
+        Self::at(Instant::now(), position, button, previous, interval)
+    }
+
+    pub(crate) fn at(
+        time: Instant,
+        position: Point,
+        button: mouse::Button,
+        previous: Option<Click>,
+        interval: Duration,
+    ) -> Self {
+        let kind = match previous {
+            Some(previous) if previous.is_followed_by(time, position, button, interval) => {
+                previous.kind.next()
+            }
+            _ => ClickKind::Single,
+        };
+
+        Self {
+            kind,
+            position,
+            button,
+            time,
+        }
+    }
+
+    pub fn kind(&self) -> ClickKind {
+        self.kind
+    }
+
+    pub fn position(&self) -> Point {
+        self.position
+    }
+
+    pub fn button(&self) -> mouse::Button {
+        self.button
+    }
+
+    fn is_followed_by(
+        &self,
+        time: Instant,
+        position: Point,
+        button: mouse::Button,
+        interval: Duration,
+    ) -> bool {
+        self.button == button
+            && self.position == position
+            && time
+                .checked_duration_since(self.time)
+                .map(|elapsed| elapsed <= interval)
+                .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Click, ClickKind};
+    use iced_native::{mouse, Point};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn it_detect_consecutive_clicks() {
+        let interval = Duration::from_millis(300);
+        let start = Instant::now();
+        let position = Point::new(3., 4.);
+        let click_at = |millis: u64, previous: Option<Click>| {
+            Click::at(
+                start + Duration::from_millis(millis),
+                position,
+                mouse::Button::Left,
+                previous,
+                interval,
+            )
+        };
+
+        let first = click_at(0, None);
+        let second = click_at(200, Some(first));
+        let third = click_at(400, Some(second));
+        let fourth = click_at(600, Some(third));
+        let late = click_at(1000, Some(fourth));
+
+        assert_eq!(first.kind(), ClickKind::Single);
+        assert_eq!(second.kind(), ClickKind::Double);
+        assert_eq!(third.kind(), ClickKind::Triple);
+        assert_eq!(fourth.kind(), ClickKind::Single);
+        assert_eq!(late.kind(), ClickKind::Single);
+    }
+
+    #[test]
+    fn it_reset_on_other_position_or_button() {
+        let interval = Duration::from_millis(300);
+        let time = Instant::now();
+        let first = Click::at(
+            time,
+            Point::new(3., 4.),
+            mouse::Button::Left,
+            None,
+            interval,
+        );
+
+        let moved = Click::at(
+            time,
+            Point::new(4., 4.),
+            mouse::Button::Left,
+            Some(first),
+            interval,
+        );
+        let other_button = Click::at(
+            time,
+            Point::new(3., 4.),
+            mouse::Button::Right,
+            Some(first),
+            interval,
+        );
+
+        assert_eq!(moved.kind(), ClickKind::Single);
+        assert_eq!(other_button.kind(), ClickKind::Single);
+    }
+}
@@ -0,0 +1,88 @@
+/// The backend of the [`Clipboard`] used by the runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClipboardBackend {
+    /// Copies to the clipboard of the terminal with OSC 52 escape sequences, which also
+    /// works over SSH.
+    ///
+    /// Terminals usually don't let applications read their clipboard, so pastes from other
+    /// applications come from bracketed paste, and copied text is kept in memory to be
+    /// pasted back.
+    #[default]
+    Osc52,
+    /// Keeps the copied text in a buffer of the process.
+    Memory,
+}
+
+/// A clipboard for the widgets, using a [`ClipboardBackend`].
+#[derive(Debug, Clone, Default)]
+pub struct Clipboard {
+    backend: ClipboardBackend,
+    contents: Option<String>,
+    /// The escape sequences waiting to be written to the terminal with the next frame.
+    pending_output: String,
+}
+
+impl Clipboard {
+    pub fn new(backend: ClipboardBackend) -> Self {
+        Self {
+            backend,
+            contents: None,
+            pending_output: String::new(),
+        }
+    }
+
+    pub fn backend(&self) -> ClipboardBackend {
+        self.backend
+    }
+
+    /// Takes the escape sequences to write to the terminal.
+    pub(crate) fn take_pending_output(&mut self) -> String {
+        std::mem::take(&mut self.pending_output)
+    }
+}
+
+impl iced_native::Clipboard for Clipboard {
+    fn read(&self) -> Option<String> {
+        self.contents.clone()
+    }
+
+    fn write(&mut self, contents: String) {
+        // the sequence goes through the renderer, so it isn't mixed with a frame being drawn
+        if self.backend == ClipboardBackend::Osc52 {
+            self.pending_output.push_str(&osc52_sequence(&contents));
+        }
+
+        self.contents = Some(contents);
+    }
+}
+
+/// Returns the escape sequence setting the clipboard of the terminal to `contents`.
+fn osc52_sequence(contents: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64::encode(contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{osc52_sequence, Clipboard, ClipboardBackend};
+    use iced_native::Clipboard as _;
+
+    #[test]
+    fn it_keep_copied_text_in_memory() {
+        let mut clipboard = Clipboard::new(ClipboardBackend::Memory);
+        assert_eq!(clipboard.read(), None);
+
+        clipboard.write("hello".to_string());
+        assert_eq!(clipboard.read(), Some("hello".to_string()));
+    }
+
+    #[test]
+    fn it_encode_osc52_sequence() {
+        assert_eq!(osc52_sequence("hello"), "\x1b]52;c;aGVsbG8=\x07");
+
+        let mut clipboard = Clipboard::new(ClipboardBackend::Osc52);
+        clipboard.write("hello".to_string());
+        assert_eq!(clipboard.take_pending_output(), osc52_sequence("hello"));
+        assert_eq!(clipboard.take_pending_output(), "");
+        assert_eq!(clipboard.read(), Some("hello".to_string()));
+    }
+}
@@ -0,0 +1,309 @@
+use crate::application::{UiMessage, UiRuntime};
+use crate::renderer::virtual_buffer::VirtualBuffer;
+use crate::{Application, ClipboardBackend};
+use iced_futures::futures::executor::block_on;
+use iced_native::{mouse, window, Event, Executor, Point, Size};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// The maximum number of cycles run for a step, in case the application never settles.
+const MAX_CYCLES: usize = 1000;
+
+/// Runs an [`Application`] without terminal, to test it in a `cargo test`.
+///
+/// The application is drawn into a [`VirtualBuffer`] of a fixed size, and updated with
+/// scripted events. The commands are awaited before the next step, while the subscriptions
+/// are not run. The clipboard is kept in memory, and the clock only moves with
+/// [`Headless::advance`].
+///
+/// ```ignore
+/// let mut headless = Headless::<MyApp>::new(20, 5);
+/// headless.send_events(vec![Event::Mouse(mouse::Event::CursorMoved {
+///     position: Point::new(2., 1.),
+/// })]);
+/// assert_eq!(headless.vbuffer().rows[1][2].content, Some('x'));
+/// ```
+pub struct Headless<A: Application> {
+    application: A,
+    executor: A::Executor,
+    ui_runtime: UiRuntime,
+    size: Size,
+    cursor_position: Point,
+    vbuffer: VirtualBuffer,
+}
+
+impl<A: Application> Headless<A> {
+    /// Creates the application with the given size, and draws it.
+    pub fn new(width: u16, height: u16) -> Self {
+        let executor = A::Executor::new().expect("Create executor");
+        let (application, command) = executor.enter(A::new);
+        let settings = A::settings()
+            .clipboard(ClipboardBackend::Memory)
+            .graphics_protocol(None);
+
+        let mut ui_runtime = UiRuntime::new(settings);
+        ui_runtime.renderer.manual_clock = Some(Duration::ZERO);
+
+        let mut headless = Self {
+            application,
+            executor,
+            ui_runtime,
+            size: Size::new(width as f32, height as f32),
+            cursor_position: Point::new(0., 0.),
+            vbuffer: VirtualBuffer::from_size(width, height),
+        };
+
+        let mut ui_messages: VecDeque<UiMessage<A::Message>> =
+            VecDeque::from(vec![UiMessage::RenderRequest]);
+        ui_messages.extend(headless.perform(command));
+        headless.process(ui_messages);
+        headless
+    }
+
+    /// Sends the events at once, like the runtime does for an input of the terminal, and
+    /// draws the application again once updated.
+    ///
+    /// The mouse events happen at the position of the last `CursorMoved` event, and a
+    /// `Resized` event changes the size of the screen.
+    pub fn send_events<I>(&mut self, events: I) -> &mut Self
+    where
+        I: IntoIterator<Item = Event>,
+    {
+        let events: Vec<Event> = events.into_iter().collect();
+        let mut ui_messages = VecDeque::new();
+
+        for event in &events {
+            match event {
+                Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                    self.cursor_position = *position;
+                }
+                Event::Window(window::Event::Resized { width, height }) => {
+                    self.size = Size::new(*width as f32, *height as f32);
+                    ui_messages.push_back(UiMessage::RenderRequest);
+                }
+                _ => (),
+            }
+        }
+
+        ui_messages.push_front(UiMessage::IcedEvents(events));
+        self.process(ui_messages);
+        self
+    }
+
+    /// Updates the application with a message, and draws it again.
+    pub fn send_message(&mut self, message: A::Message) -> &mut Self {
+        self.process(VecDeque::from(vec![UiMessage::AppMessage(message)]));
+        self
+    }
+
+    /// Advances the clock of the animations and of the double clicks, and draws the
+    /// application again.
+    pub fn advance(&mut self, duration: Duration) -> &mut Self {
+        let renderer = &mut self.ui_runtime.renderer;
+        renderer.manual_clock = Some(renderer.manual_clock.unwrap_or_default() + duration);
+
+        self.process(VecDeque::from(vec![UiMessage::RenderRequest]));
+        self
+    }
+
+    pub fn application(&self) -> &A {
+        &self.application
+    }
+
+    /// Returns the screen drawn last.
+    pub fn vbuffer(&self) -> &VirtualBuffer {
+        &self.vbuffer
+    }
+
+    /// Runs the cycles of the messages, and of the messages of their commands, drawing the
+    /// application again while it's updated.
+    fn process(&mut self, mut ui_messages: VecDeque<UiMessage<A::Message>>) {
+        let mut state_updated = false;
+
+        for _ in 0..MAX_CYCLES {
+            let ui_message = match ui_messages.pop_front() {
+                Some(ui_message) => ui_message,
+                None if state_updated => UiMessage::RenderRequest,
+                None => return,
+            };
+
+            let Self {
+                application,
+                executor,
+                ui_runtime,
+                size,
+                cursor_position,
+                vbuffer,
+            } = self;
+            let size = *size;
+            let cycle = executor.enter(|| {
+                ui_runtime.cycle(
+                    application,
+                    size,
+                    *cursor_position,
+                    ui_message,
+                    |renderer, primitive| {
+                        let width = size.width as u16;
+                        let height = size.height as u16;
+                        *vbuffer = renderer.make_vbuffer(&primitive, width, height);
+                    },
+                )
+            });
+
+            state_updated = cycle.state_updated;
+
+            for command in cycle.commands {
+                ui_messages.extend(self.perform(command));
+            }
+        }
+
+        log::warn!(
+            target: crate::constants::LOG_TARGET,
+            "The application didn't settle after {} cycles",
+            MAX_CYCLES
+        );
+    }
+
+    /// Awaits the futures of a command, returning their messages.
+    fn perform(&self, command: iced_native::Command<A::Message>) -> Vec<UiMessage<A::Message>> {
+        command
+            .futures()
+            .into_iter()
+            .map(|future| UiMessage::AppMessage(self.executor.enter(|| block_on(future))))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Headless;
+    use crate::{Application, ClickKind, Spinner, TuiRenderer};
+    use iced_futures::executor::Tokio;
+    use iced_native::{
+        button, mouse, window, Button, Column, Command, Element, Event, Point, Text,
+    };
+    use std::time::Duration;
+
+    struct Counter {
+        count: u32,
+        increment: button::State,
+    }
+
+    #[derive(Debug, Clone)]
+    enum Message {
+        Increment,
+        Add(u32),
+    }
+
+    impl Application for Counter {
+        type Executor = Tokio;
+        type Message = Message;
+
+        fn new() -> (Self, Command<Message>) {
+            let counter = Self {
+                count: 0,
+                increment: button::State::new(),
+            };
+
+            (counter, Command::perform(async { 10 }, Message::Add))
+        }
+
+        fn update(&mut self, message: Message) -> Command<Message> {
+            match message {
+                Message::Increment => self.count += 1,
+                Message::Add(count) => self.count += count,
+            }
+
+            Command::none()
+        }
+
+        fn view(&mut self) -> Element<'_, Message, TuiRenderer> {
+            Column::new()
+                .push(Text::new(format!("Count: {}", self.count)))
+                .push(
+                    Button::new(&mut self.increment, Text::new("+1")).on_press(Message::Increment),
+                )
+                .into()
+        }
+    }
+
+    fn lines<A: Application>(headless: &Headless<A>) -> Vec<String> {
+        headless
+            .vbuffer()
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.content.unwrap_or(' ')).collect())
+            .collect()
+    }
+
+    #[test]
+    fn it_run_the_application_with_scripted_events() {
+        let mut headless = Headless::<Counter>::new(10, 2);
+        assert_eq!(headless.application().count, 10);
+        assert_eq!(lines(&headless), vec!["Count: 10 ", "+1        "]);
+
+        headless.send_events(vec![
+            Event::Mouse(mouse::Event::CursorMoved {
+                position: Point::new(0., 1.),
+            }),
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)),
+        ]);
+        assert_eq!(lines(&headless), vec!["Count: 11 ", "+1        "]);
+
+        headless
+            .send_message(Message::Add(4))
+            .send_events(vec![Event::Window(window::Event::Resized {
+                width: 12,
+                height: 1,
+            })]);
+        assert_eq!(lines(&headless), vec!["Count: 15   "]);
+    }
+
+    struct Loading;
+
+    impl Application for Loading {
+        type Executor = Tokio;
+        type Message = ();
+
+        fn new() -> (Self, Command<()>) {
+            (Self, Command::none())
+        }
+
+        fn update(&mut self, _message: ()) -> Command<()> {
+            Command::none()
+        }
+
+        fn view(&mut self) -> Element<'_, (), TuiRenderer> {
+            Spinner::new().into()
+        }
+    }
+
+    #[test]
+    fn it_advance_the_clock() {
+        let mut headless = Headless::<Loading>::new(1, 1);
+        assert_eq!(lines(&headless), vec!["⠋"]);
+
+        headless.send_events(vec![]);
+        assert_eq!(lines(&headless), vec!["⠋"]);
+
+        headless.advance(Duration::from_millis(250));
+        assert_eq!(lines(&headless), vec!["⠹"]);
+
+        // the clicks are timed by the same clock
+        let click = || Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left));
+        let last_click = |headless: &Headless<Loading>| {
+            headless.ui_runtime.renderer.last_click().unwrap().kind()
+        };
+
+        headless
+            .send_events(vec![click()])
+            .send_events(vec![click()]);
+        assert_eq!(last_click(&headless), ClickKind::Double);
+
+        headless
+            .advance(Duration::from_secs(1))
+            .send_events(vec![click()]);
+        assert_eq!(last_click(&headless), ClickKind::Single);
+    }
+}
@@ -1,7 +1,19 @@
-#![feature(test)]
+#![cfg_attr(test, feature(test))]
 mod application;
+mod click;
+mod clipboard;
 mod constants;
+mod headless;
 mod renderer;
+mod settings;
+mod snapshot;
+mod widget;
 
 pub use application::*;
+pub use click::*;
+pub use clipboard::*;
+pub use headless::*;
 pub use renderer::*;
+pub use settings::*;
+pub use snapshot::*;
+pub use widget::*;
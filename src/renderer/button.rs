@@ -1,3 +1,4 @@
+use super::focus::{focused_style, FocusKind};
 use super::primitives::{Cell, Primitive};
 use super::tui_renderer::TuiRenderer;
 use super::utils::round_individual_layout;
@@ -9,6 +10,7 @@ use iced_native::Layout;
 pub struct ButtonStyle {
     pub(crate) normal: Style,
     pub(crate) hover: Style,
+    pub(crate) focused: Style,
     pub(crate) pressed: Style,
     pub(crate) disabled: Style,
 }
@@ -28,6 +30,14 @@ impl ButtonStyle {
         self
     }
 
+    /// Sets the style of the button when it has the keyboard focus.
+    ///
+    /// When not set, focused buttons are drawn in bold.
+    pub fn focused(mut self, focused: Style) -> Self {
+        self.focused = focused;
+        self
+    }
+
     pub fn pressed(mut self, pressed: Style) -> Self {
         self.pressed = pressed;
         self
@@ -61,7 +71,17 @@ impl button::Renderer for TuiRenderer {
         let content_primitive =
             content.draw(self, defaults, new_elem_layout, cursor_position, &bounds);
 
-        let selected_style = button_style.normal.try_merge(if is_disabled {
+        let is_focused = !is_disabled && self.register_focusable(FocusKind::Button, bounds, false);
+
+        let base_style = if is_focused {
+            button_style
+                .normal
+                .merge(focused_style(button_style.focused))
+        } else {
+            button_style.normal
+        };
+
+        let selected_style = base_style.try_merge(if is_disabled {
             Some(button_style.disabled)
         } else if is_pressed {
             Some(button_style.pressed)
@@ -0,0 +1,103 @@
+use super::focus::{focused_style, FocusKind};
+use super::primitives::{Cell, Primitive};
+use super::tui_renderer::TuiRenderer;
+use crate::Style;
+use iced_native::checkbox;
+
+#[derive(Debug, Clone, Copy)]
+pub struct CheckboxStyle {
+    pub(crate) normal: Style,
+    pub(crate) hover: Style,
+    pub(crate) focused: Style,
+    pub(crate) checked_char: char,
+    pub(crate) unchecked_char: char,
+}
+
+impl Default for CheckboxStyle {
+    fn default() -> Self {
+        Self {
+            normal: Style::default(),
+            hover: Style::default(),
+            focused: Style::default(),
+            checked_char: '☑',
+            unchecked_char: '☐',
+        }
+    }
+}
+
+impl CheckboxStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn normal(mut self, normal: Style) -> Self {
+        self.normal = normal;
+        self
+    }
+
+    pub fn hover(mut self, hover: Style) -> Self {
+        self.hover = hover;
+        self
+    }
+
+    /// Sets the style of the box when the checkbox has the keyboard focus.
+    ///
+    /// When not set, the box of focused checkboxes is drawn in bold.
+    pub fn focused(mut self, focused: Style) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    pub fn checked_char(mut self, checked_char: char) -> Self {
+        self.checked_char = checked_char;
+        self
+    }
+
+    pub fn unchecked_char(mut self, unchecked_char: char) -> Self {
+        self.unchecked_char = unchecked_char;
+        self
+    }
+}
+
+impl checkbox::Renderer for TuiRenderer {
+    type Style = CheckboxStyle;
+
+    const DEFAULT_SIZE: u16 = 1;
+
+    const DEFAULT_SPACING: u16 = 1;
+
+    fn draw(
+        &mut self,
+        bounds: iced_core::Rectangle,
+        is_checked: bool,
+        is_mouse_over: bool,
+        label: <Self as iced_native::Renderer>::Output,
+        checkbox_style: &<Self as checkbox::Renderer>::Style,
+    ) -> <Self as iced_native::Renderer>::Output {
+        let is_focused = self.register_focusable(FocusKind::Checkbox, bounds, false);
+
+        let mut style = checkbox_style.normal;
+
+        if is_focused {
+            style = style.merge(focused_style(checkbox_style.focused));
+        }
+
+        if is_mouse_over {
+            style = style.merge(checkbox_style.hover);
+        }
+
+        let content = if is_checked {
+            checkbox_style.checked_char
+        } else {
+            checkbox_style.unchecked_char
+        };
+
+        let checkbox = Primitive::Cell(
+            bounds.x.round() as u16,
+            bounds.y.round() as u16,
+            Cell::from_char(content).style(style),
+        );
+
+        Primitive::Group(vec![checkbox, label])
+    }
+}
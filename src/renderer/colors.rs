@@ -16,6 +16,7 @@ pub enum AnsiColor {
     Cyan,
     DarkCyan,
     Grey,
+    DarkGrey,
     White,
 }
 
@@ -37,6 +38,29 @@ impl AnsiColor {
             Self::DarkCyan => 122,
             Self::Grey => 123,
             Self::White => 124,
+            Self::DarkGrey => 125,
+        }
+    }
+
+    /// Approximated RGB value of the color, based on the default xterm palette.
+    pub(crate) fn rgb(&self) -> (u8, u8, u8) {
+        match self {
+            Self::Black => (0, 0, 0),
+            Self::Red => (255, 0, 0),
+            Self::DarkRed => (128, 0, 0),
+            Self::Green => (0, 255, 0),
+            Self::DarkGreen => (0, 128, 0),
+            Self::Yellow => (255, 255, 0),
+            Self::DarkYellow => (128, 128, 0),
+            Self::Blue => (0, 0, 255),
+            Self::DarkBlue => (0, 0, 128),
+            Self::Magenta => (255, 0, 255),
+            Self::DarkMagenta => (128, 0, 128),
+            Self::Cyan => (0, 255, 255),
+            Self::DarkCyan => (0, 128, 128),
+            Self::Grey => (192, 192, 192),
+            Self::DarkGrey => (128, 128, 128),
+            Self::White => (255, 255, 255),
         }
     }
 }
@@ -71,6 +95,7 @@ impl From<Color> for TermColor {
             122 => Self::Ansi(AnsiColor::DarkCyan),
             123 => Self::Ansi(AnsiColor::Grey),
             124 => Self::Ansi(AnsiColor::White),
+            125 => Self::Ansi(AnsiColor::DarkGrey),
             _ => Self::Rgb(
                 to_term_color_channel(color.r),
                 to_term_color_channel(color.g),
@@ -80,6 +105,21 @@ impl From<Color> for TermColor {
     }
 }
 
+impl TermColor {
+    /// Darkens the color by the given amount, keeping its hue, from `0.0` (unchanged) to
+    /// `1.0` (black).
+    pub(crate) fn dim(self, amount: f32) -> Self {
+        let (r, g, b) = match self {
+            Self::Rgb(r, g, b) => (r, g, b),
+            Self::Ansi(ansi_color) => ansi_color.rgb(),
+        };
+        let kept = 1.0 - amount.clamp(0.0, 1.0);
+        let dim_channel = |channel: u8| (channel as f32 * kept).round() as u8;
+
+        Self::Rgb(dim_channel(r), dim_channel(g), dim_channel(b))
+    }
+}
+
 pub(crate) fn get_crossterm_color(color: TermColor) -> crossterm::style::Color {
     match color {
         TermColor::Rgb(r, g, b) => crossterm::style::Color::Rgb { r, g, b },
@@ -97,6 +137,7 @@ pub(crate) fn get_crossterm_color(color: TermColor) -> crossterm::style::Color {
         TermColor::Ansi(AnsiColor::Cyan) => crossterm::style::Color::Cyan,
         TermColor::Ansi(AnsiColor::DarkCyan) => crossterm::style::Color::DarkCyan,
         TermColor::Ansi(AnsiColor::Grey) => crossterm::style::Color::Grey,
+        TermColor::Ansi(AnsiColor::DarkGrey) => crossterm::style::Color::DarkGrey,
         TermColor::Ansi(AnsiColor::White) => crossterm::style::Color::White,
     }
 }
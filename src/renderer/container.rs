@@ -1,11 +1,122 @@
-use super::primitives::{Cell, Primitive};
+use super::primitives::{Border, Cell, Primitive};
 use super::tui_renderer::TuiRenderer;
-use crate::renderer::utils::round_individual_layout;
-use crate::Style;
-use iced_native::{container, Element, Layout, Point, Rectangle};
+use crate::renderer::utils::{
+    round_individual_layout, round_inset_layout, text_cells, text_width, truncate_text,
+};
+use crate::{Shadow, Style};
+use iced_native::{container, Color, Element, HorizontalAlignment, Layout, Point, Rectangle};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerStyle {
+    pub(crate) style: Style,
+    pub(crate) border: Border,
+    pub(crate) border_style: Style,
+    pub(crate) title: Option<String>,
+    pub(crate) title_style: Style,
+    pub(crate) title_alignment: HorizontalAlignment,
+    pub(crate) shadow: Option<Shadow>,
+}
+
+impl Default for ContainerStyle {
+    fn default() -> Self {
+        Self {
+            style: Style::default(),
+            border: Border::default(),
+            border_style: Style::default(),
+            title: None,
+            title_style: Style::default(),
+            title_alignment: HorizontalAlignment::Left,
+            shadow: None,
+        }
+    }
+}
+
+impl From<Style> for ContainerStyle {
+    fn from(style: Style) -> Self {
+        Self {
+            style,
+            ..Self::default()
+        }
+    }
+}
+
+impl ContainerStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn fg<C>(mut self, color: C) -> Self
+    where
+        C: Into<Color>,
+    {
+        self.style = self.style.fg(color);
+        self
+    }
+
+    pub fn bg<C>(mut self, color: C) -> Self
+    where
+        C: Into<Color>,
+    {
+        self.style = self.style.bg(color);
+        self
+    }
+
+    /// Sets the border drawn around the content.
+    ///
+    /// The border is drawn over the padding of the container. A padding wider than the border
+    /// leaves a gap inside it, a narrower one pushes the content inside it.
+    pub fn border(mut self, border: Border) -> Self {
+        self.border = border;
+        self
+    }
+
+    pub fn border_color<C>(mut self, color: C) -> Self
+    where
+        C: Into<Color>,
+    {
+        self.border_style = self.border_style.fg(color);
+        self
+    }
+
+    pub fn border_style(mut self, border_style: Style) -> Self {
+        self.border_style = border_style;
+        self
+    }
+
+    /// Sets a title embedded in the top edge of the border.
+    ///
+    /// The title is only drawn when the container has a [`Border`].
+    pub fn title<T>(mut self, title: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn title_style(mut self, title_style: Style) -> Self {
+        self.title_style = title_style;
+        self
+    }
+
+    pub fn title_alignment(mut self, title_alignment: HorizontalAlignment) -> Self {
+        self.title_alignment = title_alignment;
+        self
+    }
+
+    pub fn shadow(mut self, shadow: Shadow) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+}
 
 impl container::Renderer for TuiRenderer {
-    type Style = Style;
+    type Style = ContainerStyle;
 
     fn draw<Message>(
         &mut self,
@@ -17,24 +128,151 @@ impl container::Renderer for TuiRenderer {
         content: &Element<'_, Message, Self>,
         original_content_layout: Layout<'_>,
     ) -> <Self as iced_native::Renderer>::Output {
-        let (layout_offset, node) =
-            round_individual_layout(container_bounds, original_content_layout, content, self);
+        let (layout_offset, node) = match style.border {
+            Border::None => {
+                round_individual_layout(container_bounds, original_content_layout, content, self)
+            }
+            border => round_inset_layout(
+                container_bounds,
+                border.width(),
+                original_content_layout,
+                content,
+                self,
+            ),
+        };
         let new_elem_layout = Layout::with_offset(layout_offset, &node);
 
         let content_primitive =
             content.draw(self, defaults, new_elem_layout, cursor_position, viewport);
 
+        let x = container_bounds.x.round() as u16;
+        let y = container_bounds.y.round() as u16;
+        let width = container_bounds.width.round() as u16;
+        let height = container_bounds.height.round() as u16;
+
         let rectangle = Primitive::Rectangle(
-            container_bounds.x.round() as u16,
-            container_bounds.y.round() as u16,
-            container_bounds.width.round() as u16,
-            container_bounds.height.round() as u16,
+            x,
+            y,
+            width,
+            height,
             Cell {
-                style: *style,
+                style: style.style,
                 ..Cell::default()
             },
         );
 
-        Primitive::Group(vec![rectangle, content_primitive])
+        let border_style = style.style.merge(style.border_style);
+        let border = Primitive::border(x, y, width, height, style.border, border_style);
+
+        let title = match &style.title {
+            Some(title) if style.border != Border::None => draw_title(
+                title,
+                x,
+                y,
+                width,
+                style.title_alignment,
+                border_style.merge(style.title_style),
+            ),
+            _ => Primitive::Group(vec![]),
+        };
+
+        let shadow = match style.shadow {
+            Some(shadow) => Primitive::shadow(x, y, width, height, shadow),
+            None => Primitive::Group(vec![]),
+        };
+
+        Primitive::Group(vec![rectangle, border, title, content_primitive, shadow])
+    }
+}
+
+fn draw_title(
+    title: &str,
+    x: u16,
+    y: u16,
+    width: u16,
+    alignment: HorizontalAlignment,
+    style: Style,
+) -> Primitive {
+    // keep the corners visible, and one cell of edge next to them
+    let available_width = width.saturating_sub(4);
+
+    if available_width <= 2 {
+        return Primitive::Group(vec![]);
+    }
+
+    let title: String = title.chars().filter(|c| !c.is_control()).collect();
+    let title = format!(" {} ", truncate_text(&title, available_width - 2));
+    let title_width = text_width(&title);
+
+    let start_x = match alignment {
+        HorizontalAlignment::Left => x + 2,
+        HorizontalAlignment::Center => x + (width - title_width) / 2,
+        HorizontalAlignment::Right => x + width - 2 - title_width,
+    };
+
+    Primitive::Group(text_cells(&title, start_x, y, style))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ContainerStyle;
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::{Border, TuiRenderer};
+    use iced_native::{
+        Cache, Container, Element, HorizontalAlignment, Length, Point, Size, Space, UserInterface,
+    };
+
+    fn draw(style: ContainerStyle, width: u16, height: u16) -> Vec<String> {
+        let mut renderer = TuiRenderer::default();
+        let container: Element<'_, (), TuiRenderer> =
+            Container::new(Space::new(Length::Fill, Length::Fill))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .style(style)
+                .into();
+        let mut ui = UserInterface::build(
+            container,
+            Size::new(width as f32, height as f32),
+            Cache::default(),
+            &mut renderer,
+        );
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(width, height);
+        vbuffer.merge_primitive(&primitive);
+
+        vbuffer.to_plain_text().lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn it_draw_border_glyphs() {
+        let expected = [
+            (Border::Light, ["┌──┐", "└──┘"]),
+            (Border::Heavy, ["┏━━┓", "┗━━┛"]),
+            (Border::Double, ["╔══╗", "╚══╝"]),
+            (Border::Rounded, ["╭──╮", "╰──╯"]),
+            (Border::Ascii, ["+--+", "+--+"]),
+        ];
+
+        for (border, lines) in expected {
+            assert_eq!(draw(ContainerStyle::new().border(border), 4, 2), lines);
+        }
+
+        assert_eq!(draw(ContainerStyle::new(), 4, 2), ["", ""]);
+    }
+
+    #[test]
+    fn it_align_title_by_display_width() {
+        let style = ContainerStyle::new().border(Border::Light).title("日本");
+        let title_line =
+            |alignment| draw(style.clone().title_alignment(alignment), 12, 2)[0].clone();
+
+        assert_eq!(title_line(HorizontalAlignment::Left), "┌─ 日本 ───┐");
+        assert_eq!(title_line(HorizontalAlignment::Center), "┌── 日本 ──┐");
+        assert_eq!(title_line(HorizontalAlignment::Right), "┌─── 日本 ─┐");
+
+        // long titles are truncated to the width in cells
+        let style = ContainerStyle::new().border(Border::Light).title("日本語");
+        assert_eq!(draw(style, 10, 2)[0], "┌─ 日… ──┐");
     }
 }
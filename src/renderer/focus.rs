@@ -0,0 +1,451 @@
+use crate::Style;
+use iced_native::{keyboard, mouse, Event, Point, Rectangle};
+
+/// The kind of a widget that can receive the keyboard focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FocusKind {
+    TextInput,
+    TextEditor,
+    Button,
+    Checkbox,
+    PickList,
+    Table,
+    List,
+    Tree,
+}
+
+impl FocusKind {
+    /// Returns whether the widget reads the keyboard by itself, instead of being activated
+    /// by Enter and Space.
+    fn handles_keys(&self) -> bool {
+        matches!(
+            self,
+            Self::TextInput | Self::TextEditor | Self::Table | Self::List | Self::Tree
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Focusable {
+    pub kind: FocusKind,
+    pub bounds: Rectangle,
+}
+
+/// The focusable widgets of the last drawn frame, in drawing order.
+///
+/// Widgets register themselves while they are drawn, and the runtime moves the focus
+/// between them by emulating mouse clicks, since iced widgets only react to the mouse.
+///
+/// The focused widget is identified by its kind and its bounds, so the focus stays on it
+/// when other widgets appear or disappear before it.
+#[derive(Debug, Default)]
+pub(crate) struct FocusChain {
+    focusables: Vec<Focusable>,
+    drawing: Vec<Focusable>,
+    focused: Option<Focusable>,
+    focused_while_drawing: Option<Focusable>,
+    /// Whether the focused widget was registered in the frame being drawn.
+    focused_drawn: bool,
+    /// The range of the focusables the focus can't leave, e.g. the widgets of a modal.
+    trap: Option<(usize, usize)>,
+    trap_while_drawing: Option<(usize, usize)>,
+}
+
+impl FocusChain {
+    pub fn begin_frame(&mut self) {
+        self.drawing.clear();
+        self.focused_while_drawing = None;
+        self.focused_drawn = false;
+        self.trap_while_drawing = None;
+    }
+
+    /// Starts trapping the focus in the widgets drawn until [`end_trap`].
+    ///
+    /// [`end_trap`]: FocusChain::end_trap
+    pub fn begin_trap(&mut self) {
+        self.trap_while_drawing = Some((self.drawing.len(), self.drawing.len()));
+    }
+
+    pub fn end_trap(&mut self) {
+        if let Some((_, end)) = &mut self.trap_while_drawing {
+            *end = self.drawing.len();
+        }
+    }
+
+    /// Registers a focusable widget being drawn, returning whether it holds the focus.
+    ///
+    /// `has_focus` tells that the widget considers itself focused (e.g. a text input that
+    /// was clicked), and takes over the focus of the chain.
+    pub fn register(&mut self, kind: FocusKind, bounds: Rectangle, has_focus: bool) -> bool {
+        let focusable = Focusable { kind, bounds };
+        self.drawing.push(focusable);
+
+        if has_focus && self.focused_while_drawing.is_none() {
+            self.focused_while_drawing = Some(focusable);
+        }
+
+        // widgets with the same kind and bounds can't be told apart, the first one wins
+        let is_focused = !self.focused_drawn && self.focused == Some(focusable);
+        self.focused_drawn |= is_focused;
+        is_focused
+    }
+
+    /// Finishes the frame, returning whether the focus changed while drawing (so the
+    /// frame should be drawn again).
+    pub fn end_frame(&mut self) -> bool {
+        std::mem::swap(&mut self.focusables, &mut self.drawing);
+        let previous_focused = self.focused;
+        self.trap = self.trap_while_drawing;
+
+        if self
+            .focused_while_drawing
+            .is_some_and(|focusable| self.index_of(focusable).is_some())
+        {
+            self.focused = self.focused_while_drawing;
+        }
+
+        if self
+            .focused
+            .is_some_and(|focusable| self.index_of(focusable).is_none())
+        {
+            self.focused = None;
+        }
+
+        self.focused != previous_focused
+    }
+
+    pub fn focused(&self) -> Option<Focusable> {
+        self.focused
+    }
+
+    /// Returns the index of a reachable focusable in the drawing order.
+    fn index_of(&self, focusable: Focusable) -> Option<usize> {
+        let (start, end) = self.reachable();
+
+        self.focusables[start..end]
+            .iter()
+            .position(|other| *other == focusable)
+            .map(|index| start + index)
+    }
+
+    fn focus_index(&mut self, index: Option<usize>) -> Option<Focusable> {
+        self.focused = index.and_then(|index| self.focusables.get(index).copied());
+        self.focused
+    }
+
+    /// Returns the range of the focusables that can get the focus.
+    fn reachable(&self) -> (usize, usize) {
+        let len = self.focusables.len();
+        let (start, end) = self.trap.unwrap_or((0, len));
+        (start.min(len), end.min(len))
+    }
+
+    fn focus_next(&mut self) -> Option<Focusable> {
+        let (start, end) = self.reachable();
+        let len = end - start;
+        let index = match self.focused.and_then(|focusable| self.index_of(focusable)) {
+            _ if len == 0 => None,
+            Some(index) => Some(start + (index - start + 1) % len),
+            None => Some(start),
+        };
+        self.focus_index(index)
+    }
+
+    fn focus_previous(&mut self) -> Option<Focusable> {
+        let (start, end) = self.reachable();
+        let len = end - start;
+        let index = match self.focused.and_then(|focusable| self.index_of(focusable)) {
+            _ if len == 0 => None,
+            Some(index) => Some(start + (index - start + len - 1) % len),
+            None => Some(end - 1),
+        };
+        self.focus_index(index)
+    }
+
+    fn focus_at(&mut self, position: Point) {
+        let (start, end) = self.reachable();
+
+        if let Some(focusable) = self.focusables[start..end]
+            .iter()
+            .find(|focusable| focusable.bounds.contains(position))
+        {
+            self.focused = Some(*focusable);
+        }
+    }
+
+    /// Handles the focus traversal keys (Tab, Shift+Tab) and the activation keys
+    /// (Enter, Space) of the focused widget.
+    ///
+    /// Returns the events that were not used, and the positions that must be clicked to
+    /// apply the focus changes to the widgets.
+    pub fn split_events(
+        &mut self,
+        events: Vec<Event>,
+        cursor_position: Point,
+    ) -> (Vec<Event>, Vec<Point>) {
+        let mut remaining_events = Vec::with_capacity(events.len());
+        let mut clicks = vec![];
+        let mut consumed_key: Option<keyboard::KeyCode> = None;
+
+        for event in events {
+            match event {
+                Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code,
+                    modifiers,
+                }) => match self.key_pressed(key_code, modifiers) {
+                    Some(click) => {
+                        clicks.push(click);
+                        consumed_key = Some(key_code);
+                    }
+                    None => {
+                        consumed_key = None;
+                        remaining_events.push(event);
+                    }
+                },
+                Event::Keyboard(keyboard::Event::CharacterReceived(_))
+                    if consumed_key.is_some() => {}
+                Event::Keyboard(keyboard::Event::KeyReleased { key_code, .. })
+                    if consumed_key == Some(key_code) =>
+                {
+                    consumed_key = None;
+                }
+                Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                    self.focus_at(cursor_position);
+                    remaining_events.push(event);
+                }
+                _ => remaining_events.push(event),
+            }
+        }
+
+        (remaining_events, clicks)
+    }
+
+    fn key_pressed(
+        &mut self,
+        key_code: keyboard::KeyCode,
+        modifiers: keyboard::Modifiers,
+    ) -> Option<Point> {
+        if modifiers.control || modifiers.alt || modifiers.logo {
+            return None;
+        }
+
+        match key_code {
+            keyboard::KeyCode::Tab => {
+                let focusable = if modifiers.shift {
+                    self.focus_previous()?
+                } else {
+                    self.focus_next()?
+                };
+
+                Some(match focusable.kind {
+                    // clicking at the end of the value moves the cursor there
+                    FocusKind::TextInput => Point::new(
+                        focusable.bounds.x + focusable.bounds.width - 1.0,
+                        focusable.bounds.y,
+                    ),
+                    FocusKind::TextEditor => focusable.bounds.position(),
+                    // clicking outside of everything unfocuses the text inputs
+                    _ => Point::new(-1.0, -1.0),
+                })
+            }
+            keyboard::KeyCode::Enter | keyboard::KeyCode::Space => {
+                let focusable = self.focused()?;
+
+                match focusable.kind.handles_keys() {
+                    true => None,
+                    false => Some(focusable.bounds.center()),
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns the style drawn over a focused widget, falling back to bold text.
+pub(crate) fn focused_style(style: Style) -> Style {
+    if style.is_empty() {
+        Style::new().bold()
+    } else {
+        style
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FocusChain, FocusKind};
+    use iced_native::{keyboard, mouse, Event, Point, Rectangle, Size};
+
+    fn make_chain() -> FocusChain {
+        let mut chain = FocusChain::default();
+        chain.begin_frame();
+        chain.register(
+            FocusKind::TextInput,
+            Rectangle::new(Point::new(0., 0.), Size::new(10., 1.)),
+            false,
+        );
+        chain.register(
+            FocusKind::Button,
+            Rectangle::new(Point::new(12., 0.), Size::new(6., 1.)),
+            false,
+        );
+        chain.register(
+            FocusKind::Checkbox,
+            Rectangle::new(Point::new(0., 2.), Size::new(1., 1.)),
+            false,
+        );
+        chain.end_frame();
+        chain
+    }
+
+    fn key_events(key_code: keyboard::KeyCode, shift: bool) -> Vec<Event> {
+        let modifiers = keyboard::Modifiers {
+            shift,
+            ..keyboard::Modifiers::default()
+        };
+
+        vec![
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code,
+                modifiers,
+            }),
+            Event::Keyboard(keyboard::Event::KeyReleased {
+                key_code,
+                modifiers,
+            }),
+        ]
+    }
+
+    #[test]
+    fn it_cycle_focus_with_tab() {
+        let mut chain = make_chain();
+
+        let (events, clicks) =
+            chain.split_events(key_events(keyboard::KeyCode::Tab, false), Point::ORIGIN);
+        assert!(events.is_empty());
+        assert_eq!(clicks, vec![Point::new(9., 0.)]);
+        assert_eq!(chain.focused().unwrap().kind, FocusKind::TextInput);
+
+        let (_, clicks) =
+            chain.split_events(key_events(keyboard::KeyCode::Tab, false), Point::ORIGIN);
+        assert_eq!(clicks, vec![Point::new(-1., -1.)]);
+        assert_eq!(chain.focused().unwrap().kind, FocusKind::Button);
+
+        chain.split_events(key_events(keyboard::KeyCode::Tab, false), Point::ORIGIN);
+        chain.split_events(key_events(keyboard::KeyCode::Tab, false), Point::ORIGIN);
+        assert_eq!(chain.focused().unwrap().kind, FocusKind::TextInput);
+
+        chain.split_events(key_events(keyboard::KeyCode::Tab, true), Point::ORIGIN);
+        assert_eq!(chain.focused().unwrap().kind, FocusKind::Checkbox);
+    }
+
+    #[test]
+    fn it_activate_focused_widget() {
+        let mut chain = make_chain();
+
+        // nothing focused yet
+        let (events, clicks) =
+            chain.split_events(key_events(keyboard::KeyCode::Enter, false), Point::ORIGIN);
+        assert_eq!(events.len(), 2);
+        assert!(clicks.is_empty());
+
+        chain.split_events(key_events(keyboard::KeyCode::Tab, false), Point::ORIGIN);
+
+        // text inputs handle enter and space by themselves
+        let (events, clicks) =
+            chain.split_events(key_events(keyboard::KeyCode::Space, false), Point::ORIGIN);
+        assert_eq!(events.len(), 2);
+        assert!(clicks.is_empty());
+
+        chain.split_events(key_events(keyboard::KeyCode::Tab, false), Point::ORIGIN);
+
+        let mut space_events = key_events(keyboard::KeyCode::Space, false);
+        space_events.insert(1, Event::Keyboard(keyboard::Event::CharacterReceived(' ')));
+        let (events, clicks) = chain.split_events(space_events, Point::ORIGIN);
+        assert!(events.is_empty());
+        assert_eq!(clicks, vec![Point::new(15., 0.5)]);
+    }
+
+    #[test]
+    fn it_follow_focus_from_mouse_and_widgets() {
+        let mut chain = make_chain();
+
+        let (events, _) = chain.split_events(
+            vec![Event::Mouse(mouse::Event::ButtonPressed(
+                mouse::Button::Left,
+            ))],
+            Point::new(13., 0.),
+        );
+        assert_eq!(events.len(), 1);
+        assert_eq!(chain.focused().unwrap().kind, FocusKind::Button);
+
+        // a text input focused by itself takes over the focus
+        chain.begin_frame();
+        let button = chain.focused().unwrap();
+        assert!(!chain.register(FocusKind::TextInput, Rectangle::default(), true));
+        assert!(chain.register(button.kind, button.bounds, false));
+        assert!(chain.end_frame());
+        assert_eq!(chain.focused().unwrap().kind, FocusKind::TextInput);
+    }
+
+    #[test]
+    fn it_keep_focus_on_widget_when_others_appear() {
+        let mut chain = make_chain();
+        chain.split_events(key_events(keyboard::KeyCode::Tab, false), Point::ORIGIN);
+        chain.split_events(key_events(keyboard::KeyCode::Tab, false), Point::ORIGIN);
+        let button = chain.focused().unwrap();
+
+        // a checkbox is now drawn before the button
+        chain.begin_frame();
+        chain.register(FocusKind::Checkbox, Rectangle::default(), false);
+        chain.register(FocusKind::TextInput, Rectangle::default(), false);
+        assert!(chain.register(button.kind, button.bounds, false));
+        assert!(!chain.end_frame());
+        assert_eq!(chain.focused(), Some(button));
+
+        // the focus is lost with the widget
+        chain.begin_frame();
+        chain.register(FocusKind::Checkbox, Rectangle::default(), false);
+        assert!(chain.end_frame());
+        assert_eq!(chain.focused(), None);
+    }
+
+    #[test]
+    fn it_trap_focus() {
+        let mut chain = make_chain();
+        chain.split_events(key_events(keyboard::KeyCode::Tab, false), Point::ORIGIN);
+
+        chain.begin_frame();
+        chain.register(
+            FocusKind::TextInput,
+            Rectangle::new(Point::new(0., 0.), Size::new(10., 1.)),
+            true,
+        );
+        chain.begin_trap();
+        chain.register(
+            FocusKind::Button,
+            Rectangle::new(Point::new(2., 4.), Size::new(4., 1.)),
+            false,
+        );
+        chain.register(
+            FocusKind::Button,
+            Rectangle::new(Point::new(8., 4.), Size::new(4., 1.)),
+            false,
+        );
+        chain.end_trap();
+        chain.end_frame();
+
+        // the widgets outside of the trap lose the focus
+        assert_eq!(chain.focused(), None);
+
+        for x in [2., 8., 2.] {
+            chain.split_events(key_events(keyboard::KeyCode::Tab, false), Point::ORIGIN);
+            assert_eq!(chain.focused().unwrap().bounds.x, x);
+        }
+
+        let click = vec![Event::Mouse(mouse::Event::ButtonPressed(
+            mouse::Button::Left,
+        ))];
+        chain.split_events(click, Point::ORIGIN);
+        assert_eq!(chain.focused().unwrap().bounds.x, 2.);
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+/// A protocol drawing images in pixels, supported by some terminals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GraphicsProtocol {
+    Kitty,
+    Sixel,
+}
+
+impl GraphicsProtocol {
+    /// Returns the protocol supported by the terminal, guessed from the environment.
+    pub fn detect() -> Option<Self> {
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+        let term = var("TERM");
+        let term_program = var("TERM_PROGRAM");
+
+        if term == "xterm-kitty"
+            || term == "xterm-ghostty"
+            || std::env::var_os("KITTY_WINDOW_ID").is_some()
+            || term_program == "WezTerm"
+        {
+            Some(Self::Kitty)
+        } else if term.contains("sixel")
+            || term.starts_with("foot")
+            || term.starts_with("mlterm")
+            || term_program == "iTerm.app"
+        {
+            Some(Self::Sixel)
+        } else {
+            None
+        }
+    }
+}
+
+/// An image drawn with a [`GraphicsProtocol`] over an area of cells.
+#[derive(Clone)]
+pub struct TerminalImage {
+    pub(crate) id: u32,
+    pub(crate) width: u16,
+    pub(crate) height: u16,
+    pub(crate) protocol: GraphicsProtocol,
+    /// The escape sequence drawing the image from the cursor position.
+    pub(crate) escape: Arc<str>,
+}
+
+impl TerminalImage {
+    /// Returns the escape sequence placing the image again from the cursor position, for the
+    /// protocols keeping the images sent.
+    pub(crate) fn place_escape(&self) -> Option<String> {
+        match self.protocol {
+            GraphicsProtocol::Kitty => Some(format!(
+                "\x1b_Ga=p,i={},p=1,c={},r={},q=2,C=1\x1b\\",
+                self.id, self.width, self.height
+            )),
+            GraphicsProtocol::Sixel => None,
+        }
+    }
+
+    /// Returns the escape sequence removing the image, for the protocols needing one.
+    pub(crate) fn delete_escape(&self) -> Option<String> {
+        match self.protocol {
+            GraphicsProtocol::Kitty => Some(format!("\x1b_Ga=d,d=I,i={},q=2\x1b\\", self.id)),
+            // the image is gone once the cells below it are printed again
+            GraphicsProtocol::Sixel => None,
+        }
+    }
+}
+
+impl PartialEq for TerminalImage {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.width == other.width
+            && self.height == other.height
+            && self.protocol == other.protocol
+    }
+}
+
+impl std::fmt::Debug for TerminalImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TerminalImage")
+            .field("id", &self.id)
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("protocol", &self.protocol)
+            .finish()
+    }
+}
@@ -0,0 +1,543 @@
+use super::colors::TermColor;
+use super::graphics::{GraphicsProtocol, TerminalImage};
+use super::primitives::{Cell, Primitive};
+use super::tui_renderer::TuiRenderer;
+use crate::Style;
+use ::image::imageops::{self, FilterType};
+use ::image::RgbaImage;
+use iced_native::image::{self, Data, Handle};
+use iced_native::Layout;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// The size of a cell in pixels, assumed when sending images to the terminal.
+const CELL_PIXELS: (u32, u32) = (10, 20);
+
+/// A decoded image, kept while it's drawn.
+pub(crate) struct CachedImage {
+    /// The id of the image for the Kitty protocol, unique among the images kept.
+    id: u32,
+    pixels: Option<Arc<RgbaImage>>,
+    /// The last escape sequence drawing the image, for a size in cells.
+    escape: Option<((u16, u16), Arc<str>)>,
+    /// The last half blocks drawing the image, for a size in cells.
+    half_blocks: Option<((u16, u16), HalfBlocks)>,
+    pub is_drawn: bool,
+}
+
+impl TuiRenderer {
+    /// Returns the pixels of the image, decoded once, or None if it can't be decoded.
+    fn image_pixels(&self, handle: &Handle) -> Option<Arc<RgbaImage>> {
+        let mut images = self.images.borrow_mut();
+
+        images
+            .entry(handle.id())
+            .or_insert_with(|| CachedImage {
+                id: self.next_image_id(),
+                pixels: decode(handle.data()).map(Arc::new),
+                escape: None,
+                half_blocks: None,
+                is_drawn: false,
+            })
+            .pixels
+            .clone()
+    }
+
+    /// Returns a new id for the Kitty protocol, which must not be 0.
+    fn next_image_id(&self) -> u32 {
+        let id = self.next_image_id.get().max(1);
+        self.next_image_id.set(id.wrapping_add(1));
+        id
+    }
+
+    /// Returns the id of the image for the Kitty protocol, once its pixels are cached.
+    fn image_id(&self, handle: &Handle) -> u32 {
+        self.images
+            .borrow()
+            .get(&handle.id())
+            .map_or(1, |cached| cached.id)
+    }
+
+    /// Returns the half blocks drawing the image over the cells of the size.
+    fn image_half_blocks(
+        &self,
+        handle: &Handle,
+        pixels: &RgbaImage,
+        size: (u16, u16),
+    ) -> HalfBlocks {
+        let mut images = self.images.borrow_mut();
+        let cached = images.get_mut(&handle.id());
+
+        if let Some(CachedImage {
+            half_blocks: Some((half_blocks_size, half_blocks)),
+            ..
+        }) = &cached
+        {
+            if *half_blocks_size == size {
+                return half_blocks.clone();
+            }
+        }
+
+        let half_blocks: HalfBlocks = half_blocks(pixels, size).into();
+
+        if let Some(cached) = cached {
+            cached.half_blocks = Some((size, half_blocks.clone()));
+        }
+
+        half_blocks
+    }
+
+    /// Returns the escape sequence drawing the image over the cells with the protocol.
+    fn image_escape(
+        &self,
+        handle: &Handle,
+        pixels: &RgbaImage,
+        size: (u16, u16),
+        protocol: GraphicsProtocol,
+    ) -> Arc<str> {
+        let mut images = self.images.borrow_mut();
+        let cached = images.get_mut(&handle.id());
+
+        if let Some(CachedImage {
+            escape: Some((escape_size, escape)),
+            ..
+        }) = &cached
+        {
+            if *escape_size == size {
+                return escape.clone();
+            }
+        }
+
+        let resized = imageops::resize(
+            pixels,
+            size.0 as u32 * CELL_PIXELS.0,
+            size.1 as u32 * CELL_PIXELS.1,
+            FilterType::Triangle,
+        );
+
+        let escape: Arc<str> = match protocol {
+            GraphicsProtocol::Kitty => {
+                let id = cached.as_ref().map_or(1, |cached| cached.id);
+                kitty_escape(&resized, id, size).into()
+            }
+            GraphicsProtocol::Sixel => sixel_escape(&resized).into(),
+        };
+
+        if let Some(cached) = cached {
+            cached.escape = Some((size, escape.clone()));
+        }
+
+        escape
+    }
+}
+
+impl image::Renderer for TuiRenderer {
+    /// Returns the size of the image in cells, a cell showing two pixels on top of each other.
+    fn dimensions(&self, handle: &Handle) -> (u32, u32) {
+        match self.image_pixels(handle) {
+            Some(pixels) if pixels.width() > 0 && pixels.height() > 0 => {
+                (pixels.width(), pixels.height().div_ceil(2))
+            }
+            _ => (1, 1),
+        }
+    }
+
+    fn draw(&mut self, handle: Handle, layout: Layout<'_>) -> Self::Output {
+        let bounds = layout.bounds();
+        let x = bounds.x.round() as i32;
+        let y = bounds.y.round() as i32;
+        let width = bounds.width.round() as u16;
+        let height = bounds.height.round() as u16;
+
+        let pixels = match self.image_pixels(&handle) {
+            Some(pixels) if width > 0 && height > 0 => pixels,
+            _ => return Primitive::Group(vec![]),
+        };
+
+        if let Some(cached) = self.images.get_mut().get_mut(&handle.id()) {
+            cached.is_drawn = true;
+        }
+
+        // the image may start above or left of the screen, e.g. when scrolled
+        let mut primitives: Vec<Primitive> = self
+            .image_half_blocks(&handle, &pixels, (width, height))
+            .iter()
+            .filter_map(|(column, row, cell)| {
+                let (cell_x, cell_y) = (x + *column as i32, y + *row as i32);
+
+                (cell_x >= 0 && cell_y >= 0).then_some(Primitive::Cell(
+                    cell_x as u16,
+                    cell_y as u16,
+                    *cell,
+                ))
+            })
+            .collect();
+
+        // the half blocks are left below the image, and shown if the image is dropped
+        // because it's covered or scrolled out of view
+        match self.graphics_protocol {
+            Some(protocol) if x >= 0 && y >= 0 => {
+                let escape = self.image_escape(&handle, &pixels, (width, height), protocol);
+
+                primitives.push(Primitive::Image(
+                    x as u16,
+                    y as u16,
+                    TerminalImage {
+                        id: self.image_id(&handle),
+                        width,
+                        height,
+                        protocol,
+                        escape,
+                    },
+                ));
+            }
+            _ => {}
+        }
+
+        Primitive::Group(primitives)
+    }
+}
+
+/// The cells showing an image with half blocks, at their column and row in the image.
+type HalfBlocks = Arc<[(u16, u16, Cell)]>;
+
+/// Returns the cells showing the image with `▀` blocks, colored with the pixels above and
+/// below.
+fn half_blocks(pixels: &RgbaImage, (width, height): (u16, u16)) -> Vec<(u16, u16, Cell)> {
+    let resized = imageops::resize(
+        pixels,
+        width as u32,
+        height as u32 * 2,
+        FilterType::Triangle,
+    );
+
+    // the transparent pixels show the cells behind
+    let color = |column: u16, row: u32| {
+        let [r, g, b, a] = resized.get_pixel(column as u32, row).0;
+
+        match a >= 128 {
+            true => Some(TermColor::Rgb(r, g, b)),
+            false => None,
+        }
+    };
+
+    let mut cells = vec![];
+
+    for row in 0..height {
+        for column in 0..width {
+            let top = color(column, row as u32 * 2);
+            let bottom = color(column, row as u32 * 2 + 1);
+
+            let cell = match (top, bottom) {
+                (Some(_), _) => Cell::from_char('▀').style(Style {
+                    fg_color: top,
+                    bg_color: bottom,
+                    ..Style::default()
+                }),
+                (None, Some(_)) => Cell::from_char('▄').style(Style {
+                    fg_color: bottom,
+                    ..Style::default()
+                }),
+                (None, None) => continue,
+            };
+
+            cells.push((column, row, cell));
+        }
+    }
+
+    cells
+}
+
+fn decode(data: &Data) -> Option<RgbaImage> {
+    match data {
+        Data::Path(path) => ::image::open(path).ok().map(|image| image.into_rgba8()),
+        Data::Bytes(bytes) => ::image::load_from_memory(bytes)
+            .ok()
+            .map(|image| image.into_rgba8()),
+        Data::Pixels {
+            width,
+            height,
+            pixels,
+        } => {
+            // the pixels are in BGRA
+            let rgba = pixels
+                .chunks_exact(4)
+                .flat_map(|pixel| [pixel[2], pixel[1], pixel[0], pixel[3]])
+                .collect();
+
+            RgbaImage::from_raw(*width, *height, rgba)
+        }
+    }
+}
+
+fn kitty_escape(pixels: &RgbaImage, id: u32, (width, height): (u16, u16)) -> String {
+    let data = base64::encode(pixels.as_raw());
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(4096).collect();
+    let mut escape = String::new();
+
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = if index + 1 < chunks.len() { 1 } else { 0 };
+
+        // the first chunk holds the parameters, the cursor is left in place
+        if index == 0 {
+            write!(
+                escape,
+                "\x1b_Ga=T,f=32,s={},v={},c={},r={},i={},p=1,q=2,C=1,m={};",
+                pixels.width(),
+                pixels.height(),
+                width,
+                height,
+                id,
+                more
+            )
+            .unwrap();
+        } else {
+            write!(escape, "\x1b_Gm={};", more).unwrap();
+        }
+
+        escape.push_str(std::str::from_utf8(chunk).unwrap());
+        escape.push_str("\x1b\\");
+    }
+
+    escape
+}
+
+/// Returns the sixels of the image, with its colors reduced to a 6x6x6 palette.
+fn sixel_escape(pixels: &RgbaImage) -> String {
+    let (width, height) = pixels.dimensions();
+    let palette_index = |x: u32, y: u32| {
+        let [r, g, b, a] = pixels.get_pixel(x, y).0;
+        let level = |value: u8| (value as u16 * 5 + 127) / 255;
+
+        match a >= 128 {
+            true => Some(level(r) * 36 + level(g) * 6 + level(b)),
+            false => None,
+        }
+    };
+
+    // transparent pixels are left untouched
+    let mut escape = format!("\x1bP0;1;0q\"1;1;{};{}", width, height);
+
+    for index in 0..216_u16 {
+        let percent = |level: u16| level * 100 / 5;
+        write!(
+            escape,
+            "#{};2;{};{};{}",
+            index,
+            percent(index / 36),
+            percent(index / 6 % 6),
+            percent(index % 6)
+        )
+        .unwrap();
+    }
+
+    for band in (0..height).step_by(6) {
+        let band_height = (height - band).min(6);
+        let mut colors: Vec<u16> = (0..width)
+            .flat_map(|x| (0..band_height).filter_map(move |dy| palette_index(x, band + dy)))
+            .collect();
+        colors.sort_unstable();
+        colors.dedup();
+
+        for color in colors {
+            write!(escape, "#{}", color).unwrap();
+
+            let sixels = (0..width).map(|x| {
+                let bits = (0..band_height)
+                    .filter(|dy| palette_index(x, band + dy) == Some(color))
+                    .fold(0, |bits, dy| bits | 1 << dy);
+
+                char::from(63 + bits as u8)
+            });
+
+            push_run_lengths(&mut escape, sixels);
+            // back to the start of the band for the next color
+            escape.push('$');
+        }
+
+        escape.push('-');
+    }
+
+    escape.push_str("\x1b\\");
+    escape
+}
+
+/// Pushes the sixels, repeating the runs of the same sixel with `!`.
+fn push_run_lengths(escape: &mut String, sixels: impl Iterator<Item = char>) {
+    let mut run: Option<(char, usize)> = None;
+    let push_run = |escape: &mut String, (sixel, count): (char, usize)| match count {
+        1..=3 => escape.extend(std::iter::repeat_n(sixel, count)),
+        _ => write!(escape, "!{}{}", count, sixel).unwrap(),
+    };
+
+    for sixel in sixels {
+        run = match run {
+            Some((run_sixel, count)) if run_sixel == sixel => Some((sixel, count + 1)),
+            Some(previous) => {
+                push_run(escape, previous);
+                Some((sixel, 1))
+            }
+            None => Some((sixel, 1)),
+        };
+    }
+
+    if let Some(run) = run {
+        push_run(escape, run);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::colors::TermColor;
+    use super::{sixel_escape, GraphicsProtocol};
+    use crate::renderer::primitives::{Cell, Primitive};
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::{Style, TuiRenderer};
+    use ::image::{Rgba, RgbaImage};
+    use iced_native::{
+        image::Handle, scrollable, Cache, Column, Element, Image, Length, Point, Scrollable, Size,
+        UserInterface,
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn it_draw_half_blocks() {
+        let mut renderer = TuiRenderer::default();
+
+        // a column of red, green, blue and transparent pixels, in BGRA
+        let pixels = vec![0, 0, 255, 255, 0, 255, 0, 255, 255, 0, 0, 255, 0, 0, 0, 0];
+        let image: Element<'_, (), TuiRenderer> =
+            Image::new(Handle::from_pixels(1, 4, pixels)).into();
+        let mut ui =
+            UserInterface::build(image, Size::new(4., 4.), Cache::default(), &mut renderer);
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(4, 4);
+        vbuffer.merge_primitive(&primitive);
+
+        assert_eq!(vbuffer.rows[0][0].content, Some('▀'));
+        assert_eq!(
+            vbuffer.rows[0][0].style,
+            Style {
+                fg_color: Some(TermColor::Rgb(255, 0, 0)),
+                bg_color: Some(TermColor::Rgb(0, 255, 0)),
+                ..Style::default()
+            }
+        );
+        assert_eq!(
+            vbuffer.rows[1][0].style,
+            Style {
+                fg_color: Some(TermColor::Rgb(0, 0, 255)),
+                ..Style::default()
+            }
+        );
+        assert_eq!(vbuffer.rows[0][1].content, None);
+
+        // the half blocks are only computed again for another size
+        let half_blocks = |renderer: &TuiRenderer| {
+            let images = renderer.images.borrow();
+            let cached = images.values().next().unwrap();
+            cached.half_blocks.as_ref().unwrap().1.clone()
+        };
+        let drawn = half_blocks(&renderer);
+        ui.draw(&mut renderer, Point::new(-1., -1.));
+        assert!(Arc::ptr_eq(&drawn, &half_blocks(&renderer)));
+    }
+
+    #[test]
+    fn it_draw_terminal_images() {
+        let mut renderer = TuiRenderer::default();
+        renderer.graphics_protocol = Some(GraphicsProtocol::Kitty);
+
+        let image: Element<'_, (), TuiRenderer> =
+            Image::new(Handle::from_pixels(2, 2, vec![255; 16])).into();
+        let mut ui =
+            UserInterface::build(image, Size::new(4., 4.), Cache::default(), &mut renderer);
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(4, 4);
+        vbuffer.merge_primitive(&primitive);
+
+        // the half blocks are left below the image
+        assert_eq!(vbuffer.rows[0][0].content, Some('▀'));
+        assert_eq!(vbuffer.images.len(), 1);
+        assert!(vbuffer.images[0]
+            .2
+            .escape
+            .starts_with("\x1b_Ga=T,f=32,s=20,v=20,c=2,r=1,"));
+
+        // two red pixels above a transparent one
+        let mut pixels = RgbaImage::new(1, 3);
+        pixels.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+        pixels.put_pixel(0, 1, Rgba([255, 0, 0, 255]));
+
+        let sixels = sixel_escape(&pixels);
+        assert!(sixels.starts_with("\x1bP0;1;0q\"1;1;1;3#0;2;0;0;0"));
+        assert!(sixels.ends_with("#180B$-\x1b\\"));
+    }
+
+    #[test]
+    fn it_give_unique_ids_to_images() {
+        let mut renderer = TuiRenderer::default();
+        renderer.graphics_protocol = Some(GraphicsProtocol::Kitty);
+
+        let images: Element<'_, (), TuiRenderer> = Column::new()
+            .push(Image::new(Handle::from_pixels(2, 2, vec![255; 16])))
+            .push(Image::new(Handle::from_pixels(2, 2, vec![0; 16])))
+            .into();
+        let mut ui =
+            UserInterface::build(images, Size::new(4., 4.), Cache::default(), &mut renderer);
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(4, 4);
+        vbuffer.merge_primitive(&primitive);
+
+        let ids: Vec<u32> = vbuffer
+            .images
+            .iter()
+            .map(|(_, _, image)| image.id)
+            .collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert!(vbuffer.images[1].2.escape.contains(",i=2,"));
+    }
+
+    #[test]
+    fn it_fall_back_to_half_blocks_when_hidden() {
+        let mut renderer = TuiRenderer::default();
+        renderer.graphics_protocol = Some(GraphicsProtocol::Kitty);
+
+        let image: Element<'_, (), TuiRenderer> =
+            Image::new(Handle::from_pixels(2, 4, vec![255; 32])).into();
+        let mut ui =
+            UserInterface::build(image, Size::new(4., 4.), Cache::default(), &mut renderer);
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(4, 4);
+        vbuffer.merge_primitive(&primitive);
+        assert_eq!(vbuffer.images.len(), 1);
+
+        // covered by a later layer, e.g. an overlay
+        vbuffer.merge_primitive(&Primitive::Cell(1, 1, Cell::from_char('x')));
+        assert!(vbuffer.images.is_empty());
+        assert_eq!(vbuffer.to_plain_text(), "▀▀\n▀x\n\n\n");
+
+        // scrolled partially out of the viewport
+        let mut state = scrollable::State::new();
+        let scrollable: Element<'_, (), TuiRenderer> = Scrollable::new(&mut state)
+            .height(Length::Units(1))
+            .push(Image::new(Handle::from_pixels(2, 4, vec![255; 32])))
+            .into();
+        let mut ui = UserInterface::build(
+            scrollable,
+            Size::new(4., 4.),
+            Cache::default(),
+            &mut renderer,
+        );
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(4, 4);
+        vbuffer.merge_primitive(&primitive);
+        assert!(vbuffer.images.is_empty());
+        assert_eq!(vbuffer.to_plain_text(), "▀▀\n\n\n\n");
+    }
+}
@@ -1,8 +1,14 @@
 mod button;
+mod checkbox;
 mod colors;
 mod column;
 mod container;
-mod primitives;
+pub(crate) mod focus;
+mod graphics;
+#[cfg(feature = "image")]
+mod image;
+mod pick_list;
+pub(crate) mod primitives;
 mod progress_bar;
 mod row;
 mod scrollable;
@@ -11,17 +17,22 @@ mod style;
 mod text;
 mod text_input;
 mod tui_renderer;
-mod utils;
-mod virtual_buffer;
+pub(crate) mod utils;
+pub(crate) mod virtual_buffer;
 
 pub use button::ButtonStyle;
+pub use checkbox::CheckboxStyle;
 pub use colors::AnsiColor;
-
+pub use container::ContainerStyle;
+pub use graphics::GraphicsProtocol;
+pub use pick_list::{MenuStyle, PickListStyle};
+pub use primitives::{Border, Cell};
 pub use progress_bar::ProgressBarStyle;
 pub use style::CursorShape;
 pub use style::CursorStyle;
+pub use style::Shadow;
 pub use style::Style;
 pub use text_input::TextInputStyle;
 pub(crate) use tui_renderer::RenderResult;
 pub use tui_renderer::TuiRenderer;
-
+pub use virtual_buffer::VirtualBuffer;
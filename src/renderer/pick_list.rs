@@ -0,0 +1,276 @@
+use super::focus::{focused_style, FocusKind};
+use super::primitives::{Cell, Primitive};
+use super::tui_renderer::TuiRenderer;
+use super::utils::crop_text_to_bounds;
+use crate::{AnsiColor, Shadow, Style};
+use iced_native::{overlay::menu, pick_list, Color, Point, Rectangle, Size};
+
+#[derive(Debug, Clone, Default)]
+pub struct PickListStyle {
+    pub(crate) normal: Style,
+    pub(crate) hover: Style,
+    pub(crate) focused: Style,
+    pub(crate) menu: MenuStyle,
+}
+
+impl PickListStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn normal(mut self, normal: Style) -> Self {
+        self.normal = normal;
+        self
+    }
+
+    pub fn hover(mut self, hover: Style) -> Self {
+        self.hover = hover;
+        self
+    }
+
+    /// Sets the style of the pick list when it has the keyboard focus.
+    ///
+    /// When not set, focused pick lists are drawn in bold.
+    pub fn focused(mut self, focused: Style) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    pub fn menu(mut self, menu: MenuStyle) -> Self {
+        self.menu = menu;
+        self
+    }
+}
+
+/// The style of the menu listing the options of a pick list, also used by the menus of
+/// menu bars and context menus.
+#[derive(Debug, Clone, Copy)]
+pub struct MenuStyle {
+    pub(crate) normal: Style,
+    pub(crate) selected: Style,
+    pub(crate) disabled: Style,
+    pub(crate) shadow: Option<Shadow>,
+}
+
+impl Default for MenuStyle {
+    fn default() -> Self {
+        Self {
+            normal: Style::default(),
+            // the highlighted option must stand out without colors set by the application
+            selected: Style::new()
+                .fg(Color::from(AnsiColor::Black))
+                .bg(Color::from(AnsiColor::Grey)),
+            disabled: Style::default(),
+            shadow: None,
+        }
+    }
+}
+
+impl MenuStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn normal(mut self, normal: Style) -> Self {
+        self.normal = normal;
+        self
+    }
+
+    /// Sets the style of the option under the cursor, black on grey by default.
+    pub fn selected(mut self, selected: Style) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Sets the style of the disabled menu items.
+    ///
+    /// When not set, disabled items are drawn with faded colors.
+    pub fn disabled(mut self, disabled: Style) -> Self {
+        self.disabled = disabled;
+        self
+    }
+
+    pub fn shadow(mut self, shadow: Shadow) -> Self {
+        self.shadow = Some(shadow);
+        self
+    }
+}
+
+impl pick_list::Renderer for TuiRenderer {
+    const DEFAULT_PADDING: u16 = 0;
+
+    type Style = PickListStyle;
+
+    fn menu_style(style: &<Self as pick_list::Renderer>::Style) -> <Self as menu::Renderer>::Style {
+        style.menu
+    }
+
+    fn draw(
+        &mut self,
+        bounds: Rectangle,
+        cursor_position: Point,
+        selected: Option<String>,
+        padding: u16,
+        _text_size: u16,
+        font: <Self as iced_native::text::Renderer>::Font,
+        style: &<Self as pick_list::Renderer>::Style,
+    ) -> <Self as iced_native::Renderer>::Output {
+        let is_focused = self.register_focusable(FocusKind::PickList, bounds, false);
+
+        let mut pick_list_style = style.normal;
+
+        if is_focused {
+            pick_list_style = pick_list_style.merge(focused_style(style.focused));
+        }
+
+        if bounds.contains(cursor_position) {
+            pick_list_style = pick_list_style.merge(style.hover);
+        }
+
+        let x = bounds.x.round() as u16;
+        let y = bounds.y.round() as u16;
+        let width = bounds.width.round() as u16;
+        let height = bounds.height.round() as u16;
+
+        let background = Primitive::Rectangle(
+            x,
+            y,
+            width,
+            height,
+            Cell {
+                style: pick_list_style,
+                ..Cell::default()
+            },
+        );
+
+        // the last cell before the padding holds the arrow
+        let text_width = width.saturating_sub(padding * 2 + 1);
+        let (label, _, _) = crop_text_to_bounds(
+            &selected.unwrap_or_default(),
+            Some(Size::new(text_width as f32, 1.0)),
+            x + padding,
+            y + padding,
+            false,
+            true,
+            pick_list_style.merge(font),
+            false,
+        );
+
+        let arrow = match width.checked_sub(padding + 1) {
+            Some(arrow_x) => Primitive::Cell(
+                x + arrow_x,
+                y + padding,
+                Cell::from_char('▾').style(pick_list_style),
+            ),
+            None => Primitive::Group(vec![]),
+        };
+
+        Primitive::Group(vec![background, Primitive::Group(label), arrow])
+    }
+}
+
+impl menu::Renderer for TuiRenderer {
+    type Style = MenuStyle;
+
+    fn decorate(
+        &mut self,
+        bounds: Rectangle,
+        _cursor_position: Point,
+        style: &<Self as menu::Renderer>::Style,
+        primitive: <Self as iced_native::Renderer>::Output,
+    ) -> <Self as iced_native::Renderer>::Output {
+        let x = bounds.x.round() as u16;
+        let y = bounds.y.round() as u16;
+        let width = bounds.width.round() as u16;
+        let height = bounds.height.round() as u16;
+
+        let background = Primitive::Rectangle(
+            x,
+            y,
+            width,
+            height,
+            Cell {
+                style: style.normal,
+                ..Cell::default()
+            },
+        );
+
+        let shadow = match style.shadow {
+            Some(shadow) => Primitive::shadow(x, y, width, height, shadow),
+            None => Primitive::Group(vec![]),
+        };
+
+        Primitive::Group(vec![background, primitive, shadow])
+    }
+
+    fn draw<T: ToString>(
+        &mut self,
+        bounds: Rectangle,
+        _cursor_position: Point,
+        viewport: &Rectangle,
+        options: &[T],
+        hovered_option: Option<usize>,
+        padding: u16,
+        text_size: u16,
+        font: <Self as iced_native::text::Renderer>::Font,
+        style: &<Self as menu::Renderer>::Style,
+    ) -> <Self as iced_native::Renderer>::Output {
+        let option_height = text_size + padding * 2;
+
+        if option_height == 0 {
+            return Primitive::Group(vec![]);
+        }
+
+        let x = bounds.x.round() as u16;
+        let y = bounds.y.round() as u16;
+        let width = bounds.width.round() as u16;
+
+        // only the options visible in the viewport are drawn
+        let offset = (viewport.y - bounds.y).max(0.0) as usize / option_height as usize;
+        let visible = (viewport.height / f32::from(option_height)).ceil() as usize + 1;
+
+        let primitives = options
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(visible)
+            .map(|(index, option)| {
+                let option_style = if hovered_option == Some(index) {
+                    style.normal.merge(style.selected)
+                } else {
+                    style.normal
+                };
+                let option_y = y + index as u16 * option_height;
+
+                let background = Primitive::Rectangle(
+                    x,
+                    option_y,
+                    width,
+                    option_height,
+                    Cell {
+                        style: option_style,
+                        ..Cell::default()
+                    },
+                );
+
+                let (label, _, _) = crop_text_to_bounds(
+                    &option.to_string(),
+                    Some(Size::new(
+                        width.saturating_sub(padding * 2) as f32,
+                        text_size as f32,
+                    )),
+                    x + padding,
+                    option_y + padding,
+                    false,
+                    true,
+                    option_style.merge(font),
+                    false,
+                );
+
+                Primitive::Group(vec![background, Primitive::Group(label)])
+            })
+            .collect();
+
+        Primitive::Group(primitives)
+    }
+}
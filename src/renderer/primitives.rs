@@ -1,7 +1,9 @@
-
+use super::graphics::TerminalImage;
 use crate::CursorStyle;
+use crate::Shadow;
 use crate::Style;
 use core::fmt::Debug;
+use std::convert::TryFrom;
 
 #[derive(Debug, PartialEq)]
 pub enum Primitive {
@@ -9,12 +11,233 @@ pub enum Primitive {
     Rectangle(u16, u16, u16, u16, Cell),
     Group(Vec<Primitive>),
     CursorPosition(u16, u16, CursorStyle),
+    /// Darkens the colors of the cells already drawn in the area by the given amount, from
+    /// `0.0` (unchanged) to `1.0` (black).
+    Dim(u16, u16, u16, u16, f32),
+    /// An image drawn with a graphics protocol from the cell, over the cells of its size.
+    Image(u16, u16, TerminalImage),
 }
 
 impl Primitive {
     pub fn from_char(x: u16, y: u16, content: char) -> Self {
         Self::Cell(x, y, Cell::from_char(content))
     }
+
+    /// Draws the edges of a box with the glyphs of the given [`Border`].
+    ///
+    /// Only the frame is drawn, the inside of the box is left untouched.
+    pub fn border(x: u16, y: u16, width: u16, height: u16, border: Border, style: Style) -> Self {
+        let chars = match border.chars() {
+            Some(chars) if width > 0 && height > 0 => chars,
+            _ => return Self::Group(vec![]),
+        };
+
+        let right = x + width - 1;
+        let bottom = y + height - 1;
+        let cell = |c: char| Cell::from_char(c).style(style);
+        let mut primitives = Vec::with_capacity(2 * (width as usize + height as usize));
+
+        for column in (x + 1)..right {
+            primitives.push(Self::Cell(column, y, cell(chars.horizontal)));
+            primitives.push(Self::Cell(column, bottom, cell(chars.horizontal)));
+        }
+
+        for row in (y + 1)..bottom {
+            primitives.push(Self::Cell(x, row, cell(chars.vertical)));
+            primitives.push(Self::Cell(right, row, cell(chars.vertical)));
+        }
+
+        primitives.push(Self::Cell(x, y, cell(chars.top_left)));
+        primitives.push(Self::Cell(right, y, cell(chars.top_right)));
+        primitives.push(Self::Cell(x, bottom, cell(chars.bottom_left)));
+        primitives.push(Self::Cell(right, bottom, cell(chars.bottom_right)));
+
+        Self::Group(primitives)
+    }
+
+    /// Keeps the parts of the primitive inside the area, e.g. for a row partially visible
+    /// at the bottom of a list.
+    ///
+    /// The images that don't fit entirely are dropped, since they can't be cut.
+    pub fn crop(self, x: u16, y: u16, width: u16, height: u16) -> Self {
+        let right = x.saturating_add(width);
+        let bottom = y.saturating_add(height);
+        let contains = |cell_x: u16, cell_y: u16| {
+            cell_x >= x && cell_x < right && cell_y >= y && cell_y < bottom
+        };
+        // returns the intersection of an area with the crop area, if not empty
+        let intersect = |area_x: u16, area_y: u16, area_width: u16, area_height: u16| {
+            let start_x = area_x.max(x);
+            let start_y = area_y.max(y);
+            let end_x = area_x.saturating_add(area_width).min(right);
+            let end_y = area_y.saturating_add(area_height).min(bottom);
+
+            (start_x < end_x && start_y < end_y)
+                .then(|| (start_x, start_y, end_x - start_x, end_y - start_y))
+        };
+
+        match self {
+            Self::Cell(cell_x, cell_y, _) | Self::CursorPosition(cell_x, cell_y, _)
+                if !contains(cell_x, cell_y) =>
+            {
+                Self::Group(vec![])
+            }
+            Self::Rectangle(area_x, area_y, area_width, area_height, cell) => {
+                match intersect(area_x, area_y, area_width, area_height) {
+                    Some((x, y, width, height)) => Self::Rectangle(x, y, width, height, cell),
+                    None => Self::Group(vec![]),
+                }
+            }
+            Self::Dim(area_x, area_y, area_width, area_height, amount) => {
+                match intersect(area_x, area_y, area_width, area_height) {
+                    Some((x, y, width, height)) => Self::Dim(x, y, width, height, amount),
+                    None => Self::Group(vec![]),
+                }
+            }
+            Self::Image(image_x, image_y, image)
+                if intersect(image_x, image_y, image.width, image.height)
+                    != Some((image_x, image_y, image.width, image.height)) =>
+            {
+                Self::Group(vec![])
+            }
+            Self::Group(primitives) => Self::Group(
+                primitives
+                    .into_iter()
+                    .map(|primitive| primitive.crop(x, y, width, height))
+                    .collect(),
+            ),
+            primitive => primitive,
+        }
+    }
+
+    /// Moves the primitive by the given number of cells, e.g. for the content of a scrolled
+    /// view.
+    ///
+    /// The parts moved before the first row or column are dropped, and so are the images
+    /// starting there.
+    pub fn translate(self, dx: i32, dy: i32) -> Self {
+        let move_x = |x: u16| u16::try_from(i32::from(x) + dx).ok();
+        let move_y = |y: u16| u16::try_from(i32::from(y) + dy).ok();
+        // moves an area, cutting the part before the first row or column
+        let move_area = |x: u16, y: u16, width: u16, height: u16| {
+            let start_x = i32::from(x) + dx;
+            let start_y = i32::from(y) + dy;
+            let end_x = start_x + i32::from(width);
+            let end_y = start_y + i32::from(height);
+
+            (end_x > 0 && end_y > 0).then(|| {
+                let (x, y) = (start_x.max(0), start_y.max(0));
+                (x as u16, y as u16, (end_x - x) as u16, (end_y - y) as u16)
+            })
+        };
+
+        match self {
+            Self::Cell(x, y, cell) => match (move_x(x), move_y(y)) {
+                (Some(x), Some(y)) => Self::Cell(x, y, cell),
+                _ => Self::Group(vec![]),
+            },
+            Self::CursorPosition(x, y, style) => match (move_x(x), move_y(y)) {
+                (Some(x), Some(y)) => Self::CursorPosition(x, y, style),
+                _ => Self::Group(vec![]),
+            },
+            Self::Image(x, y, image) => match (move_x(x), move_y(y)) {
+                (Some(x), Some(y)) => Self::Image(x, y, image),
+                _ => Self::Group(vec![]),
+            },
+            Self::Rectangle(x, y, width, height, cell) => match move_area(x, y, width, height) {
+                Some((x, y, width, height)) => Self::Rectangle(x, y, width, height, cell),
+                None => Self::Group(vec![]),
+            },
+            Self::Dim(x, y, width, height, amount) => match move_area(x, y, width, height) {
+                Some((x, y, width, height)) => Self::Dim(x, y, width, height, amount),
+                None => Self::Group(vec![]),
+            },
+            Self::Group(primitives) => Self::Group(
+                primitives
+                    .into_iter()
+                    .map(|primitive| primitive.translate(dx, dy))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Casts the [`Shadow`] of the box at the given position and size.
+    pub fn shadow(x: u16, y: u16, width: u16, height: u16, shadow: Shadow) -> Self {
+        if width == 0 || height == 0 {
+            return Self::Group(vec![]);
+        }
+
+        Self::Group(vec![
+            // right strip
+            Self::Dim(
+                x + width,
+                y + shadow.offset_y,
+                shadow.offset_x,
+                height,
+                shadow.dim,
+            ),
+            // bottom strip
+            Self::Dim(
+                x + shadow.offset_x,
+                y + height,
+                width.saturating_sub(shadow.offset_x),
+                shadow.offset_y,
+                shadow.dim,
+            ),
+        ])
+    }
+}
+
+/// The line style used to draw the edges of a box.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Border {
+    #[default]
+    None,
+    Light,
+    Heavy,
+    Double,
+    Rounded,
+    Ascii,
+}
+
+impl Border {
+    /// Returns the number of cells taken by the border on each side of a box.
+    pub fn width(&self) -> u16 {
+        match self {
+            Self::None => 0,
+            _ => 1,
+        }
+    }
+
+    pub(crate) fn chars(&self) -> Option<BorderChars> {
+        let (top_left, top_right, bottom_left, bottom_right, horizontal, vertical) = match self {
+            Self::None => return None,
+            Self::Light => ('┌', '┐', '└', '┘', '─', '│'),
+            Self::Heavy => ('┏', '┓', '┗', '┛', '━', '┃'),
+            Self::Double => ('╔', '╗', '╚', '╝', '═', '║'),
+            Self::Rounded => ('╭', '╮', '╰', '╯', '─', '│'),
+            Self::Ascii => ('+', '+', '+', '+', '-', '|'),
+        };
+
+        Some(BorderChars {
+            top_left,
+            top_right,
+            bottom_left,
+            bottom_right,
+            horizontal,
+            vertical,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BorderChars {
+    pub top_left: char,
+    pub top_right: char,
+    pub bottom_left: char,
+    pub bottom_right: char,
+    pub horizontal: char,
+    pub vertical: char,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
@@ -47,4 +270,8 @@ impl Cell {
         self.style = style;
         self
     }
+
+    pub fn dim(&mut self, amount: f32) {
+        self.style = self.style.dim(amount);
+    }
 }
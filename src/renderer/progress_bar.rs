@@ -1,13 +1,18 @@
 use super::primitives::{Cell, Primitive};
 use super::tui_renderer::TuiRenderer;
-use crate::Style;
+use super::utils::{progress_ratio, BarStyle, HORIZONTAL_EIGHTHS};
 use iced_native::progress_bar;
 use iced_native::Color;
+use std::time::Duration;
+
+/// The duration of a step of an indeterminate progress bar.
+const INDETERMINATE_INTERVAL: Duration = Duration::from_millis(100);
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct ProgressBarStyle {
-    pub(crate) loaded_style: Style,
-    pub(crate) unloaded_style: Style,
+    pub(crate) bar: BarStyle,
+    pub(crate) has_label: bool,
+    pub(crate) is_indeterminate: bool,
 }
 
 impl ProgressBarStyle {
@@ -19,7 +24,7 @@ impl ProgressBarStyle {
     where
         C: Into<Color>,
     {
-        self.loaded_style = self.loaded_style.bg(color);
+        self.bar = self.bar.fg(color);
         self
     }
 
@@ -27,7 +32,22 @@ impl ProgressBarStyle {
     where
         C: Into<Color>,
     {
-        self.unloaded_style = self.unloaded_style.bg(color);
+        self.bar = self.bar.bg(color);
+        self
+    }
+
+    /// Sets whether the percentage is shown in the middle of the bar.
+    ///
+    /// Where it overlaps the loaded part, its colors are inverted.
+    pub fn label(mut self, has_label: bool) -> Self {
+        self.has_label = has_label;
+        self
+    }
+
+    /// Sets whether the bar shows an activity of unknown progress, with a block moving
+    /// back and forth instead of the value.
+    pub fn indeterminate(mut self, is_indeterminate: bool) -> Self {
+        self.is_indeterminate = is_indeterminate;
         self
     }
 }
@@ -44,25 +64,192 @@ impl progress_bar::Renderer for TuiRenderer {
         value: f32,
         progress_style: &<Self as iced_native::progress_bar::Renderer>::Style,
     ) -> <Self as iced_native::Renderer>::Output {
-        let range_length = range.end() - range.start();
-        let progress_ratio = value / range_length;
-        let progress_width = (bounds.width * progress_ratio).round() as u16;
-
-        Primitive::Group(vec![
-            Primitive::Rectangle(
-                bounds.x.round() as u16,
-                bounds.y.round() as u16,
-                progress_width,
-                bounds.height as u16,
-                Cell::from_char(' ').style(progress_style.loaded_style),
-            ),
-            Primitive::Rectangle(
-                bounds.x.round() as u16 + progress_width,
-                bounds.y.round() as u16,
-                (bounds.width.round() as u16 - progress_width).max(0),
-                bounds.height as u16,
-                Cell::from_char(' ').style(progress_style.unloaded_style),
-            ),
-        ])
+        let x = bounds.x.round() as u16;
+        let y = bounds.y.round() as u16;
+        let width = bounds.width.round() as u16;
+        let height = bounds.height.round() as u16;
+
+        let mut primitives = vec![Primitive::Rectangle(
+            x,
+            y,
+            width,
+            height,
+            Cell::from_char(' ').style(progress_style.bar.unfilled),
+        )];
+
+        if progress_style.is_indeterminate {
+            self.request_animation(INDETERMINATE_INTERVAL);
+
+            let (start, block_width) = indeterminate_block(width, self.animation_time());
+
+            primitives.push(Primitive::Rectangle(
+                x + start,
+                y,
+                block_width,
+                height,
+                Cell::from_char(' ').style(progress_style.bar.filled),
+            ));
+
+            return Primitive::Group(primitives);
+        }
+
+        let progress_ratio = progress_ratio(&range, value);
+
+        let eighths = (progress_ratio * width as f32 * 8.0).round() as u16;
+        let full_width = eighths / 8;
+        let partial_eighths = eighths % 8;
+
+        primitives.push(Primitive::Rectangle(
+            x,
+            y,
+            full_width,
+            height,
+            Cell::from_char(' ').style(progress_style.bar.filled),
+        ));
+
+        if partial_eighths > 0 {
+            // the block is drawn with the color of the loaded part, over the unloaded one
+            primitives.push(Primitive::Rectangle(
+                x + full_width,
+                y,
+                1,
+                height,
+                Cell::from_char(HORIZONTAL_EIGHTHS[partial_eighths as usize - 1])
+                    .style(progress_style.bar.text_style(false)),
+            ));
+        }
+
+        if progress_style.has_label && height > 0 {
+            let label = format!("{}%", (progress_ratio * 100.0).round());
+            let label_width = label.chars().count() as u16;
+            let label_x = x + width.saturating_sub(label_width) / 2;
+            let label_y = y + (height - 1) / 2;
+
+            for (index, c) in label.chars().enumerate().take(width as usize) {
+                let column = label_x + index as u16;
+
+                // a cell more than half loaded shows the label inverted
+                let is_loaded = (column - x) * 8 + 4 <= eighths;
+
+                primitives.push(Primitive::Cell(
+                    column,
+                    label_y,
+                    Cell::from_char(c).style(progress_style.bar.text_style(is_loaded)),
+                ));
+            }
+        }
+
+        Primitive::Group(primitives)
+    }
+}
+
+/// Returns the start and the width of the block of an indeterminate progress bar, moving
+/// back and forth a cell at each step.
+fn indeterminate_block(width: u16, elapsed: Duration) -> (u16, u16) {
+    let block_width = (width / 4).max(1).min(width);
+    let course = width - block_width;
+
+    if course == 0 {
+        return (0, block_width);
+    }
+
+    let step = (elapsed.as_millis() / INDETERMINATE_INTERVAL.as_millis()) as u64;
+    let position = step % (course as u64 * 2);
+
+    let start = match position <= course as u64 {
+        true => position,
+        false => course as u64 * 2 - position,
+    };
+
+    (start as u16, block_width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProgressBarStyle;
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::{AnsiColor, TuiRenderer};
+    use iced_native::{progress_bar::Renderer, Color, Rectangle};
+    use std::time::Duration;
+
+    fn draw(
+        renderer: &TuiRenderer,
+        range: std::ops::RangeInclusive<f32>,
+        value: f32,
+        style: ProgressBarStyle,
+    ) -> VirtualBuffer {
+        let bounds = Rectangle {
+            x: 0.,
+            y: 0.,
+            width: 10.,
+            height: 1.,
+        };
+
+        let primitive = renderer.draw(bounds, range, value, &style);
+        let mut vbuffer = VirtualBuffer::from_size(10, 1);
+        vbuffer.merge_primitive(&primitive);
+        vbuffer
+    }
+
+    fn line(vbuffer: &VirtualBuffer) -> String {
+        vbuffer.rows[0]
+            .iter()
+            .map(|cell| cell.content.unwrap_or(' '))
+            .collect()
+    }
+
+    #[test]
+    fn it_draw_fractional_progress() {
+        let renderer = TuiRenderer::default();
+        let style = ProgressBarStyle::new()
+            .fg(Color::from(AnsiColor::Green))
+            .bg(Color::from(AnsiColor::DarkGrey));
+
+        // the range doesn't start at 0
+        let vbuffer = draw(&renderer, 10.0..=20.0, 13.5, style);
+        assert_eq!(line(&vbuffer), "   ▌      ");
+        assert_eq!(vbuffer.rows[0][2].style, style.bar.filled);
+        assert_eq!(vbuffer.rows[0][3].style, style.bar.text_style(false));
+
+        let vbuffer = draw(&renderer, 10.0..=20.0, 14.0, style.label(true));
+        assert_eq!(line(&vbuffer), "   40%    ");
+        assert_eq!(vbuffer.rows[0][3].style, style.bar.text_style(true));
+        assert_eq!(vbuffer.rows[0][4].style, style.bar.text_style(false));
+    }
+
+    #[test]
+    fn it_animate_indeterminate_progress() {
+        let mut renderer = TuiRenderer::default();
+        let style = ProgressBarStyle::new()
+            .fg(Color::from(AnsiColor::Green))
+            .indeterminate(true);
+
+        let loaded = |vbuffer: &VirtualBuffer| -> String {
+            vbuffer.rows[0]
+                .iter()
+                .map(|cell| match cell.style == style.bar.filled {
+                    true => '#',
+                    false => ' ',
+                })
+                .collect()
+        };
+
+        renderer.begin_frame();
+        renderer.animation_time = Duration::from_millis(300);
+        assert_eq!(
+            loaded(&draw(&renderer, 0.0..=1.0, 0.0, style)),
+            "   ##     "
+        );
+        renderer.animation_time = Duration::from_millis(1000);
+        assert_eq!(
+            loaded(&draw(&renderer, 0.0..=1.0, 0.0, style)),
+            "      ##  "
+        );
+        renderer.end_frame();
+
+        assert_eq!(
+            renderer.animation_interval(),
+            Some(Duration::from_millis(100))
+        );
     }
 }
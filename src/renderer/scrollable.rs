@@ -20,16 +20,63 @@ impl scrollable::Renderer for TuiRenderer {
     fn draw(
         &mut self,
         _scrollable: &iced_native::scrollable::State,
-        _bounds: iced_core::Rectangle,
+        bounds: iced_core::Rectangle,
         _content_bounds: iced_core::Rectangle,
         _is_mouse_over: bool,
         _is_mouse_over_scrollbar: bool,
         _scrollbar: std::option::Option<iced_native::scrollable::Scrollbar>,
-        _offset: u32,
+        offset: u32,
         _style: &<Self as iced_native::scrollable::Renderer>::Style,
         content: <Self as iced_native::Renderer>::Output,
     ) -> <Self as iced_native::Renderer>::Output {
-        content
-        //content.cut_to_offset(offset as u16, bounds.height.round() as u16)
+        // the content is drawn at its unscrolled position, the part scrolled out of the
+        // viewport is hidden
+        content.translate(0, -(offset as i32)).crop(
+            bounds.x.round() as u16,
+            bounds.y.round() as u16,
+            bounds.width.round() as u16,
+            bounds.height.round() as u16,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::TuiRenderer;
+    use iced_native::{
+        scrollable, Cache, Column, Element, Length, Point, Rectangle, Scrollable, Size, Text,
+        UserInterface,
+    };
+
+    #[test]
+    fn it_draw_scrolled_content() {
+        let mut renderer = TuiRenderer::default();
+        let mut state = scrollable::State::new();
+        state.scroll_to(
+            1.0,
+            Rectangle::new(Point::new(0., 1.), Size::new(4., 2.)),
+            Rectangle::new(Point::new(0., 1.), Size::new(4., 4.)),
+        );
+
+        let column = ["a", "b", "c", "d"]
+            .iter()
+            .fold(Column::new(), |column, text| column.push(Text::new(*text)));
+        let content: Element<'_, (), TuiRenderer> = Column::new()
+            .push(Text::new("top"))
+            .push(
+                Scrollable::new(&mut state)
+                    .height(Length::Units(2))
+                    .push(column),
+            )
+            .into();
+        let mut ui =
+            UserInterface::build(content, Size::new(4., 4.), Cache::default(), &mut renderer);
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(4, 4);
+        vbuffer.merge_primitive(&primitive);
+
+        assert_eq!(vbuffer.to_plain_text(), "top\nc\nd\n\n");
     }
 }
@@ -1,4 +1,4 @@
-use super::colors::TermColor;
+use super::colors::{AnsiColor, TermColor};
 use iced_native::Color;
 
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
@@ -6,6 +6,7 @@ pub struct Style {
     pub(crate) fg_color: Option<TermColor>,
     pub(crate) bg_color: Option<TermColor>,
     pub(crate) is_bold: bool,
+    pub(crate) is_underlined: bool,
 }
 
 impl Style {
@@ -14,7 +15,7 @@ impl Style {
     }
 
     pub fn is_empty(&self) -> bool {
-        self.fg_color.is_none() && self.bg_color.is_none() && !self.is_bold
+        self.fg_color.is_none() && self.bg_color.is_none() && !self.is_bold && !self.is_underlined
     }
 
     pub fn try_merge(self, other: Option<Self>) -> Self {
@@ -37,6 +38,24 @@ impl Style {
             self.is_bold = other.is_bold;
         }
 
+        if other.is_underlined {
+            self.is_underlined = other.is_underlined;
+        }
+
+        self
+    }
+
+    /// Darkens the colors by the given amount, from `0.0` (unchanged) to `1.0` (black).
+    ///
+    /// The default background color is unknown and kept as is, so only the text of the cells
+    /// without a background color is darkened.
+    pub(crate) fn dim(mut self, amount: f32) -> Self {
+        self.fg_color = Some(match self.fg_color {
+            Some(fg_color) => fg_color.dim(amount),
+            // the default foreground color is unknown, so just fade it
+            None => TermColor::Ansi(AnsiColor::DarkGrey),
+        });
+        self.bg_color = self.bg_color.map(|bg_color| bg_color.dim(amount));
         self
     }
 
@@ -45,6 +64,11 @@ impl Style {
         self
     }
 
+    pub fn underline(mut self) -> Self {
+        self.is_underlined = true;
+        self
+    }
+
     pub fn bg<C>(mut self, color: C) -> Self
     where
         C: Into<Color>,
@@ -62,19 +86,54 @@ impl Style {
     }
 }
 
+/// A shadow cast to the right and below a box, dimming the content behind it.
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub enum CursorShape {
-    UnderScore,
-    Line,
-    Block,
+pub struct Shadow {
+    pub(crate) offset_x: u16,
+    pub(crate) offset_y: u16,
+    pub(crate) dim: f32,
 }
 
-impl Default for CursorShape {
+impl Default for Shadow {
     fn default() -> Self {
-        Self::Line
+        Self {
+            offset_x: 2,
+            offset_y: 1,
+            dim: 0.5,
+        }
+    }
+}
+
+impl Shadow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn offset(mut self, x: u16, y: u16) -> Self {
+        self.offset_x = x;
+        self.offset_y = y;
+        self
+    }
+
+    /// Sets how much the colors behind the shadow are darkened, from `0.0` (unchanged) to
+    /// `1.0` (black).
+    ///
+    /// Only the text is darkened on the default background, which is unknown, so the shadow
+    /// is only visible over cells with a background color.
+    pub fn dim(mut self, dim: f32) -> Self {
+        self.dim = dim;
+        self
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CursorShape {
+    UnderScore,
+    #[default]
+    Line,
+    Block,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct CursorStyle {
     pub(crate) shape: CursorShape,
@@ -1,9 +1,12 @@
+use super::focus::FocusKind;
 use super::primitives::{Cell, Primitive};
 use super::tui_renderer::TuiRenderer;
-use super::utils::crop_text_to_bounds;
+use crate::AnsiColor;
 use crate::CursorStyle;
 use crate::Style;
 use iced_native::text_input;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct TextInputStyle {
@@ -11,7 +14,10 @@ pub struct TextInputStyle {
     pub(crate) focused: Style,
     pub(crate) placeholder: Style,
     pub(crate) hover: Style,
+    pub(crate) selection: Style,
     pub(crate) cursor: CursorStyle,
+    pub(crate) mask: Option<char>,
+    pub(crate) margin: u16,
 }
 
 impl TextInputStyle {
@@ -39,10 +45,35 @@ impl TextInputStyle {
         self
     }
 
+    /// Sets the style of the selected text.
+    ///
+    /// When not set, the selected text is drawn in white on blue.
+    pub fn selection(mut self, selection: Style) -> Self {
+        self.selection = selection;
+        self
+    }
+
     pub fn cursor(mut self, cursor: CursorStyle) -> Self {
         self.cursor = cursor;
         self
     }
+
+    /// Sets the character drawn in place of each character of the value.
+    ///
+    /// Password inputs (see [`TextInput::password`](iced_native::TextInput::password))
+    /// are already drawn masked with `•`, this allows to use another character.
+    pub fn mask(mut self, mask: char) -> Self {
+        self.mask = Some(mask);
+        self
+    }
+
+    /// Sets the number of cells kept visible around the cursor when the value is scrolled.
+    ///
+    /// At least one cell is kept, for the overflow indicators.
+    pub fn margin(mut self, margin: u16) -> Self {
+        self.margin = margin;
+        self
+    }
 }
 
 impl text_input::Renderer for TuiRenderer {
@@ -54,32 +85,22 @@ impl text_input::Renderer for TuiRenderer {
         _size: u16,
         _font: <Self as iced_native::text::Renderer>::Font,
     ) -> f32 {
-        let (_, width, _) =
-            crop_text_to_bounds(value, None, 0, 0, false, false, Style::default(), false);
-        width as f32
+        value.graphemes(true).map(grapheme_width).sum::<u16>() as f32
     }
 
+    /// Returns the offset of the value drawn last, so that clicks land where the value
+    /// is shown. The viewport only scrolls while drawing.
     fn offset(
         &self,
-        text_bounds: iced_core::Rectangle,
+        _text_bounds: iced_core::Rectangle,
         _font: <Self as iced_native::text::Renderer>::Font,
         _size: u16,
-        value: &iced_native::text_input::Value,
+        _value: &iced_native::text_input::Value,
         state: &iced_native::text_input::State,
     ) -> f32 {
-        let cursor_state = state.cursor().state(value);
-        let text_bounds_length = text_bounds.width as u16;
-
-        let focused_index = match cursor_state {
-            text_input::cursor::State::Index(cursor_index) => cursor_index as u16,
-            text_input::cursor::State::Selection { start: _, end } => end as u16,
-        };
-
-        if focused_index > text_bounds_length {
-            return (focused_index - text_bounds_length) as f32;
-        }
-
-        0_f32
+        self.text_input_viewports
+            .get(&viewport_key(state))
+            .map_or(0, |viewport| viewport.offset) as f32
     }
 
     fn draw(
@@ -87,20 +108,51 @@ impl text_input::Renderer for TuiRenderer {
         bounds: iced_core::Rectangle,
         text_bounds: iced_core::Rectangle,
         cursor_position: iced_core::Point,
-        font: <Self as iced_native::text::Renderer>::Font,
-        size: u16,
+        _font: <Self as iced_native::text::Renderer>::Font,
+        _size: u16,
         placeholder: &str,
         value: &iced_native::text_input::Value,
         state: &iced_native::text_input::State,
         style: &<Self as iced_native::text_input::Renderer>::Style,
     ) -> <Self as iced_native::Renderer>::Output {
-        let offset = self.offset(text_bounds, font, size, value, state);
+        self.register_focusable(FocusKind::TextInput, bounds, state.is_focused());
+
+        let offset = {
+            let viewport = self
+                .text_input_viewports
+                .entry(viewport_key(state))
+                .or_default();
+            viewport.margin = style.margin;
+            viewport.is_drawn = true;
+
+            let widths = grapheme_widths(value, style.mask);
+            let cursor_index = match state.cursor().state(value) {
+                text_input::cursor::State::Index(cursor_index) => cursor_index,
+                text_input::cursor::State::Selection { start: _, end } => end,
+            };
+
+            viewport.scroll_to(
+                widths.iter().take(cursor_index).sum(),
+                widths.iter().sum(),
+                text_bounds.width.round() as u16,
+            );
+
+            viewport.offset
+        };
 
-        let mut rendered_string = value.to_string();
         let mut rendered_is_placeholder = false;
+        // password inputs are given their secure value, with the characters already masked
+        let mut graphemes: Vec<String> = match style.mask {
+            Some(mask) => vec![mask.to_string(); value.len()],
+            None => value
+                .to_string()
+                .graphemes(true)
+                .map(String::from)
+                .collect(),
+        };
 
-        if rendered_string.is_empty() && !state.is_focused() {
-            rendered_string = placeholder.to_string();
+        if graphemes.is_empty() && !state.is_focused() {
+            graphemes = placeholder.graphemes(true).map(String::from).collect();
             rendered_is_placeholder = true;
         }
 
@@ -122,58 +174,339 @@ impl text_input::Renderer for TuiRenderer {
             main_style
         };
 
-        let (mut parsed_primitives, _, _) = crop_text_to_bounds(
-            &rendered_string,
-            None,
+        let selection = match state.cursor().selection(value) {
+            Some(selection) if state.is_focused() && !rendered_is_placeholder => Some(selection),
+            _ => None,
+        };
+        let selection_style = if style.selection.is_empty() {
+            Style::new().fg(AnsiColor::White).bg(AnsiColor::Blue)
+        } else {
+            style.selection
+        };
+
+        let total_width: u16 = graphemes.iter().map(|g| grapheme_width(g)).sum();
+        let overflows_left = offset > 0;
+        // a focused input also shows the cursor after the value
+        let overflows_right = offset + text_bounds_width < total_width + state.is_focused() as u16;
+
+        // the indicators take the place of the first and last cells
+        let visible_start = offset + overflows_left as u16;
+        let visible_end = (offset + text_bounds_width).saturating_sub(overflows_right as u16);
+
+        let mut result_primitives = Vec::with_capacity(text_bounds_width as usize + 1);
+        result_primitives.push(Primitive::Rectangle(
             start_x,
             start_y,
-            false,
-            true,
-            text_style,
-            false,
-        );
-        let mut parsed_primitives_drain = parsed_primitives.drain((offset as usize)..);
-        let mut result_primitives = Vec::with_capacity(text_bounds.width as usize);
+            text_bounds_width,
+            1,
+            Cell {
+                content: None,
+                style: main_style,
+            },
+        ));
 
-        for index in 0..text_bounds_width {
-            let primitive = match parsed_primitives_drain.next() {
-                Some(Primitive::Cell(_, _, cell)) => {
-                    Primitive::Cell(start_x + index, start_y, cell)
-                }
-                _ => Primitive::Cell(
-                    start_x + index,
-                    start_y,
-                    Cell {
-                        content: None,
-                        style: main_style,
-                    },
-                ),
-            };
+        let mut column = 0;
 
-            result_primitives.push(primitive);
-        }
+        for (index, grapheme) in graphemes.iter().enumerate() {
+            let width = grapheme_width(grapheme);
 
-        if state.is_focused() {
-            let cursor_state = state.cursor().state(value);
+            if column >= visible_start && column + width <= visible_end {
+                let is_selected =
+                    selection.is_some_and(|(start, end)| (start..end).contains(&index));
+                let cell_style = if is_selected {
+                    text_style.merge(selection_style)
+                } else {
+                    text_style
+                };
 
-            match cursor_state {
-                text_input::cursor::State::Index(cursor_index) => {
-                    result_primitives.push(Primitive::CursorPosition(
-                        start_x + (cursor_index as u16) - offset as u16,
-                        start_y,
-                        style.cursor,
-                    ))
-                }
-                text_input::cursor::State::Selection { start: _, end } => {
-                    result_primitives.push(Primitive::CursorPosition(
-                        start_x + (end as u16) - offset as u16,
+                // the cells of wide characters after the first one are left empty
+                for cell_index in 0..width {
+                    result_primitives.push(Primitive::Cell(
+                        start_x + column - offset + cell_index,
                         start_y,
-                        style.cursor,
-                    ))
+                        Cell {
+                            content: match cell_index {
+                                0 => grapheme.chars().next(),
+                                _ => None,
+                            },
+                            style: cell_style,
+                        },
+                    ));
                 }
+            }
+
+            column += width;
+        }
+
+        if overflows_left {
+            result_primitives.push(Primitive::Cell(
+                start_x,
+                start_y,
+                Cell::from_char('‹').style(main_style),
+            ));
+        }
+
+        if overflows_right && text_bounds_width > 0 {
+            result_primitives.push(Primitive::Cell(
+                start_x + text_bounds_width - 1,
+                start_y,
+                Cell::from_char('›').style(main_style),
+            ));
+        }
+
+        if state.is_focused() {
+            let cursor_index = match state.cursor().state(value) {
+                text_input::cursor::State::Index(cursor_index) => cursor_index,
+                text_input::cursor::State::Selection { start: _, end } => end,
             };
+            let cursor_column: u16 = graphemes
+                .iter()
+                .take(cursor_index)
+                .map(|g| grapheme_width(g))
+                .sum();
+
+            result_primitives.push(Primitive::CursorPosition(
+                start_x + cursor_column.saturating_sub(offset),
+                start_y,
+                style.cursor,
+            ));
         }
 
         Primitive::Group(result_primitives)
     }
 }
+
+/// The horizontal scrolling of a text input, kept between frames so that the cursor
+/// can move within the visible part of the value without scrolling it.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct TextInputViewport {
+    offset: u16,
+    margin: u16,
+    pub(crate) is_drawn: bool,
+}
+
+impl TextInputViewport {
+    /// Scrolls just enough to show the cursor with the margin around it.
+    ///
+    /// `value_width` doesn't include the cell of the cursor at the end of the value.
+    fn scroll_to(&mut self, cursor_column: u16, value_width: u16, width: u16) {
+        if width == 0 {
+            self.offset = 0;
+            return;
+        }
+
+        // the overflow indicators take one cell on each side
+        let margin = self.margin.max(1).min(width.saturating_sub(1) / 2);
+
+        if cursor_column < self.offset + margin {
+            self.offset = cursor_column.saturating_sub(margin);
+        } else if cursor_column + margin >= self.offset + width {
+            self.offset = cursor_column + margin + 1 - width;
+        }
+
+        self.offset = self.offset.min((value_width + 1).saturating_sub(width));
+    }
+}
+
+/// The state of a text input can't hold its viewport, so text inputs are identified by
+/// the address of their state, which the application keeps between frames.
+///
+/// Unlike the layout bounds, the address stays the same when the text input moves on the
+/// screen. But the scroll of the viewport is lost when the application moves the state
+/// itself (e.g. when a `Vec` of states grows), and a new state allocated at the address of
+/// a dropped one starts from its scroll, moved to show the new cursor.
+pub(crate) fn viewport_key(state: &text_input::State) -> usize {
+    state as *const text_input::State as usize
+}
+
+fn grapheme_width(grapheme: &str) -> u16 {
+    grapheme.width().clamp(1, 2) as u16
+}
+
+fn grapheme_widths(value: &iced_native::text_input::Value, mask: Option<char>) -> Vec<u16> {
+    match mask {
+        Some(mask) => vec![grapheme_width(&mask.to_string()); value.len()],
+        None => value
+            .to_string()
+            .graphemes(true)
+            .map(grapheme_width)
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::primitives::Primitive;
+    use super::super::virtual_buffer::VirtualBuffer;
+    use super::TextInputViewport;
+    use crate::{AnsiColor, Style, TextInputStyle, TuiRenderer};
+    use iced_native::{
+        clipboard, keyboard, text_input, Cache, Column, Element, Event, Length, Point, Size, Text,
+        TextInput, UserInterface,
+    };
+
+    #[test]
+    fn it_highlight_selection() {
+        let mut renderer = TuiRenderer::default();
+        let mut state = text_input::State::focused();
+        state.move_cursor_to_end();
+
+        let selection = Style::new().bg(AnsiColor::Green);
+        let text_input: Element<'_, String, TuiRenderer> =
+            TextInput::new(&mut state, "", "hello", |value| value)
+                .width(Length::Units(10))
+                .style(TextInputStyle::new().selection(selection))
+                .into();
+
+        let mut ui = UserInterface::build(
+            text_input,
+            Size::new(10., 1.),
+            Cache::default(),
+            &mut renderer,
+        );
+
+        let shift = keyboard::Modifiers {
+            shift: true,
+            ..keyboard::Modifiers::default()
+        };
+        let left = Event::Keyboard(keyboard::Event::KeyPressed {
+            key_code: keyboard::KeyCode::Left,
+            modifiers: shift,
+        });
+        ui.update(
+            &[
+                Event::Keyboard(keyboard::Event::ModifiersChanged(shift)),
+                left.clone(),
+                left,
+            ],
+            Point::new(-1., -1.),
+            &renderer,
+            &mut clipboard::Null,
+            &mut vec![],
+        );
+
+        let primitive: Primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(10, 1);
+        vbuffer.merge_primitive(&primitive);
+
+        let selected: Vec<bool> = vbuffer.rows[0][..5]
+            .iter()
+            .map(|cell| cell.style == selection)
+            .collect();
+        assert_eq!(selected, vec![false, false, false, true, true]);
+    }
+
+    #[test]
+    fn it_mask_value() {
+        let mut renderer = TuiRenderer::default();
+        let mut state = text_input::State::new();
+        let mut password_state = text_input::State::new();
+
+        let text_inputs: Element<'_, String, TuiRenderer> = Column::new()
+            .push(
+                TextInput::new(&mut state, "", "secret", |value| value)
+                    .style(TextInputStyle::new().mask('*')),
+            )
+            .push(TextInput::new(&mut password_state, "", "secret", |value| value).password())
+            .into();
+
+        let mut ui = UserInterface::build(
+            text_inputs,
+            Size::new(10., 2.),
+            Cache::default(),
+            &mut renderer,
+        );
+
+        let primitive: Primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(10, 2);
+        vbuffer.merge_primitive(&primitive);
+
+        let rows: Vec<String> = vbuffer
+            .rows
+            .iter()
+            .map(|row| row.iter().filter_map(|cell| cell.content).collect())
+            .collect();
+        assert_eq!(rows, vec!["******", "••••••"]);
+    }
+
+    #[test]
+    fn it_keep_viewport_stable_around_cursor() {
+        let mut viewport = TextInputViewport {
+            margin: 2,
+            ..TextInputViewport::default()
+        };
+
+        // typing at the end scrolls to keep the margin after the cursor
+        viewport.scroll_to(20, 20, 10);
+        assert_eq!(viewport.offset, 11);
+
+        // moving back to the left doesn't scroll until the margin is reached
+        viewport.scroll_to(14, 20, 10);
+        assert_eq!(viewport.offset, 11);
+        viewport.scroll_to(12, 20, 10);
+        assert_eq!(viewport.offset, 10);
+
+        viewport.scroll_to(0, 20, 10);
+        assert_eq!(viewport.offset, 0);
+    }
+
+    #[test]
+    fn it_show_overflow_indicators() {
+        let mut renderer = TuiRenderer::default();
+        let mut state = text_input::State::focused();
+        state.move_cursor_to(4);
+
+        let text_input: Element<'_, String, TuiRenderer> =
+            TextInput::new(&mut state, "", "0123456789界abc", |value| value)
+                .width(Length::Units(6))
+                .into();
+
+        let mut ui = UserInterface::build(
+            text_input,
+            Size::new(6., 1.),
+            Cache::default(),
+            &mut renderer,
+        );
+        let primitive: Primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        assert_eq!(render_rows(&primitive, 6, 1), vec!["01234›"]);
+
+        let cache = ui.into_cache();
+        state.move_cursor_to(11);
+
+        let text_input: Element<'_, String, TuiRenderer> =
+            TextInput::new(&mut state, "", "0123456789界abc", |value| value)
+                .width(Length::Units(6))
+                .into();
+
+        let mut ui = UserInterface::build(text_input, Size::new(6., 1.), cache, &mut renderer);
+        let primitive: Primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        assert_eq!(render_rows(&primitive, 6, 1), vec!["‹9界 a›"]);
+
+        // the viewport follows the state when the input moves
+        let cache = ui.into_cache();
+        state.move_cursor_to(10);
+
+        let text_inputs: Element<'_, String, TuiRenderer> = Column::new()
+            .push(Text::new("moved"))
+            .push(
+                TextInput::new(&mut state, "", "0123456789界abc", |value| value)
+                    .width(Length::Units(6)),
+            )
+            .into();
+
+        let mut ui = UserInterface::build(text_inputs, Size::new(6., 2.), cache, &mut renderer);
+        let primitive: Primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        assert_eq!(render_rows(&primitive, 6, 2), vec!["moved ", "‹9界 a›"]);
+    }
+
+    fn render_rows(primitive: &Primitive, width: u16, height: u16) -> Vec<String> {
+        let mut vbuffer = VirtualBuffer::from_size(width, height);
+        vbuffer.merge_primitive(primitive);
+
+        vbuffer
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.content.unwrap_or(' ')).collect())
+            .collect()
+    }
+}
@@ -1,12 +1,42 @@
 use super::colors::get_crossterm_color;
+use super::focus::{FocusChain, FocusKind};
+use super::graphics::{GraphicsProtocol, TerminalImage};
+#[cfg(feature = "image")]
+use super::image::CachedImage;
 use super::primitives::{Cell, Primitive};
 use super::style::{CursorShape, Style};
+use super::text_input::TextInputViewport;
 use super::virtual_buffer::VirtualBuffer;
+use crate::Click;
 use crossterm::{cursor, execute, queue, terminal};
 use iced_native::Renderer;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthChar;
 
 #[derive(Default)]
-pub struct TuiRenderer {}
+pub struct TuiRenderer {
+    last_click: Option<Click>,
+    pub(crate) focus_chain: FocusChain,
+    pub(crate) text_input_viewports: HashMap<usize, TextInputViewport>,
+    #[cfg(feature = "image")]
+    pub(crate) images: RefCell<HashMap<u64, CachedImage>>,
+    /// The next id given to an image for the Kitty protocol.
+    #[cfg(feature = "image")]
+    pub(crate) next_image_id: std::cell::Cell<u32>,
+    pub(crate) graphics_protocol: Option<GraphicsProtocol>,
+    animation_start: Option<Instant>,
+    pub(crate) animation_time: Duration,
+    /// The time elapsed since the first frame when set, e.g. by a headless runtime, instead
+    /// of the time of the system.
+    pub(crate) manual_clock: Option<Duration>,
+    animation_interval: Option<Duration>,
+    // set while drawing with a shared reference, like the widget renderers do
+    animation_interval_while_drawing: std::cell::Cell<Option<Duration>>,
+    /// The escape sequences written before the next frame, e.g. to set the clipboard.
+    pending_output: RefCell<String>,
+}
 
 pub struct RenderResult {
     primitive: Primitive,
@@ -15,28 +45,155 @@ pub struct RenderResult {
 }
 
 impl TuiRenderer {
+    /// Returns the last mouse [`Click`] tracked by the runtime.
+    ///
+    /// Widgets can use it while handling a `ButtonPressed` event to detect double and
+    /// triple clicks.
+    pub fn last_click(&self) -> Option<Click> {
+        self.last_click
+    }
+
+    pub(crate) fn set_last_click(&mut self, click: Click) {
+        self.last_click = Some(click);
+    }
+
+    /// Must be called before drawing the user interface.
+    pub(crate) fn begin_frame(&mut self) {
+        self.focus_chain.begin_frame();
+
+        let animation_start = *self.animation_start.get_or_insert_with(Instant::now);
+        self.animation_time = self.now().duration_since(animation_start);
+        self.animation_interval_while_drawing.set(None);
+
+        for viewport in self.text_input_viewports.values_mut() {
+            viewport.is_drawn = false;
+        }
+
+        #[cfg(feature = "image")]
+        for image in self.images.get_mut().values_mut() {
+            image.is_drawn = false;
+        }
+    }
+
+    /// Must be called after drawing the user interface, returns whether the frame must
+    /// be drawn again.
+    pub(crate) fn end_frame(&mut self) -> bool {
+        // forget the text inputs that are gone
+        self.text_input_viewports
+            .retain(|_, viewport| viewport.is_drawn);
+
+        // forget the decoded images that are gone
+        #[cfg(feature = "image")]
+        self.images.get_mut().retain(|_, image| image.is_drawn);
+
+        self.animation_interval = self.animation_interval_while_drawing.get();
+
+        self.focus_chain.end_frame()
+    }
+
+    /// Returns the current time, from the manual clock when it's set.
+    pub(crate) fn now(&mut self) -> Instant {
+        let animation_start = *self.animation_start.get_or_insert_with(Instant::now);
+
+        match self.manual_clock {
+            Some(elapsed) => animation_start + elapsed,
+            None => Instant::now(),
+        }
+    }
+
+    /// Returns the time elapsed since the first frame, to pick the frames of animations.
+    pub(crate) fn animation_time(&self) -> Duration {
+        self.animation_time
+    }
+
+    /// Requests the frame to be drawn again after the interval, for an animation being drawn.
+    pub(crate) fn request_animation(&self, interval: Duration) {
+        let interval = match self.animation_interval_while_drawing.get() {
+            Some(requested) => requested.min(interval),
+            None => interval,
+        };
+
+        self.animation_interval_while_drawing.set(Some(interval));
+    }
+
+    /// Returns the interval at which the animations drawn in the last frame are ticking.
+    pub(crate) fn animation_interval(&self) -> Option<Duration> {
+        self.animation_interval
+    }
+
+    /// Registers a focusable widget being drawn, returning whether it holds the focus.
+    pub(crate) fn register_focusable(
+        &mut self,
+        kind: FocusKind,
+        bounds: iced_native::Rectangle,
+        has_focus: bool,
+    ) -> bool {
+        self.focus_chain.register(kind, bounds, has_focus)
+    }
+
+    /// Traps the focus in the focusable widgets drawn until [`end_focus_trap`] is called.
+    ///
+    /// [`end_focus_trap`]: TuiRenderer::end_focus_trap
+    pub(crate) fn begin_focus_trap(&mut self) {
+        self.focus_chain.begin_trap();
+    }
+
+    pub(crate) fn end_focus_trap(&mut self) {
+        self.focus_chain.end_trap();
+    }
+
+    /// Returns whether the focusable widget drawn in the bounds holds the focus.
+    pub(crate) fn is_focused(&self, bounds: iced_native::Rectangle) -> bool {
+        self.focus_chain
+            .focused()
+            .is_some_and(|focusable| focusable.bounds == bounds)
+    }
+
+    /// Returns whether the focused widget is drawn within the bounds.
+    pub(crate) fn has_focus_within(&self, bounds: iced_native::Rectangle) -> bool {
+        self.focus_chain
+            .focused()
+            .is_some_and(|focusable| bounds.contains(focusable.bounds.position()))
+    }
+
     pub fn begin_screen(&self, stdout: &mut std::io::Stdout) {
         terminal::enable_raw_mode().unwrap();
         execute!(
             stdout,
             terminal::EnterAlternateScreen,
-            crossterm::event::EnableMouseCapture
+            crossterm::event::EnableMouseCapture,
+            crossterm::event::EnableBracketedPaste
         )
         .unwrap();
     }
 
     pub fn end_screen(&self, stdout: &mut std::io::Stdout) {
-        execute!(stdout, terminal::LeaveAlternateScreen).unwrap();
+        execute!(
+            stdout,
+            crossterm::event::DisableBracketedPaste,
+            terminal::LeaveAlternateScreen
+        )
+        .unwrap();
         terminal::disable_raw_mode().unwrap();
         execute!(stdout, crossterm::style::ResetColor, cursor::Show).unwrap();
     }
 
-    fn make_vbuffer(&self, primitive: &Primitive, width: u16, height: u16) -> VirtualBuffer {
+    pub(crate) fn make_vbuffer(
+        &self,
+        primitive: &Primitive,
+        width: u16,
+        height: u16,
+    ) -> VirtualBuffer {
         let mut vbuffer = VirtualBuffer::from_size(width, height);
         vbuffer.merge_primitive(primitive);
         vbuffer
     }
 
+    /// Queues an escape sequence written to the terminal with the next frame.
+    pub(crate) fn queue_output(&self, escape: &str) {
+        self.pending_output.borrow_mut().push_str(escape);
+    }
+
     pub fn render<O>(
         &self,
         output: &mut O,
@@ -49,8 +206,15 @@ impl TuiRenderer {
         let size = terminal::size().unwrap();
         let mut last_vbuffer: Option<VirtualBuffer> = None;
 
+        let pending_output = self.pending_output.take();
+        if !pending_output.is_empty() {
+            queue!(output, crossterm::style::Print(pending_output)).unwrap();
+        }
+
         if let Some(last_render) = last_render {
             if last_render.primitive == primitive && last_render.size == size {
+                output.flush().unwrap();
+
                 return RenderResult {
                     vbuffer: last_render.vbuffer,
                     primitive,
@@ -108,59 +272,71 @@ impl TuiRenderer {
         let diff_rows: Vec<(usize, &Vec<Cell>)> = if let Some(last_vbuffer) = last_vbuffer {
             self.get_diff_rows(&vbuffer, last_vbuffer)
         } else {
-            vbuffer.rows.iter().enumerate().into_iter().collect()
+            vbuffer.rows.iter().enumerate().collect()
         };
 
-        let splited_rows = diff_rows.iter().map(|(i, row)| (i, split_by_style(row)));
+        // an image left in place isn't drawn again, unless the cells below it changed
+        let kept_images: Vec<&(u16, u16, TerminalImage)> = match last_vbuffer {
+            Some(last_vbuffer) => vbuffer
+                .images
+                .iter()
+                .filter(|image| {
+                    last_vbuffer.images.contains(image)
+                        && !is_image_overwritten(image, &vbuffer, last_vbuffer)
+                })
+                .collect(),
+            None => vec![],
+        };
 
-        for (i, results_by_style) in splited_rows {
-            queue!(output, cursor::MoveTo(0, *i as u16),).unwrap();
+        for (i, row) in &diff_rows {
+            // the cells below the kept images aren't printed again, they would erase them
+            let skipped_columns: Vec<(u16, u16)> = kept_images
+                .iter()
+                .filter(|(_, y, image)| (*y..y + image.height).contains(&(*i as u16)))
+                .map(|(x, _, image)| (*x, image.width))
+                .collect();
 
-            for (style, content) in results_by_style {
-                let mut fg_changed = false;
-                let mut bg_changed = false;
-                let mut attribute_changed = false;
+            for (start, end) in printed_spans(row.len(), skipped_columns) {
+                queue!(output, cursor::MoveTo(start as u16, *i as u16)).unwrap();
+                rendered_anything |= queue_cells(output, &row[start..end]);
+            }
+        }
 
-                if let Some(fg_color) = style.fg_color {
-                    queue!(
-                        output,
-                        crossterm::style::SetForegroundColor(get_crossterm_color(fg_color))
-                    )
-                    .unwrap();
-                    fg_changed = true;
-                }
+        for image in &vbuffer.images {
+            if kept_images.contains(&image) {
+                continue;
+            }
 
-                if let Some(bg_color) = style.bg_color {
-                    queue!(
-                        output,
-                        crossterm::style::SetBackgroundColor(get_crossterm_color(bg_color))
-                    )
-                    .unwrap();
-                    bg_changed = true;
-                }
+            let (x, y, image) = image;
 
-                if style.is_bold {
-                    queue!(
-                        output,
-                        crossterm::style::SetAttribute(crossterm::style::Attribute::Bold)
-                    )
-                    .unwrap();
-                    attribute_changed = true;
+            // a Kitty image already sent is placed again without sending its pixels
+            let is_transmitted = last_vbuffer.is_some_and(|last_vbuffer| {
+                last_vbuffer
+                    .images
+                    .iter()
+                    .any(|(_, _, last_image)| last_image == image)
+            });
+            queue!(output, cursor::MoveTo(*x, *y)).unwrap();
+
+            match image.place_escape() {
+                Some(place_escape) if is_transmitted => {
+                    queue!(output, crossterm::style::Print(place_escape)).unwrap()
                 }
+                _ => queue!(output, crossterm::style::Print(&*image.escape)).unwrap(),
+            }
 
-                queue!(output, crossterm::style::Print(content)).unwrap();
-                rendered_anything = true;
+            rendered_anything = true;
+        }
 
-                if fg_changed || bg_changed {
-                    queue!(output, crossterm::style::ResetColor).unwrap();
-                }
+        if let Some(last_vbuffer) = last_vbuffer {
+            for (_, _, last_image) in &last_vbuffer.images {
+                let is_gone = !vbuffer
+                    .images
+                    .iter()
+                    .any(|(_, _, image)| image.id == last_image.id);
 
-                if attribute_changed {
-                    queue!(
-                        output,
-                        crossterm::style::SetAttribute(crossterm::style::Attribute::Reset),
-                    )
-                    .unwrap();
+                if let Some(escape) = last_image.delete_escape().filter(|_| is_gone) {
+                    queue!(output, crossterm::style::Print(escape)).unwrap();
                 }
             }
         }
@@ -204,12 +380,134 @@ impl TuiRenderer {
     }
 }
 
+/// Prints the cells from the cursor position, returning whether anything was printed.
+fn queue_cells<O>(output: &mut O, cells: &[Cell]) -> bool
+where
+    O: std::io::Write,
+{
+    let results_by_style = split_by_style(cells);
+
+    for (style, content) in &results_by_style {
+        let mut fg_changed = false;
+        let mut bg_changed = false;
+        let mut attribute_changed = false;
+
+        if let Some(fg_color) = style.fg_color {
+            queue!(
+                output,
+                crossterm::style::SetForegroundColor(get_crossterm_color(fg_color))
+            )
+            .unwrap();
+            fg_changed = true;
+        }
+
+        if let Some(bg_color) = style.bg_color {
+            queue!(
+                output,
+                crossterm::style::SetBackgroundColor(get_crossterm_color(bg_color))
+            )
+            .unwrap();
+            bg_changed = true;
+        }
+
+        if style.is_bold {
+            queue!(
+                output,
+                crossterm::style::SetAttribute(crossterm::style::Attribute::Bold)
+            )
+            .unwrap();
+            attribute_changed = true;
+        }
+
+        if style.is_underlined {
+            queue!(
+                output,
+                crossterm::style::SetAttribute(crossterm::style::Attribute::Underlined)
+            )
+            .unwrap();
+            attribute_changed = true;
+        }
+
+        queue!(output, crossterm::style::Print(content)).unwrap();
+
+        if fg_changed || bg_changed {
+            queue!(output, crossterm::style::ResetColor).unwrap();
+        }
+
+        if attribute_changed {
+            queue!(
+                output,
+                crossterm::style::SetAttribute(crossterm::style::Attribute::Reset),
+            )
+            .unwrap();
+        }
+    }
+
+    !results_by_style.is_empty()
+}
+
+/// Returns the spans of a row left to print around the skipped columns, given by their start
+/// and width.
+fn printed_spans(width: usize, mut skipped_columns: Vec<(u16, u16)>) -> Vec<(usize, usize)> {
+    skipped_columns.sort_unstable();
+
+    let mut spans = vec![];
+    let mut start = 0;
+
+    for (skipped_start, skipped_width) in skipped_columns {
+        let skipped_start = (skipped_start as usize).min(width);
+
+        if start < skipped_start {
+            spans.push((start, skipped_start));
+        }
+
+        start = start.max((skipped_start + skipped_width as usize).min(width));
+    }
+
+    if start < width {
+        spans.push((start, width));
+    }
+
+    spans
+}
+
+/// Returns whether the cells below the image changed since the last frame.
+fn is_image_overwritten(
+    (x, y, image): &(u16, u16, TerminalImage),
+    vbuffer: &VirtualBuffer,
+    last_vbuffer: &VirtualBuffer,
+) -> bool {
+    let columns = *x as usize..(x + image.width) as usize;
+
+    (*y..y + image.height).any(|row| {
+        let cells = vbuffer
+            .rows
+            .get(row as usize)
+            .and_then(|row| row.get(columns.clone()));
+        let last_cells = last_vbuffer
+            .rows
+            .get(row as usize)
+            .and_then(|row| row.get(columns.clone()));
+
+        cells != last_cells
+    })
+}
+
 fn split_by_style(cells: &[Cell]) -> Vec<(Style, String)> {
     let mut last_style = Style::default();
     let mut results = vec![];
     let mut last_string = "".to_string();
+    let mut is_wide_char_continuation = false;
 
     for cell in cells {
+        // wide characters are printed over the next cell
+        if is_wide_char_continuation {
+            is_wide_char_continuation = false;
+            continue;
+        }
+
+        is_wide_char_continuation = cell.content.and_then(|c| c.width()) == Some(2);
+
         if !last_string.is_empty() && last_style != cell.style {
             results.push((last_style, last_string));
 
@@ -235,10 +533,10 @@ impl Renderer for TuiRenderer {
     fn overlay(
         &mut self,
         base: <Self as iced_native::Renderer>::Output,
-        _overlay: <Self as iced_native::Renderer>::Output,
+        overlay: <Self as iced_native::Renderer>::Output,
         _overlay_bounds: iced_native::Rectangle,
     ) -> <Self as iced_native::Renderer>::Output {
-        base
+        Primitive::Group(vec![base, overlay])
     }
 }
 
@@ -267,6 +565,7 @@ mod tests {
                                 fg_color: Some(TermColor::Rgb(x, x + 10_u8, y + 5_u8)),
                                 bg_color: Some(TermColor::Rgb(x, x + 8_u8, y + 7_u8)),
                                 is_bold: x % 2 == 0,
+                                is_underlined: false,
                             },
                         },
                     ));
@@ -289,6 +588,64 @@ mod tests {
         b.iter(|| vbuffer.clone())
     }
 
+    #[test]
+    fn it_skip_cell_after_wide_char() {
+        let cells = vec![
+            Cell::from_char('a'),
+            Cell::from_char('界'),
+            Cell::default(),
+            Cell::from_char('b'),
+        ];
+
+        assert_eq!(
+            split_by_style(&cells),
+            vec![(Style::default(), "a界b".to_string())]
+        );
+    }
+
+    #[test]
+    fn it_draw_images_again_only_when_needed() {
+        let render = |vbuffer: &VirtualBuffer, last_vbuffer: Option<&VirtualBuffer>| {
+            let mut output: Vec<u8> = vec![];
+            TuiRenderer::default().render_vbuffer(&mut output, vbuffer.clone(), last_vbuffer);
+            String::from_utf8(output).unwrap()
+        };
+        let image = |protocol: GraphicsProtocol| TerminalImage {
+            id: 1,
+            width: 2,
+            height: 1,
+            protocol,
+            escape: "<image>".into(),
+        };
+
+        for protocol in [GraphicsProtocol::Kitty, GraphicsProtocol::Sixel] {
+            let mut vbuffer = VirtualBuffer::from_size(6, 2);
+            vbuffer.merge_primitive(&Primitive::Rectangle(2, 0, 2, 1, Cell::from_char('▀')));
+            vbuffer.merge_primitive(&Primitive::Image(2, 0, image(protocol)));
+            assert!(render(&vbuffer, None).contains("<image>"));
+
+            // the cells next to the image change
+            let mut next_vbuffer = vbuffer.clone();
+            next_vbuffer.merge_primitive(&Primitive::from_char(0, 0, 'x'));
+            let output = render(&next_vbuffer, Some(&vbuffer));
+            assert!(output.contains('x'));
+            assert!(!output.contains('▀'));
+            assert!(!output.contains("<image>"));
+
+            // the cells below the image change
+            let mut next_vbuffer = vbuffer.clone();
+            next_vbuffer.merge_primitive(&Primitive::Rectangle(2, 0, 2, 1, Cell::from_char('▄')));
+            next_vbuffer.merge_primitive(&Primitive::Image(2, 0, image(protocol)));
+            let output = render(&next_vbuffer, Some(&vbuffer));
+            assert!(output.contains('▄'));
+
+            match protocol {
+                GraphicsProtocol::Kitty => assert!(output.contains("\x1b_Ga=p,i=1,p=1,c=2,r=1")),
+                GraphicsProtocol::Sixel => assert!(output.contains("<image>")),
+            }
+        }
+    }
+
     #[bench]
     fn bench_split_rows_by_style_one_line(b: &mut Bencher) {
         let vbuffer = make_example_vbuffer();
@@ -3,9 +3,10 @@ use super::style::Style;
 use super::tui_renderer::TuiRenderer;
 use iced_native::layout::Node;
 use iced_native::{
-    layout::Layout, layout::Limits, Element, Point, Rectangle, Renderer, Size, Vector,
+    layout::Layout, layout::Limits, Color, Element, Point, Rectangle, Renderer, Size, Vector,
 };
 use std::cmp;
+use unicode_width::UnicodeWidthChar;
 pub enum RoundDirection {
     Horizontal,
     Vertical,
@@ -84,7 +85,7 @@ fn round_layout_list(
         };
 
         let mut result_position: i16 = match min_next_position {
-            Some(min_position) => cmp::max(min_position as i16, planned_position.round() as i16),
+            Some(min_position) => cmp::max(min_position, planned_position.round() as i16),
             None => planned_position.round() as i16,
         };
 
@@ -164,6 +165,73 @@ where
     (layout_offset, node)
 }
 
+/// Same as [`round_individual_layout`], but keeps the content `inset` cells away
+/// from the edges of the container (e.g. to leave room for a border).
+///
+/// The border takes the first `inset` cells of the padding of the container, so the content
+/// keeps its place when the padding is at least as wide as the border, and the rest of the
+/// padding is left inside the border.
+pub fn round_inset_layout<'a, Message, Renderer>(
+    container_bounds: Rectangle,
+    inset: u16,
+    original_content_layout: Layout<'a>,
+    content: &Element<'_, Message, Renderer>,
+    renderer: &Renderer,
+) -> (Vector, Node)
+where
+    Renderer: iced_native::Renderer,
+{
+    let inset = inset as f32;
+    let original_content_layout_bounds = original_content_layout.bounds();
+    let container_x = container_bounds.x.round();
+    let container_y = container_bounds.y.round();
+
+    let (content_x, content_end_x) = inset_span(
+        container_x,
+        container_bounds.width.round(),
+        original_content_layout_bounds.x.round(),
+        original_content_layout_bounds.width.round(),
+        inset,
+    );
+    let (content_y, content_end_y) = inset_span(
+        container_y,
+        container_bounds.height.round(),
+        original_content_layout_bounds.y.round(),
+        original_content_layout_bounds.height.round(),
+        inset,
+    );
+
+    let limits = Limits::new(
+        Size::ZERO,
+        Size::new(
+            (content_end_x - content_x).max(0.0),
+            (content_end_y - content_y).max(0.0),
+        ),
+    );
+    let mut node = content.layout(renderer, &limits);
+    node.move_to(Point::new(content_x - container_x, content_y - container_y));
+
+    let layout_offset = Vector::new(container_bounds.x, container_bounds.y);
+
+    (layout_offset, node)
+}
+
+/// Returns the start and the end of the content on an axis, only shifted and shrunk where it
+/// would overlap the inset.
+fn inset_span(
+    container_start: f32,
+    container_length: f32,
+    content_start: f32,
+    content_length: f32,
+    inset: f32,
+) -> (f32, f32) {
+    let start = content_start.max(container_start + inset);
+    let end = (content_start + content_length).min(container_start + container_length - inset);
+
+    (start, end.max(start))
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn crop_text_to_bounds(
     content: &str,
     size: Option<Size>,
@@ -232,6 +300,153 @@ pub fn crop_text_to_bounds(
     (primitive_cells, filled_width, filled_height)
 }
 
+/// Returns the width of a character in cells, wide characters taking two cells.
+pub(crate) fn char_width(c: char) -> u16 {
+    c.width().unwrap_or(1).clamp(1, 2) as u16
+}
+
+/// Wraps a line of text to a width in cells, returning the index of the first character
+/// of each row.
+///
+/// When the last row is full, an empty row follows it for the cursor at the end of the line.
+pub(crate) fn wrap_line(line: &str, width: u16) -> Vec<usize> {
+    let mut row_starts = vec![0];
+    let mut row_width = 0;
+
+    if width == 0 {
+        return row_starts;
+    }
+
+    for (index, c) in line.chars().enumerate() {
+        let c_width = char_width(c);
+
+        if row_width > 0 && row_width + c_width > width {
+            row_starts.push(index);
+            row_width = 0;
+        }
+
+        row_width += c_width;
+    }
+
+    if row_width >= width {
+        row_starts.push(line.chars().count());
+    }
+
+    row_starts
+}
+
+/// The blocks filling the eighths of a cell, from the left.
+pub(crate) const HORIZONTAL_EIGHTHS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// The blocks filling the eighths of a cell, from the bottom.
+pub(crate) const VERTICAL_EIGHTHS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// The styles of the filled and the unfilled parts of a bar, e.g. a progress bar or a gauge.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct BarStyle {
+    pub filled: Style,
+    pub unfilled: Style,
+}
+
+impl BarStyle {
+    pub fn fg<C>(mut self, color: C) -> Self
+    where
+        C: Into<Color>,
+    {
+        self.filled = self.filled.bg(color);
+        self
+    }
+
+    pub fn bg<C>(mut self, color: C) -> Self
+    where
+        C: Into<Color>,
+    {
+        self.unfilled = self.unfilled.bg(color);
+        self
+    }
+
+    /// Returns the style of a character drawn over the filled part or not, in the color of
+    /// the other part.
+    pub fn text_style(&self, is_filled: bool) -> Style {
+        let (fg, bg) = match is_filled {
+            true => (self.unfilled, self.filled),
+            false => (self.filled, self.unfilled),
+        };
+
+        Style {
+            fg_color: fg.bg_color,
+            ..bg
+        }
+    }
+}
+
+/// Returns the ratio of the range reached by a value, between 0 and 1.
+pub(crate) fn progress_ratio(range: &std::ops::RangeInclusive<f32>, value: f32) -> f32 {
+    let range_length = range.end() - range.start();
+
+    match range_length > 0.0 {
+        true => ((value - range.start()) / range_length).clamp(0.0, 1.0),
+        false if value >= *range.end() => 1.0,
+        false => 0.0,
+    }
+}
+
+/// Returns the width of a text in cells.
+pub(crate) fn text_width(text: &str) -> u16 {
+    text.chars().map(char_width).sum()
+}
+
+/// Truncates a text to a width in cells, ending it with `…` when it doesn't fit.
+pub(crate) fn truncate_text(text: &str, width: u16) -> String {
+    if text_width(text) <= width {
+        return text.to_string();
+    }
+
+    let mut truncated = String::new();
+    let mut truncated_width = 0;
+
+    for c in text.chars() {
+        if truncated_width + char_width(c) + 1 > width {
+            break;
+        }
+
+        truncated.push(c);
+        truncated_width += char_width(c);
+    }
+
+    if width > 0 {
+        truncated.push('…');
+    }
+
+    truncated
+}
+
+/// Returns the cells of a single line of text, the second cell of wide characters being
+/// left empty.
+pub(crate) fn text_cells(text: &str, x: u16, y: u16, style: Style) -> Vec<Primitive> {
+    let mut cells = Vec::with_capacity(text.len());
+    let mut cell_x = x;
+
+    for c in text.chars().filter(|c| is_printable(*c)) {
+        cells.push(Primitive::Cell(cell_x, y, Cell::from_char(c).style(style)));
+
+        if char_width(c) == 2 {
+            cells.push(Primitive::Cell(
+                cell_x + 1,
+                y,
+                Cell {
+                    content: None,
+                    style,
+                },
+            ));
+        }
+
+        cell_x += char_width(c);
+    }
+
+    cells
+}
+
 fn is_printable(c: char) -> bool {
     c as u32 >= 30
 }
@@ -239,12 +454,12 @@ fn is_printable(c: char) -> bool {
 #[cfg(test)]
 mod tests {
     use super::super::primitives::Primitive;
-    use super::crop_text_to_bounds;
-    use super::{round_layout_list, RoundDirection};
-    use crate::Style;
+    use super::{crop_text_to_bounds, truncate_text, wrap_line};
+    use super::{round_inset_layout, round_layout_list, RoundDirection};
+    use crate::{Style, TuiRenderer};
     use iced_native::{
         layout::{Layout, Node},
-        Point, Rectangle, Size, Vector,
+        Element, Length, Point, Rectangle, Size, Space, Vector,
     };
 
     #[test]
@@ -259,7 +474,7 @@ mod tests {
             Layout::with_offset(Vector::new(121.2, 11.3), &planned_node3),
         ];
 
-        let expected_rectangles = vec![
+        let expected_rectangles = [
             Rectangle {
                 x: 101_f32,
                 y: 11_f32,
@@ -302,7 +517,7 @@ mod tests {
             Layout::with_offset(Vector::new(11.3, 121.2), &planned_node3),
         ];
 
-        let expected_rectangles = vec![
+        let expected_rectangles = [
             Rectangle {
                 x: 11_f32,
                 y: 101_f32,
@@ -345,7 +560,7 @@ mod tests {
             Layout::with_offset(Vector::new(123_f32, 54_f32), &planned_node3),
         ];
 
-        let expected_rectangles = vec![
+        let expected_rectangles = [
             Rectangle {
                 x: 100_f32,
                 y: 10_f32,
@@ -397,7 +612,7 @@ mod tests {
         assert_eq!(width, 5);
         assert_eq!(height, 2);
 
-        let expected_primitives = vec![
+        let expected_primitives = [
             Primitive::from_char(10, 10, 'H'),
             Primitive::from_char(11, 10, 'e'),
             Primitive::from_char(12, 10, 'l'),
@@ -668,4 +883,65 @@ mod tests {
         assert_eq!(height, 3);
         assert_eq!(primitives.len(), expected_primitives.len());
     }
+
+    #[test]
+    fn it_inset_content_inside_border() {
+        let renderer = TuiRenderer::default();
+        let content: Element<'_, (), TuiRenderer> = Space::new(Length::Fill, Length::Fill).into();
+        let container_bounds = Rectangle::new(Point::new(2., 3.), Size::new(10., 5.));
+
+        // without padding, the content is pushed inside the border
+        let original_node = Node::new(Size::new(10., 5.));
+        let original_layout = Layout::with_offset(Vector::new(2., 3.), &original_node);
+        let (offset, node) =
+            round_inset_layout(container_bounds, 1, original_layout, &content, &renderer);
+
+        assert_eq!(
+            Layout::with_offset(offset, &node).bounds(),
+            Rectangle::new(Point::new(3., 4.), Size::new(8., 3.))
+        );
+
+        // a padding as wide as the border keeps the content in place
+        let original_node = Node::new(Size::new(8., 3.));
+        let original_layout = Layout::with_offset(Vector::new(3., 4.), &original_node);
+        let (offset, node) =
+            round_inset_layout(container_bounds, 1, original_layout, &content, &renderer);
+
+        assert_eq!(
+            Layout::with_offset(offset, &node).bounds(),
+            Rectangle::new(Point::new(3., 4.), Size::new(8., 3.))
+        );
+
+        // the rest of a larger padding is left inside the border
+        let shrink: Element<'_, (), TuiRenderer> =
+            Space::new(Length::Units(6), Length::Units(1)).into();
+        let original_node = Node::new(Size::new(6., 1.));
+        let original_layout = Layout::with_offset(Vector::new(4., 5.), &original_node);
+        let (offset, node) =
+            round_inset_layout(container_bounds, 1, original_layout, &shrink, &renderer);
+
+        assert_eq!(
+            Layout::with_offset(offset, &node).bounds(),
+            Rectangle::new(Point::new(4., 5.), Size::new(6., 1.))
+        );
+    }
+
+    #[test]
+    fn it_wrap_line_to_width() {
+        assert_eq!(wrap_line("", 4), vec![0]);
+        assert_eq!(wrap_line("abc", 4), vec![0]);
+        assert_eq!(wrap_line("abcdefghij", 4), vec![0, 4, 8]);
+        // full last row, followed by a row for the cursor
+        assert_eq!(wrap_line("abcdefgh", 4), vec![0, 4, 8]);
+        // wide characters are not split
+        assert_eq!(wrap_line("abc界d", 4), vec![0, 3]);
+    }
+
+    #[test]
+    fn it_truncate_text_to_width() {
+        assert_eq!(truncate_text("hello", 5), "hello");
+        assert_eq!(truncate_text("hello world", 5), "hell…");
+        assert_eq!(truncate_text("界界界", 4), "界…");
+        assert_eq!(truncate_text("hello", 0), "");
+    }
 }
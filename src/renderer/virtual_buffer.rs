@@ -1,12 +1,24 @@
+use super::colors::TermColor;
+use super::graphics::TerminalImage;
 use super::primitives::{Cell, Primitive};
+use super::style::Style;
+use super::utils::char_width;
 use crate::CursorStyle;
 
+/// The line starting the styles of the cells in a styled text.
+pub(crate) const STYLES_HEADER: &str = "---- styles\n";
+/// The line starting the legend of the styles in a styled text.
+pub(crate) const LEGEND_HEADER: &str = "---- legend\n";
+/// The key of the default style in a styled text.
+pub(crate) const DEFAULT_STYLE_KEY: char = '.';
+
 #[derive(Clone, PartialEq)]
 pub struct VirtualBuffer {
     pub width: u16,
     pub height: u16,
     pub rows: Vec<Vec<Cell>>,
     pub cursor_position: Option<(u16, u16, CursorStyle)>,
+    pub images: Vec<(u16, u16, TerminalImage)>,
     pub primitive_hash: u64,
 }
 
@@ -27,6 +39,7 @@ impl VirtualBuffer {
             height,
             primitive_hash: 0,
             cursor_position: None,
+            images: vec![],
         }
     }
 
@@ -39,6 +52,8 @@ impl VirtualBuffer {
             }
             Primitive::Rectangle(start_x, start_y, width, height, fill_cell) => {
                 if !fill_cell.is_empty() {
+                    self.drop_covered_images(*start_x, *start_y, *width, *height);
+
                     for x in *start_x..(*start_x + width) {
                         for y in *start_y..(*start_y + height) {
                             if x < self.width && y < self.height {
@@ -49,20 +64,164 @@ impl VirtualBuffer {
                 }
             }
             Primitive::Cell(x, y, cell) => {
+                if !cell.is_empty() {
+                    self.drop_covered_images(*x, *y, 1, 1);
+                }
+
                 if *x < self.width && *y < self.height {
                     self.rows[*y as usize][*x as usize].merge(*cell);
                 }
             }
             Primitive::CursorPosition(x, y, style) => self.cursor_position = Some((*x, *y, *style)),
+            Primitive::Dim(start_x, start_y, width, height, amount) => {
+                let end_x = start_x.saturating_add(*width).min(self.width);
+                let end_y = start_y.saturating_add(*height).min(self.height);
+                self.drop_covered_images(*start_x, *start_y, *width, *height);
+
+                for y in *start_y..end_y {
+                    for x in *start_x..end_x {
+                        self.rows[y as usize][x as usize].dim(*amount);
+                    }
+                }
+            }
+            Primitive::Image(x, y, image) => {
+                self.drop_covered_images(*x, *y, image.width, image.height);
+                self.images.push((*x, *y, image.clone()));
+            }
+        };
+    }
+
+    /// Drops the images drawn below the area, since they would be printed over it.
+    ///
+    /// The cells drawn below the images, e.g. with half blocks, are shown instead.
+    fn drop_covered_images(&mut self, x: u16, y: u16, width: u16, height: u16) {
+        if self.images.is_empty() {
+            return;
+        }
+
+        let overlaps = |start: u16, length: u16, other_start: u16, other_length: u16| {
+            start < other_start.saturating_add(other_length)
+                && other_start < start.saturating_add(length)
         };
+
+        self.images.retain(|(image_x, image_y, image)| {
+            !(overlaps(x, width, *image_x, image.width)
+                && overlaps(y, height, *image_y, image.height))
+        });
+    }
+
+    /// Returns the characters of the rows, without their trailing spaces.
+    pub fn to_plain_text(&self) -> String {
+        self.rows
+            .iter()
+            .map(|row| format!("{}\n", row_text(row).trim_end()))
+            .collect()
+    }
+
+    /// Returns the characters of the rows, followed by the styles of their cells.
+    ///
+    /// Each style is a key described in a legend at the end, `.` being the default style,
+    /// e.g. `a: fg(Red) bg(#102030) bold underline`.
+    pub fn to_styled_text(&self) -> String {
+        let mut styles: Vec<Style> = vec![];
+        let mut text = self.to_plain_text();
+
+        text.push_str(STYLES_HEADER);
+        for row in &self.rows {
+            for cell in row {
+                if cell.style.is_empty() {
+                    text.push(DEFAULT_STYLE_KEY);
+                    continue;
+                }
+
+                let index = match styles.iter().position(|style| *style == cell.style) {
+                    Some(index) => index,
+                    None => {
+                        styles.push(cell.style);
+                        styles.len() - 1
+                    }
+                };
+                text.push(style_key(index));
+            }
+            text.push('\n');
+        }
+
+        text.push_str(LEGEND_HEADER);
+        for (index, style) in styles.iter().enumerate() {
+            text.push_str(&format!(
+                "{}: {}\n",
+                style_key(index),
+                describe_style(style)
+            ));
+        }
+
+        text
     }
 }
 
+/// Returns the characters of a row, skipping the cells covered by wide characters.
+fn row_text(row: &[Cell]) -> String {
+    let mut text = String::with_capacity(row.len());
+    let mut is_covered = false;
+
+    for cell in row {
+        // like in the terminal, wide characters are printed over the next cell
+        if is_covered {
+            is_covered = false;
+            continue;
+        }
+
+        let c = cell.content.unwrap_or(' ');
+        is_covered = char_width(c) > 1;
+        text.push(c);
+    }
+
+    text
+}
+
+/// Returns the key of a style in a styled text.
+fn style_key(index: usize) -> char {
+    const KEYS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+    match KEYS.chars().nth(index) {
+        Some(key) => key,
+        // then the letters from À, far enough from the surrogates
+        None => char::from_u32(0xC0 + (index - KEYS.len()) as u32).unwrap_or('?'),
+    }
+}
+
+fn describe_style(style: &Style) -> String {
+    let describe_color = |color: TermColor| match color {
+        TermColor::Rgb(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        TermColor::Ansi(ansi_color) => format!("{:?}", ansi_color),
+    };
+
+    let mut attributes = vec![];
+
+    if let Some(fg_color) = style.fg_color {
+        attributes.push(format!("fg({})", describe_color(fg_color)));
+    }
+
+    if let Some(bg_color) = style.bg_color {
+        attributes.push(format!("bg({})", describe_color(bg_color)));
+    }
+
+    if style.is_bold {
+        attributes.push("bold".to_string());
+    }
+
+    if style.is_underlined {
+        attributes.push("underline".to_string());
+    }
+
+    attributes.join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     extern crate test;
 
-    use super::super::colors::TermColor;
+    use super::super::colors::{AnsiColor, TermColor};
     use super::super::primitives::{Cell, Primitive};
     use super::super::style::Style;
     use super::VirtualBuffer;
@@ -82,6 +241,7 @@ mod tests {
                                 fg_color: Some(TermColor::Rgb(x, x + 10_u8, y + 5_u8)),
                                 bg_color: Some(TermColor::Rgb(x, x + 8_u8, y + 7_u8)),
                                 is_bold: x % 2 == 0,
+                                is_underlined: false,
                             },
                         },
                     ));
@@ -92,6 +252,53 @@ mod tests {
         Primitive::Group(primitive_cells)
     }
 
+    #[test]
+    fn it_dim_cells_behind_shadow() {
+        let mut vbuffer = VirtualBuffer::from_size(4, 2);
+        let style = Style {
+            fg_color: Some(TermColor::Rgb(200, 100, 50)),
+            bg_color: Some(TermColor::Ansi(AnsiColor::Blue)),
+            is_bold: false,
+            is_underlined: false,
+        };
+        let cell = Cell::from_char('a').style(style);
+        vbuffer.merge_primitive(&Primitive::Rectangle(0, 0, 4, 2, cell));
+        vbuffer.merge_primitive(&Primitive::Dim(2, 1, 5, 5, 0.5));
+
+        let dimmed_cell = Cell::from_char('a').style(Style {
+            fg_color: Some(TermColor::Rgb(100, 50, 25)),
+            bg_color: Some(TermColor::Rgb(0, 0, 128)),
+            is_bold: false,
+            is_underlined: false,
+        });
+
+        assert_eq!(vbuffer.rows[0][2], cell);
+        assert_eq!(vbuffer.rows[1][1], cell);
+        assert_eq!(vbuffer.rows[1][2], dimmed_cell);
+        assert_eq!(vbuffer.rows[1][3], dimmed_cell);
+    }
+
+    #[test]
+    fn it_serialize_cells_as_text() {
+        let mut vbuffer = VirtualBuffer::from_size(5, 2);
+        let bold = Style::new().bold();
+        let red = Style::new()
+            .fg(AnsiColor::Red)
+            .bg(iced_native::Color::from_rgb8(16, 32, 48));
+        vbuffer.merge_primitive(&Primitive::Group(vec![
+            Primitive::Cell(0, 0, Cell::from_char('a').style(bold)),
+            Primitive::Cell(1, 0, Cell::from_char('界').style(red)),
+            Primitive::Cell(3, 0, Cell::from_char('b').style(bold)),
+            Primitive::Rectangle(1, 1, 2, 1, Cell::default().style(red)),
+        ]));
+
+        assert_eq!(vbuffer.to_plain_text(), "a界b\n\n");
+        assert_eq!(
+            vbuffer.to_styled_text(),
+            "a界b\n\n---- styles\nab.a.\n.bb..\n---- legend\na: bold\nb: fg(Red) bg(#102030)\n"
+        );
+    }
+
     #[bench]
     fn bench_merge_primitive(b: &mut Bencher) {
         let primitive = make_example_primitive();
@@ -112,18 +319,8 @@ mod tests {
 
         b.iter(|| {
             let mut cell_vec: Vec<Cell> = vec![
-                cell_a,
-                cell_b,
-                cell_c,
-                cell_d,
-                cell_a,
-                cell_b,
-                cell_c,
-                cell_d,
-                cell_a,
-                cell_b,
-                cell_c,
-                cell_d,
+                cell_a, cell_b, cell_c, cell_d, cell_a, cell_b, cell_c, cell_d, cell_a, cell_b,
+                cell_c, cell_d,
             ];
 
             for cell in cell_vec.iter_mut() {
@@ -0,0 +1,57 @@
+use crate::ClipboardBackend;
+use crate::GraphicsProtocol;
+use std::time::Duration;
+
+/// The settings of the runtime of an [`Application`](crate::Application).
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub(crate) double_click_interval: Duration,
+    pub(crate) focus_traversal: bool,
+    pub(crate) clipboard: ClipboardBackend,
+    pub(crate) graphics_protocol: Option<GraphicsProtocol>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            double_click_interval: Duration::from_millis(300),
+            focus_traversal: true,
+            clipboard: ClipboardBackend::default(),
+            graphics_protocol: GraphicsProtocol::detect(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum time between clicks for them to be detected as double or triple clicks.
+    pub fn double_click_interval(mut self, interval: Duration) -> Self {
+        self.double_click_interval = interval;
+        self
+    }
+
+    /// Sets whether Tab and Shift+Tab move the focus between the widgets, and Enter and
+    /// Space activate the focused one.
+    pub fn focus_traversal(mut self, focus_traversal: bool) -> Self {
+        self.focus_traversal = focus_traversal;
+        self
+    }
+
+    /// Sets the backend of the clipboard used by the widgets.
+    pub fn clipboard(mut self, clipboard: ClipboardBackend) -> Self {
+        self.clipboard = clipboard;
+        self
+    }
+
+    /// Sets the protocol drawing the images in pixels, detected from the environment by
+    /// default.
+    ///
+    /// Without protocol, the images are drawn with half blocks.
+    pub fn graphics_protocol(mut self, graphics_protocol: Option<GraphicsProtocol>) -> Self {
+        self.graphics_protocol = graphics_protocol;
+        self
+    }
+}
@@ -0,0 +1,314 @@
+use crate::renderer::utils::char_width;
+use crate::renderer::virtual_buffer::{DEFAULT_STYLE_KEY, LEGEND_HEADER, STYLES_HEADER};
+use crate::VirtualBuffer;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// The environment variable making the snapshots be written instead of compared.
+const UPDATE_SNAPSHOTS: &str = "UPDATE_SNAPSHOTS";
+
+/// The line starting a snapshot, followed by the size of the screen, e.g. `---- screen 8x2`.
+const SCREEN_HEADER: &str = "---- screen ";
+
+/// The maximum number of cells listed in the difference with a snapshot.
+const MAX_LISTED_CELLS: usize = 20;
+
+/// Asserts that a [`VirtualBuffer`] matches the snapshot of the given name, stored in the
+/// `tests/snapshots` directory of the package.
+///
+/// The snapshot is written when the `UPDATE_SNAPSHOTS` environment variable is set, and
+/// a missing snapshot fails otherwise. On mismatch, the cells that differ are listed.
+///
+/// ```ignore
+/// let headless = Headless::<MyApp>::new(20, 5);
+/// assert_screen_snapshot!(headless.vbuffer(), "initial_screen");
+/// ```
+#[macro_export]
+macro_rules! assert_screen_snapshot {
+    ($vbuffer:expr, $name:expr $(,)?) => {{
+        let path = $crate::snapshot_path(env!("CARGO_MANIFEST_DIR"), $name);
+
+        if let Err(message) = $crate::compare_screen_snapshot(&$vbuffer, &path) {
+            panic!("{}", message);
+        }
+    }};
+}
+
+/// Compares a [`VirtualBuffer`] with the size and the styled text stored in a snapshot
+/// file, returning the cells that differ.
+///
+/// The snapshot is written instead when the `UPDATE_SNAPSHOTS` environment variable is set.
+pub fn compare_screen_snapshot<P>(vbuffer: &VirtualBuffer, path: P) -> Result<(), String>
+where
+    P: AsRef<Path>,
+{
+    let update = std::env::var_os(UPDATE_SNAPSHOTS).is_some();
+    compare_or_update_snapshot(vbuffer, path.as_ref(), update)
+}
+
+fn compare_or_update_snapshot(
+    vbuffer: &VirtualBuffer,
+    path: &Path,
+    update: bool,
+) -> Result<(), String> {
+    let actual = vbuffer.to_styled_text();
+    let size = (vbuffer.width, vbuffer.height);
+
+    if update {
+        return write_snapshot(path, size, &actual);
+    }
+
+    let stored = match std::fs::read_to_string(path) {
+        Ok(stored) => stored,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Err(format!(
+                "The snapshot {} doesn't exist, the screen is:\n{}Set {}=1 to write it.",
+                path.display(),
+                actual,
+                UPDATE_SNAPSHOTS
+            ))
+        }
+        Err(err) => {
+            return Err(format!(
+                "Can't read the snapshot {}: {}",
+                path.display(),
+                err
+            ))
+        }
+    };
+
+    let (expected_size, expected) = parse_header(&stored);
+
+    if expected_size != Some(size) {
+        return Err(format!(
+            "The screen is {}x{}, the snapshot {} is {}\nSet {}=1 to update the snapshot.",
+            size.0,
+            size.1,
+            path.display(),
+            expected_size.map_or(String::from("of unknown size"), |(width, height)| {
+                format!("{}x{}", width, height)
+            }),
+            UPDATE_SNAPSHOTS
+        ));
+    }
+
+    let differences = diff_cells(&parse_cells(expected), &parse_cells(&actual));
+
+    if differences.is_empty() {
+        return Ok(());
+    }
+
+    let mut message = format!(
+        "The screen doesn't match the snapshot {} ({} different cells):\n",
+        path.display(),
+        differences.len()
+    );
+
+    for difference in differences.iter().take(MAX_LISTED_CELLS) {
+        message.push_str(&format!("  {}\n", difference));
+    }
+
+    if differences.len() > MAX_LISTED_CELLS {
+        message.push_str(&format!(
+            "  and {} more\n",
+            differences.len() - MAX_LISTED_CELLS
+        ));
+    }
+
+    message.push_str(&format!(
+        "The screen is:\n{}Set {}=1 to update the snapshot.",
+        actual, UPDATE_SNAPSHOTS
+    ));
+
+    Err(message)
+}
+
+/// Returns the path of a snapshot used by [`assert_screen_snapshot`], in the
+/// `tests/snapshots` directory of the package.
+#[doc(hidden)]
+pub fn snapshot_path(manifest_dir: &str, name: &str) -> PathBuf {
+    Path::new(manifest_dir)
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{}.snap", name))
+}
+
+fn write_snapshot(
+    path: &Path,
+    (width, height): (u16, u16),
+    styled_text: &str,
+) -> Result<(), String> {
+    let snapshot = format!("{}{}x{}\n{}", SCREEN_HEADER, width, height, styled_text);
+
+    path.parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(path, snapshot))
+        .map_err(|err| format!("Can't write the snapshot {}: {}", path.display(), err))
+}
+
+/// Returns the size of the screen written in the header of a snapshot, if any, and the
+/// styled text following it.
+fn parse_header(snapshot: &str) -> (Option<(u16, u16)>, &str) {
+    let header = snapshot
+        .strip_prefix(SCREEN_HEADER)
+        .and_then(|rest| rest.split_once('\n'));
+
+    match header {
+        Some((size, styled_text)) => {
+            let size = size.split_once('x').and_then(|(width, height)| {
+                Some((width.trim().parse().ok()?, height.trim().parse().ok()?))
+            });
+
+            (size, styled_text)
+        }
+        None => (None, snapshot),
+    }
+}
+
+/// A cell of a styled text, with the description of its style.
+type StyledCell = (char, String);
+
+/// Returns the cells of a styled text, ignoring its missing trailing spaces.
+fn parse_cells(styled_text: &str) -> Vec<Vec<StyledCell>> {
+    let (text, styles) = styled_text
+        .split_once(STYLES_HEADER)
+        .unwrap_or((styled_text, ""));
+    let (styles, legend) = styles.split_once(LEGEND_HEADER).unwrap_or((styles, ""));
+
+    let legend: HashMap<char, &str> = legend
+        .lines()
+        .filter_map(|line| line.split_once(": "))
+        .filter_map(|(key, style)| Some((key.chars().next()?, style)))
+        .collect();
+
+    let text_rows: Vec<Vec<char>> = text
+        .lines()
+        .map(|line| {
+            // the cell after a wide character is covered by it
+            line.chars()
+                .flat_map(|c| match char_width(c) > 1 {
+                    true => vec![c, ' '],
+                    false => vec![c],
+                })
+                .collect()
+        })
+        .collect();
+    let style_rows: Vec<Vec<char>> = styles.lines().map(|line| line.chars().collect()).collect();
+
+    (0..text_rows.len().max(style_rows.len()))
+        .map(|y| {
+            let text_row = text_rows.get(y).map(Vec::as_slice).unwrap_or_default();
+            let style_row = style_rows.get(y).map(Vec::as_slice).unwrap_or_default();
+
+            (0..text_row.len().max(style_row.len()))
+                .map(|x| {
+                    let c = text_row.get(x).copied().unwrap_or(' ');
+                    let style = match style_row.get(x).copied().unwrap_or(DEFAULT_STYLE_KEY) {
+                        DEFAULT_STYLE_KEY => String::new(),
+                        key => legend.get(&key).copied().unwrap_or("unknown").to_string(),
+                    };
+
+                    (c, style)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the description of the cells that differ.
+fn diff_cells(expected: &[Vec<StyledCell>], actual: &[Vec<StyledCell>]) -> Vec<String> {
+    let default_cell: StyledCell = (' ', String::new());
+    let describe = |(c, style): &StyledCell| match style.is_empty() {
+        true => format!("{:?}", c),
+        false => format!("{:?} ({})", c, style),
+    };
+
+    let mut differences = vec![];
+
+    for y in 0..expected.len().max(actual.len()) {
+        let expected_row = expected.get(y).map(Vec::as_slice).unwrap_or_default();
+        let actual_row = actual.get(y).map(Vec::as_slice).unwrap_or_default();
+
+        for x in 0..expected_row.len().max(actual_row.len()) {
+            let expected_cell = expected_row.get(x).unwrap_or(&default_cell);
+            let actual_cell = actual_row.get(x).unwrap_or(&default_cell);
+
+            if expected_cell != actual_cell {
+                differences.push(format!(
+                    "row {}, column {}: expected {}, found {}",
+                    y,
+                    x,
+                    describe(expected_cell),
+                    describe(actual_cell)
+                ));
+            }
+        }
+    }
+
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compare_or_update_snapshot;
+    use crate::renderer::primitives::{Cell, Primitive};
+    use crate::{Style, VirtualBuffer};
+
+    fn make_vbuffer(text: &str) -> VirtualBuffer {
+        let mut vbuffer = VirtualBuffer::from_size(8, 2);
+        let cells = text
+            .chars()
+            .enumerate()
+            .map(|(x, c)| {
+                Primitive::Cell(x as u16, 0, Cell::from_char(c).style(Style::new().bold()))
+            })
+            .collect();
+        vbuffer.merge_primitive(&Primitive::Group(cells));
+        vbuffer
+    }
+
+    #[test]
+    fn it_match_stored_snapshot() {
+        crate::assert_screen_snapshot!(make_vbuffer("Menu"), "bold_menu");
+    }
+
+    #[test]
+    fn it_list_the_cells_differing_from_the_snapshot() {
+        let path = std::env::temp_dir().join(format!(
+            "iced_tui_snapshot_{}/screen.snap",
+            std::process::id()
+        ));
+
+        // a missing snapshot fails until it's written
+        let message = compare_or_update_snapshot(&make_vbuffer("Open"), &path, false).unwrap_err();
+        assert!(message.contains("screen.snap doesn't exist, the screen is:\nOpen\n"));
+        assert_eq!(
+            compare_or_update_snapshot(&make_vbuffer("Open"), &path, true),
+            Ok(())
+        );
+        assert_eq!(
+            compare_or_update_snapshot(&make_vbuffer("Open"), &path, false),
+            Ok(())
+        );
+
+        // trailing spaces removed from the file don't matter
+        let stored = std::fs::read_to_string(&path).unwrap();
+        assert!(stored.starts_with("---- screen 8x2\nOpen\n\n---- styles\naaaa....\n........\n"));
+        std::fs::write(&path, stored.replace("........", "")).unwrap();
+        assert_eq!(
+            compare_or_update_snapshot(&make_vbuffer("Open"), &path, false),
+            Ok(())
+        );
+
+        let message = compare_or_update_snapshot(&make_vbuffer("Opens"), &path, false).unwrap_err();
+        assert!(message.contains(" (1 different cells):\n  row 0, column 4: expected ' ', found 's' (bold)\nThe screen is:\nOpens\n"));
+
+        // a screen of another size doesn't match, even where its cells are blank
+        let message =
+            compare_or_update_snapshot(&VirtualBuffer::from_size(8, 3), &path, false).unwrap_err();
+        assert!(message.starts_with("The screen is 8x3, the snapshot "));
+        assert!(message.contains("screen.snap is 8x2\n"));
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,236 @@
+//! Compare values with vertical bars.
+use super::chart::{bar_block, ChartStyle, Scale};
+use crate::renderer::primitives::{Cell, Primitive};
+use crate::renderer::utils::{text_cells, text_width, truncate_text};
+use crate::{Style, TuiRenderer};
+use iced_native::{layout, Element, Hasher, Layout, Length, Point, Rectangle, Size, Widget};
+
+/// A chart with a vertical bar per value, above its label.
+///
+/// The tops of the bars are drawn with `▁▂▃▄▅▆▇█` blocks, to an eighth of a cell.
+pub struct BarChart {
+    bars: Vec<(String, f64)>,
+    min: f64,
+    max: Option<f64>,
+    bar_width: u16,
+    bar_gap: u16,
+    width: Length,
+    height: Length,
+    style: ChartStyle,
+}
+
+impl BarChart {
+    pub fn new<T>(bars: Vec<(T, f64)>) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            bars: bars
+                .into_iter()
+                .map(|(label, value)| (label.into(), value))
+                .collect(),
+            min: 0.0,
+            max: None,
+            bar_width: 3,
+            bar_gap: 1,
+            width: Length::Shrink,
+            height: Length::Fill,
+            style: ChartStyle::default(),
+        }
+    }
+
+    /// Sets the value at the bottom of the bars, 0 by default.
+    pub fn min(mut self, min: f64) -> Self {
+        self.min = min;
+        self
+    }
+
+    /// Sets the value at the top of the bars, the maximum of the values by default.
+    pub fn max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    /// Sets the width of the bars and of their labels.
+    pub fn bar_width(mut self, bar_width: u16) -> Self {
+        self.bar_width = bar_width;
+        self
+    }
+
+    /// Sets the space between the bars.
+    pub fn bar_gap(mut self, bar_gap: u16) -> Self {
+        self.bar_gap = bar_gap;
+        self
+    }
+
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn style(mut self, style: ChartStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn has_labels(&self) -> bool {
+        self.bars.iter().any(|(label, _)| !label.is_empty())
+    }
+
+    fn intrinsic_width(&self) -> u16 {
+        match self.bars.len() as u16 {
+            0 => 0,
+            count => count * self.bar_width + (count - 1) * self.bar_gap,
+        }
+    }
+}
+
+impl<Message> Widget<Message, TuiRenderer> for BarChart {
+    fn width(&self) -> Length {
+        self.width
+    }
+
+    fn height(&self) -> Length {
+        self.height
+    }
+
+    fn layout(&self, _renderer: &TuiRenderer, limits: &layout::Limits) -> layout::Node {
+        let label_height = if self.has_labels() { 1.0 } else { 0.0 };
+        let size = limits
+            .width(self.width)
+            .height(self.height)
+            .resolve(Size::new(self.intrinsic_width() as f32, 1.0 + label_height));
+
+        layout::Node::new(size)
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        use std::hash::Hash;
+
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+
+        self.width.hash(state);
+        self.height.hash(state);
+        self.intrinsic_width().hash(state);
+        self.has_labels().hash(state);
+    }
+
+    fn draw(
+        &self,
+        _renderer: &mut TuiRenderer,
+        _defaults: &Style,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+        _viewport: &Rectangle,
+    ) -> Primitive {
+        let bounds = layout.bounds();
+        let x = bounds.x.round() as u16;
+        let y = bounds.y.round() as u16;
+        let width = bounds.width.round() as u16;
+        let height = bounds.height.round() as u16;
+
+        let label_height = if self.has_labels() { 1 } else { 0 };
+        let bars_height = height.saturating_sub(label_height);
+
+        let values: Vec<f64> = self.bars.iter().map(|(_, value)| *value).collect();
+        let scale = Scale::new(&values, Some(self.min), self.max);
+        let mut primitives = vec![];
+
+        for (index, (label, value)) in self.bars.iter().enumerate() {
+            let start = index as u16 * (self.bar_width + self.bar_gap);
+
+            if start >= width {
+                break;
+            }
+
+            let bar_width = self.bar_width.min(width - start);
+            let eighths = scale.eighths(*value, bars_height);
+            let style = self.style.value_style(*value);
+
+            for row in 0..bars_height {
+                if let Some(block) = bar_block(eighths, row) {
+                    primitives.push(Primitive::Rectangle(
+                        x + start,
+                        y + bars_height - 1 - row,
+                        bar_width,
+                        1,
+                        Cell::from_char(block).style(style),
+                    ));
+                }
+            }
+
+            if label_height > 0 && height > 0 {
+                let label = truncate_text(label, bar_width);
+                let offset = (bar_width - text_width(&label)) / 2;
+
+                primitives.extend(text_cells(
+                    &label,
+                    x + start + offset,
+                    y + height - 1,
+                    self.style.label,
+                ));
+            }
+        }
+
+        Primitive::Group(primitives)
+    }
+}
+
+impl<'a, Message> From<BarChart> for Element<'a, Message, TuiRenderer>
+where
+    Message: 'a,
+{
+    fn from(bar_chart: BarChart) -> Self {
+        Element::new(bar_chart)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BarChart;
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::{ChartStyle, Style, TuiRenderer};
+    use iced_native::{Cache, Element, Point, Size, UserInterface};
+
+    #[test]
+    fn it_draw_bars_with_labels() {
+        let mut renderer = TuiRenderer::default();
+        let bar_chart: Element<'_, (), TuiRenderer> = BarChart::new(vec![
+            ("cpu", 50.0),
+            ("mem", 25.0),
+            ("disk", 100.0),
+            ("net", 0.0),
+        ])
+        .max(100.0)
+        .style(ChartStyle::new().threshold(90.0, Style::new().bold()))
+        .into();
+        let mut ui = UserInterface::build(
+            bar_chart,
+            Size::new(16., 3.),
+            Cache::default(),
+            &mut renderer,
+        );
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(16, 3);
+        vbuffer.merge_primitive(&primitive);
+
+        let lines: Vec<String> = vbuffer
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.content.unwrap_or(' ')).collect())
+            .collect();
+        assert_eq!(
+            lines,
+            vec!["        ███     ", "███ ▄▄▄ ███     ", "cpu mem di… net "]
+        );
+        assert!(!vbuffer.rows[1][0].style.is_bold);
+        assert!(vbuffer.rows[1][8].style.is_bold);
+    }
+}
@@ -0,0 +1,551 @@
+//! Draw points, lines and shapes at a finer resolution than the cells.
+use crate::renderer::primitives::{Cell, Primitive};
+use crate::{Style, TuiRenderer};
+use iced_native::{layout, Element, Hasher, Layout, Length, Point, Rectangle, Size, Widget};
+
+/// A surface where shapes are plotted on a grid of dots, several dots per cell.
+///
+/// The shapes are placed with coordinates going right and up, between the bounds of the
+/// axes. By default, the bounds are the dots of the canvas, starting at the bottom left.
+pub struct Canvas {
+    shapes: Vec<(Shape, Style)>,
+    resolution: Resolution,
+    x_bounds: Option<(f64, f64)>,
+    y_bounds: Option<(f64, f64)>,
+    width: Length,
+    height: Length,
+}
+
+impl Canvas {
+    pub fn new() -> Self {
+        Self {
+            shapes: vec![],
+            resolution: Resolution::Braille,
+            x_bounds: None,
+            y_bounds: None,
+            width: Length::Fill,
+            height: Length::Fill,
+        }
+    }
+
+    /// Adds a shape, drawn over the shapes added before.
+    pub fn push(mut self, shape: Shape, style: Style) -> Self {
+        self.shapes.push((shape, style));
+        self
+    }
+
+    pub fn resolution(mut self, resolution: Resolution) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    /// Sets the coordinates at the left and right edges.
+    pub fn x_bounds(mut self, min: f64, max: f64) -> Self {
+        self.x_bounds = Some((min, max));
+        self
+    }
+
+    /// Sets the coordinates at the bottom and top edges.
+    pub fn y_bounds(mut self, min: f64, max: f64) -> Self {
+        self.y_bounds = Some((min, max));
+        self
+    }
+
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+}
+
+impl Default for Canvas {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The number of dots in a cell of a [`Canvas`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Resolution {
+    /// 2x4 dots per cell, drawn with braille patterns in a single style per cell.
+    Braille,
+    /// 1x2 dots per cell, drawn with `▀` and `▄` blocks with a style per dot.
+    HalfBlock,
+}
+
+impl Resolution {
+    fn dots_per_cell(&self) -> (usize, usize) {
+        match self {
+            Resolution::Braille => (2, 4),
+            Resolution::HalfBlock => (1, 2),
+        }
+    }
+}
+
+/// A shape drawn on a [`Canvas`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    Point(f64, f64),
+    /// Several points, e.g. the samples of a series.
+    Points(Vec<(f64, f64)>),
+    Line(f64, f64, f64, f64),
+    /// Lines joining the points, e.g. to plot a series.
+    Path(Vec<(f64, f64)>),
+    /// The outline of a rectangle, from its bottom left corner and its size.
+    Rectangle(f64, f64, f64, f64),
+    /// The outline of a circle, from its center and its radius.
+    Circle(f64, f64, f64),
+}
+
+/// Clips a segment to an area given by its min and max corners, with the Liang–Barsky
+/// algorithm. Returns `None` when the segment is out of the area or not finite.
+fn clip_segment(
+    (x1, y1): (f64, f64),
+    (x2, y2): (f64, f64),
+    ((min_x, min_y), (max_x, max_y)): ((f64, f64), (f64, f64)),
+) -> Option<((f64, f64), (f64, f64))> {
+    let (dx, dy) = (x2 - x1, y2 - y1);
+
+    if ![x1, y1, dx, dy].iter().all(|value| value.is_finite()) {
+        return None;
+    }
+
+    let (mut start, mut end) = (0.0_f64, 1.0_f64);
+
+    // the distances to each edge, along the segment and from its start
+    for (along, distance) in [
+        (-dx, x1 - min_x),
+        (dx, max_x - x1),
+        (-dy, y1 - min_y),
+        (dy, max_y - y1),
+    ] {
+        if along == 0.0 {
+            if distance < 0.0 {
+                return None;
+            }
+        } else {
+            let ratio = distance / along;
+
+            if along < 0.0 {
+                start = start.max(ratio);
+            } else {
+                end = end.min(ratio);
+            }
+        }
+    }
+
+    match start <= end {
+        true => Some((
+            (x1 + start * dx, y1 + start * dy),
+            (x1 + end * dx, y1 + end * dy),
+        )),
+        false => None,
+    }
+}
+
+/// The dots of a canvas, with the style of each dot drawn.
+struct Grid {
+    width: usize,
+    height: usize,
+    dots: Vec<Option<Style>>,
+    x_bounds: (f64, f64),
+    y_bounds: (f64, f64),
+}
+
+impl Grid {
+    fn new(width: usize, height: usize, x_bounds: (f64, f64), y_bounds: (f64, f64)) -> Self {
+        Self {
+            width,
+            height,
+            dots: vec![None; width * height],
+            x_bounds,
+            y_bounds,
+        }
+    }
+
+    /// Returns the position of the coordinates on the grid, from its top left corner.
+    fn position(&self, x: f64, y: f64) -> (f64, f64) {
+        let scale = |value: f64, (min, max): (f64, f64), dots: usize| match max > min {
+            true => (value - min) / (max - min) * dots.saturating_sub(1) as f64,
+            false => 0.0,
+        };
+
+        (
+            scale(x, self.x_bounds, self.width),
+            self.height.saturating_sub(1) as f64 - scale(y, self.y_bounds, self.height),
+        )
+    }
+
+    fn set(&mut self, x: i64, y: i64, style: Style) {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            self.dots[y as usize * self.width + x as usize] = Some(style);
+        }
+    }
+
+    fn get(&self, x: usize, y: usize) -> Option<Style> {
+        self.dots.get(y * self.width + x).copied().flatten()
+    }
+
+    fn point(&mut self, x: f64, y: f64, style: Style) {
+        let (x, y) = self.position(x, y);
+
+        // NaN would be cast to 0, the other coordinates out of the grid are skipped by `set`
+        if x.is_finite() && y.is_finite() {
+            self.set(x.round() as i64, y.round() as i64, style);
+        }
+    }
+
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), style: Style) {
+        let from = self.position(from.0, from.1);
+        let to = self.position(to.0, to.1);
+        self.line_dots(from, to, style);
+    }
+
+    /// Draws a line between two positions on the grid, clipped to the grid first.
+    fn line_dots(&mut self, from: (f64, f64), to: (f64, f64), style: Style) {
+        // the dots are centered on the integer positions
+        let area = (
+            (-0.5, -0.5),
+            (self.width as f64 - 0.5, self.height as f64 - 0.5),
+        );
+        let ((x1, y1), (x2, y2)) = match clip_segment(from, to, area) {
+            Some(segment) => segment,
+            None => return,
+        };
+        let (mut x, mut y) = (x1.round() as i64, y1.round() as i64);
+        let (x2, y2) = (x2.round() as i64, y2.round() as i64);
+
+        // Bresenham's algorithm
+        let dx = (x2 - x).abs();
+        let dy = -(y2 - y).abs();
+        let step_x = if x < x2 { 1 } else { -1 };
+        let step_y = if y < y2 { 1 } else { -1 };
+        let mut error = dx + dy;
+
+        loop {
+            self.set(x, y, style);
+
+            if x == x2 && y == y2 {
+                break;
+            }
+
+            let double_error = 2 * error;
+
+            if double_error >= dy {
+                error += dy;
+                x += step_x;
+            }
+
+            if double_error <= dx {
+                error += dx;
+                y += step_y;
+            }
+        }
+    }
+
+    fn circle(&mut self, x: f64, y: f64, radius: f64, style: Style) {
+        // the largest number of sides of the polygon drawn for a circle
+        const MAX_SAMPLES: f64 = 4096.0;
+
+        let (center_x, center_y) = self.position(x, y);
+        let (edge_x, edge_y) = self.position(x + radius, y + radius);
+        let radius_x = (edge_x - center_x).abs();
+        let radius_y = (edge_y - center_y).abs();
+
+        if ![center_x, center_y, radius_x, radius_y]
+            .iter()
+            .all(|value| value.is_finite())
+        {
+            return;
+        }
+
+        // the circle is entirely out of the grid
+        if center_x + radius_x < -0.5
+            || center_y + radius_y < -0.5
+            || center_x - radius_x > self.width as f64 - 0.5
+            || center_y - radius_y > self.height as f64 - 0.5
+        {
+            return;
+        }
+
+        // enough sides for the polygon to follow the outline closely
+        let samples = (radius_x.max(radius_y) * std::f64::consts::TAU)
+            .ceil()
+            .clamp(8.0, MAX_SAMPLES) as usize;
+        let vertex = |sample: usize| {
+            let angle = sample as f64 / samples as f64 * std::f64::consts::TAU;
+            (
+                center_x + radius_x * angle.cos(),
+                center_y - radius_y * angle.sin(),
+            )
+        };
+
+        for sample in 0..samples {
+            self.line_dots(vertex(sample), vertex(sample + 1), style);
+        }
+    }
+
+    fn draw(&mut self, shape: &Shape, style: Style) {
+        match shape {
+            Shape::Point(x, y) => self.point(*x, *y, style),
+            Shape::Points(points) => {
+                for (x, y) in points {
+                    self.point(*x, *y, style);
+                }
+            }
+            Shape::Line(x1, y1, x2, y2) => self.line((*x1, *y1), (*x2, *y2), style),
+            Shape::Path(points) => {
+                for pair in points.windows(2) {
+                    self.line(pair[0], pair[1], style);
+                }
+
+                if let [(x, y)] = points.as_slice() {
+                    self.point(*x, *y, style);
+                }
+            }
+            Shape::Rectangle(x, y, width, height) => {
+                let corners = [
+                    (*x, *y),
+                    (x + width, *y),
+                    (x + width, y + height),
+                    (*x, y + height),
+                ];
+
+                for index in 0..corners.len() {
+                    self.line(corners[index], corners[(index + 1) % corners.len()], style);
+                }
+            }
+            Shape::Circle(x, y, radius) => self.circle(*x, *y, *radius, style),
+        }
+    }
+
+    /// Returns the cell showing the dots of the given cell.
+    fn cell(&self, resolution: Resolution, column: usize, row: usize) -> Option<Cell> {
+        match resolution {
+            Resolution::Braille => {
+                // the bits of the dots of a braille pattern, by column and row
+                const DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+                let mut pattern = 0;
+                let mut style = None;
+
+                for (dot_x, column_dots) in DOTS.iter().enumerate() {
+                    for (dot_y, dot) in column_dots.iter().enumerate() {
+                        if let Some(dot_style) = self.get(column * 2 + dot_x, row * 4 + dot_y) {
+                            pattern |= dot;
+                            style = Some(dot_style);
+                        }
+                    }
+                }
+
+                let c = char::from_u32(0x2800 + pattern)?;
+                style.map(|style| Cell::from_char(c).style(style))
+            }
+            Resolution::HalfBlock => {
+                match (self.get(column, row * 2), self.get(column, row * 2 + 1)) {
+                    (Some(top), Some(bottom)) if top == bottom => {
+                        Some(Cell::from_char('█').style(top))
+                    }
+                    (Some(top), Some(bottom)) => Some(Cell::from_char('▀').style(Style {
+                        bg_color: bottom.fg_color,
+                        ..top
+                    })),
+                    (Some(top), None) => Some(Cell::from_char('▀').style(top)),
+                    (None, Some(bottom)) => Some(Cell::from_char('▄').style(bottom)),
+                    (None, None) => None,
+                }
+            }
+        }
+    }
+}
+
+impl<Message> Widget<Message, TuiRenderer> for Canvas {
+    fn width(&self) -> Length {
+        self.width
+    }
+
+    fn height(&self) -> Length {
+        self.height
+    }
+
+    fn layout(&self, _renderer: &TuiRenderer, limits: &layout::Limits) -> layout::Node {
+        let size = limits
+            .width(self.width)
+            .height(self.height)
+            .resolve(Size::ZERO);
+
+        layout::Node::new(size)
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        use std::hash::Hash;
+
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+
+        self.width.hash(state);
+        self.height.hash(state);
+    }
+
+    fn draw(
+        &self,
+        _renderer: &mut TuiRenderer,
+        _defaults: &Style,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+        _viewport: &Rectangle,
+    ) -> Primitive {
+        let bounds = layout.bounds();
+        let x = bounds.x.round() as u16;
+        let y = bounds.y.round() as u16;
+        let columns = bounds.width.round() as usize;
+        let rows = bounds.height.round() as usize;
+
+        let (dots_x, dots_y) = self.resolution.dots_per_cell();
+        let (width, height) = (columns * dots_x, rows * dots_y);
+        let mut grid = Grid::new(
+            width,
+            height,
+            self.x_bounds
+                .unwrap_or((0.0, width.saturating_sub(1) as f64)),
+            self.y_bounds
+                .unwrap_or((0.0, height.saturating_sub(1) as f64)),
+        );
+
+        for (shape, style) in &self.shapes {
+            grid.draw(shape, *style);
+        }
+
+        let mut primitives = vec![];
+
+        for row in 0..rows {
+            for column in 0..columns {
+                if let Some(cell) = grid.cell(self.resolution, column, row) {
+                    primitives.push(Primitive::Cell(x + column as u16, y + row as u16, cell));
+                }
+            }
+        }
+
+        Primitive::Group(primitives)
+    }
+}
+
+impl<'a, Message> From<Canvas> for Element<'a, Message, TuiRenderer>
+where
+    Message: 'a,
+{
+    fn from(canvas: Canvas) -> Self {
+        Element::new(canvas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Canvas, Resolution, Shape};
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::{Style, TuiRenderer};
+    use iced_native::{Cache, Color, Element, Point, Size, UserInterface};
+
+    fn draw(canvas: Canvas, width: u16, height: u16) -> VirtualBuffer {
+        let mut renderer = TuiRenderer::default();
+        let canvas: Element<'_, (), TuiRenderer> = canvas.into();
+        let mut ui = UserInterface::build(
+            canvas,
+            Size::new(width as f32, height as f32),
+            Cache::default(),
+            &mut renderer,
+        );
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(width, height);
+        vbuffer.merge_primitive(&primitive);
+        vbuffer
+    }
+
+    fn lines(vbuffer: &VirtualBuffer) -> Vec<String> {
+        vbuffer
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.content.unwrap_or(' ')).collect())
+            .collect()
+    }
+
+    #[test]
+    fn it_plot_braille_dots() {
+        let canvas = Canvas::new()
+            .push(Shape::Line(0.0, 0.0, 3.0, 3.0), Style::new())
+            .push(Shape::Rectangle(4.0, 0.0, 3.0, 7.0), Style::new());
+
+        assert_eq!(lines(&draw(canvas, 4, 2)), vec!["  ⡏⢹", "⡠⠊⣇⣸"]);
+
+        let canvas = Canvas::new()
+            .x_bounds(-1.0, 1.0)
+            .y_bounds(-1.0, 1.0)
+            .push(Shape::Circle(0.0, 0.0, 1.0), Style::new());
+
+        assert_eq!(lines(&draw(canvas, 3, 2)), vec!["⡜⠉⢣", "⢣⣀⡜"]);
+    }
+
+    #[test]
+    fn it_plot_half_blocks() {
+        let red = Style::new().fg(Color::from_rgb(1.0, 0.0, 0.0));
+        let blue = Style::new().fg(Color::from_rgb(0.0, 0.0, 1.0));
+        let canvas = Canvas::new()
+            .resolution(Resolution::HalfBlock)
+            .push(Shape::Path(vec![(0.0, 3.0), (2.0, 3.0)]), red)
+            .push(Shape::Line(1.0, 0.0, 1.0, 2.0), blue);
+
+        let vbuffer = draw(canvas, 3, 2);
+
+        assert_eq!(lines(&vbuffer), vec!["▀▀▀", " █ "]);
+        assert_eq!(vbuffer.rows[0][0].style, red);
+        assert_eq!(
+            vbuffer.rows[0][1].style,
+            Style {
+                bg_color: blue.fg_color,
+                ..red
+            }
+        );
+        assert_eq!(vbuffer.rows[1][1].style, blue);
+    }
+
+    #[test]
+    fn it_clip_shapes_to_the_grid() {
+        let canvas = |shape: Shape| {
+            let canvas = Canvas::new()
+                .x_bounds(0.0, 3.0)
+                .y_bounds(0.0, 3.0)
+                .push(shape, Style::new());
+            lines(&draw(canvas, 2, 1))
+        };
+
+        assert_eq!(canvas(Shape::Line(0.0, 0.0, 3.0, 3.0)), vec!["⡠⠊"]);
+        assert_eq!(
+            canvas(Shape::Line(-1e6, -1e6, 1e6, 1e6)),
+            canvas(Shape::Line(0.0, 0.0, 3.0, 3.0))
+        );
+        assert_eq!(canvas(Shape::Line(-10.0, 5.0, 10.0, 5.0)), vec!["  "]);
+        assert_eq!(
+            canvas(Shape::Line(f64::MIN, 0.0, f64::MAX, 0.0)),
+            vec!["  "]
+        );
+
+        // not finite coordinates and circles around the grid draw nothing
+        assert_eq!(canvas(Shape::Point(f64::NAN, 0.0)), vec!["  "]);
+        assert_eq!(canvas(Shape::Line(0.0, 0.0, f64::NAN, 3.0)), vec!["  "]);
+        assert_eq!(
+            canvas(Shape::Line(0.0, 0.0, f64::INFINITY, 3.0)),
+            vec!["  "]
+        );
+        assert_eq!(canvas(Shape::Circle(f64::NAN, 0.0, 1.0)), vec!["  "]);
+        assert_eq!(canvas(Shape::Circle(0.0, 0.0, 1e300)), vec!["  "]);
+        assert_eq!(canvas(Shape::Circle(0.0, 0.0, f64::MAX)), vec!["  "]);
+
+        // only the part of a huge circle in the grid is drawn
+        assert_eq!(canvas(Shape::Circle(0.0, -1e9, 1e9 + 3.0)), vec!["⠉⠉"]);
+    }
+}
@@ -0,0 +1,128 @@
+//! Share the scaling and the styles of the charts.
+use crate::renderer::utils::VERTICAL_EIGHTHS;
+use crate::Style;
+
+/// The style of a [`Sparkline`] or a [`BarChart`].
+///
+/// [`Sparkline`]: super::Sparkline
+/// [`BarChart`]: super::BarChart
+#[derive(Debug, Clone, Default)]
+pub struct ChartStyle {
+    pub(crate) normal: Style,
+    pub(crate) label: Style,
+    pub(crate) thresholds: Vec<(f64, Style)>,
+}
+
+impl ChartStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the style of the values.
+    pub fn normal(mut self, normal: Style) -> Self {
+        self.normal = normal;
+        self
+    }
+
+    /// Sets the style of the labels of a bar chart.
+    pub fn label(mut self, label: Style) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// Adds a style merged over the normal style for the values reaching the threshold.
+    ///
+    /// When a value reaches several thresholds, the style of the highest one is used.
+    pub fn threshold(mut self, value: f64, style: Style) -> Self {
+        self.thresholds.push((value, style));
+        self
+    }
+
+    /// Returns the style of a value.
+    pub(crate) fn value_style(&self, value: f64) -> Style {
+        let threshold = self
+            .thresholds
+            .iter()
+            .filter(|(threshold, _)| value >= *threshold)
+            .max_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        match threshold {
+            Some((_, style)) => self.normal.merge(*style),
+            None => self.normal,
+        }
+    }
+}
+
+/// The range of the values shown by a chart.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Scale {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Scale {
+    /// Returns the range of the values, unless set.
+    pub fn new(values: &[f64], min: Option<f64>, max: Option<f64>) -> Self {
+        let values = values.iter().copied().filter(|value| value.is_finite());
+
+        Self {
+            min: min.unwrap_or_else(|| values.clone().fold(f64::INFINITY, f64::min)),
+            max: max.unwrap_or_else(|| values.fold(f64::NEG_INFINITY, f64::max)),
+        }
+    }
+
+    /// Returns the number of eighths of cells filled by a value in the given number of cells.
+    pub fn eighths(&self, value: f64, cells: u16) -> u16 {
+        let ratio = match self.max > self.min {
+            true => (value - self.min) / (self.max - self.min),
+            false if value > self.min => 1.0,
+            false => 0.0,
+        };
+
+        let total = cells as f64 * 8.0;
+
+        (ratio.clamp(0.0, 1.0) * total).round() as u16
+    }
+}
+
+/// Returns the block of the row of a bar filled by the eighths, counting rows from the bottom.
+pub(crate) fn bar_block(eighths: u16, row: u16) -> Option<char> {
+    match eighths.saturating_sub(row * 8) {
+        0 => None,
+        filled => Some(VERTICAL_EIGHTHS[filled.min(8) as usize - 1]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bar_block, ChartStyle, Scale};
+    use crate::Style;
+
+    #[test]
+    fn it_scale_values_in_eighths() {
+        let scale = Scale::new(&[2.0, 4.0, 10.0, f64::NAN], None, None);
+        assert_eq!(
+            scale,
+            Scale {
+                min: 2.0,
+                max: 10.0
+            }
+        );
+        assert_eq!(scale.eighths(2.0, 1), 0);
+        assert_eq!(scale.eighths(6.0, 1), 4);
+        assert_eq!(scale.eighths(6.0, 2), 8);
+        assert_eq!(scale.eighths(20.0, 2), 16);
+
+        assert_eq!(bar_block(4, 0), Some('▄'));
+        assert_eq!(bar_block(11, 0), Some('█'));
+        assert_eq!(bar_block(11, 1), Some('▃'));
+        assert_eq!(bar_block(11, 2), None);
+
+        let style = ChartStyle::new()
+            .threshold(80.0, Style::new().bold())
+            .threshold(50.0, Style::new().underline());
+        assert_eq!(style.value_style(40.0), Style::new());
+        assert_eq!(style.value_style(60.0), Style::new().underline());
+        assert_eq!(style.value_style(90.0), Style::new().bold());
+    }
+}
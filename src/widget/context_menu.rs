@@ -0,0 +1,243 @@
+//! Show a menu where the content is right-clicked.
+use super::menu::{Menu, MenuOverlay, MenuState};
+use crate::renderer::primitives::Primitive;
+use crate::{MenuStyle, Style, TuiRenderer};
+use iced_native::{
+    event, layout, mouse, overlay, Clipboard, Element, Event, Hasher, Layout, Length, Point,
+    Rectangle, Widget,
+};
+
+/// A content opening a menu at the mouse position when right-clicked.
+///
+/// While the menu is open, the content gets no keyboard events.
+pub struct ContextMenu<'a, Message> {
+    state: &'a mut State,
+    content: Element<'a, Message, TuiRenderer>,
+    menu: Menu<Message>,
+    style: MenuStyle,
+}
+
+impl<'a, Message> ContextMenu<'a, Message>
+where
+    Message: Clone,
+{
+    pub fn new<E>(state: &'a mut State, content: E, menu: Menu<Message>) -> Self
+    where
+        E: Into<Element<'a, Message, TuiRenderer>>,
+    {
+        Self {
+            state,
+            content: content.into(),
+            menu,
+            style: MenuStyle::default(),
+        }
+    }
+
+    pub fn style(mut self, style: MenuStyle) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+/// The state of a [`ContextMenu`].
+#[derive(Debug, Clone, Default)]
+pub struct State {
+    menu: MenuState,
+    position: Point,
+}
+
+impl State {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether the menu is open.
+    pub fn is_open(&self) -> bool {
+        self.menu.is_open
+    }
+
+    pub fn close(&mut self) {
+        self.menu.close();
+    }
+}
+
+impl<'a, Message> Widget<Message, TuiRenderer> for ContextMenu<'a, Message>
+where
+    Message: Clone,
+{
+    fn width(&self) -> Length {
+        self.content.width()
+    }
+
+    fn height(&self) -> Length {
+        self.content.height()
+    }
+
+    fn layout(&self, renderer: &TuiRenderer, limits: &layout::Limits) -> layout::Node {
+        self.content.layout(renderer, limits)
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        use std::hash::Hash;
+
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+
+        self.content.hash_layout(state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &TuiRenderer,
+        clipboard: &mut dyn Clipboard,
+        messages: &mut Vec<Message>,
+    ) -> event::Status {
+        let captures_keys = self.state.menu.captures_keys();
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right))
+                if layout.bounds().contains(cursor_position) =>
+            {
+                self.state.menu.is_open = true;
+                self.state.menu.path.clear();
+                self.state.position =
+                    Point::new(cursor_position.x.round(), cursor_position.y.round());
+
+                return event::Status::Captured;
+            }
+            // the open menu gets the keys through its overlay
+            Event::Keyboard(_) if captures_keys => return event::Status::Captured,
+            _ => {}
+        }
+
+        self.content.on_event(
+            event,
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            messages,
+        )
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut TuiRenderer,
+        defaults: &Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) -> Primitive {
+        self.content
+            .draw(renderer, defaults, layout, cursor_position, viewport)
+    }
+
+    fn overlay(
+        &mut self,
+        layout: Layout<'_>,
+    ) -> Option<overlay::Element<'_, Message, TuiRenderer>> {
+        if !self.state.menu.is_open {
+            return self.content.overlay(layout);
+        }
+
+        Some(
+            MenuOverlay {
+                root: &self.menu,
+                state: &mut self.state.menu,
+                first_level: 0,
+                passthrough: None,
+                style: self.style,
+            }
+            .into_overlay(self.state.position),
+        )
+    }
+}
+
+impl<'a, Message> From<ContextMenu<'a, Message>> for Element<'a, Message, TuiRenderer>
+where
+    Message: 'a + Clone,
+{
+    fn from(context_menu: ContextMenu<'a, Message>) -> Self {
+        Element::new(context_menu)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ContextMenu, State};
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::{Menu, MenuItem, TuiRenderer};
+    use iced_native::{
+        clipboard, keyboard, mouse, Cache, Element, Event, Point, Size, Text, UserInterface,
+    };
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Message {
+        Copy,
+        Paste,
+    }
+
+    #[test]
+    fn it_open_at_the_mouse_position() {
+        let mut renderer = TuiRenderer::default();
+        let mut state = State::new();
+        let mut messages = vec![];
+
+        let menu = Menu::new("")
+            .push(MenuItem::new("&Copy", Message::Copy))
+            .push(MenuItem::new("&Paste", Message::Paste));
+        let context_menu: Element<'_, Message, TuiRenderer> =
+            ContextMenu::new(&mut state, Text::new("content"), menu).into();
+        let mut ui = UserInterface::build(
+            context_menu,
+            Size::new(12., 4.),
+            Cache::default(),
+            &mut renderer,
+        );
+
+        ui.update(
+            &[Event::Mouse(mouse::Event::ButtonPressed(
+                mouse::Button::Right,
+            ))],
+            Point::new(2., 0.),
+            &renderer,
+            &mut clipboard::Null,
+            &mut messages,
+        );
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(12, 4);
+        vbuffer.merge_primitive(&primitive);
+
+        let lines: Vec<String> = vbuffer
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.content.unwrap_or(' ')).collect())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "co Copy     ",
+                "   Paste    ",
+                "            ",
+                "            "
+            ]
+        );
+
+        ui.update(
+            &[Event::Keyboard(keyboard::Event::CharacterReceived('p'))],
+            Point::new(-1., -1.),
+            &renderer,
+            &mut clipboard::Null,
+            &mut messages,
+        );
+
+        drop(ui);
+
+        assert_eq!(messages, vec![Message::Paste]);
+        assert!(!state.is_open());
+    }
+}
@@ -0,0 +1,335 @@
+//! Show a value in a range, colored by the thresholds it reaches.
+use crate::renderer::primitives::{Cell, Primitive};
+use crate::renderer::utils::{
+    char_width, progress_ratio, text_width, truncate_text, BarStyle, HORIZONTAL_EIGHTHS,
+    VERTICAL_EIGHTHS,
+};
+use crate::{Style, TuiRenderer};
+use iced_native::{layout, Color, Element, Hasher, Layout, Length, Point, Rectangle, Size, Widget};
+use std::ops::RangeInclusive;
+
+/// A meter filled up to its value, with an optional label inside.
+///
+/// The filled part takes the style of the warning or the critical threshold once the value
+/// reaches it, e.g. for the usage of a CPU, the memory or a disk.
+pub struct Gauge {
+    range: RangeInclusive<f32>,
+    value: f32,
+    label: Option<String>,
+    is_vertical: bool,
+    width: Option<Length>,
+    height: Option<Length>,
+    style: GaugeStyle,
+}
+
+impl Gauge {
+    pub fn new(range: RangeInclusive<f32>, value: f32) -> Self {
+        Self {
+            range,
+            value,
+            label: None,
+            is_vertical: false,
+            width: None,
+            height: None,
+            style: GaugeStyle::default(),
+        }
+    }
+
+    /// Sets the text shown in the middle of the gauge, its colors being inverted where it
+    /// overlaps the filled part.
+    pub fn label<T>(mut self, label: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Sets whether the gauge fills up from the bottom instead of the left.
+    pub fn vertical(mut self, is_vertical: bool) -> Self {
+        self.is_vertical = is_vertical;
+        self
+    }
+
+    /// Sets the width, filling the available space for horizontal gauges by default.
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Sets the height, filling the available space for vertical gauges by default.
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn style(mut self, style: GaugeStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn label_width(&self) -> u16 {
+        self.label.as_deref().map(text_width).unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GaugeStyle {
+    pub(crate) bar: BarStyle,
+    pub(crate) warning: Option<(f32, Style)>,
+    pub(crate) critical: Option<(f32, Style)>,
+}
+
+impl GaugeStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn fg<C>(mut self, color: C) -> Self
+    where
+        C: Into<Color>,
+    {
+        self.bar = self.bar.fg(color);
+        self
+    }
+
+    pub fn bg<C>(mut self, color: C) -> Self
+    where
+        C: Into<Color>,
+    {
+        self.bar = self.bar.bg(color);
+        self
+    }
+
+    /// Sets the style merged over the filled part once the value reaches the threshold.
+    pub fn warning(mut self, threshold: f32, style: Style) -> Self {
+        self.warning = Some((threshold, style));
+        self
+    }
+
+    /// Sets the style merged over the filled part once the value reaches the threshold,
+    /// taking precedence over the warning.
+    pub fn critical(mut self, threshold: f32, style: Style) -> Self {
+        self.critical = Some((threshold, style));
+        self
+    }
+
+    /// Returns the styles of the bar for a value, with the threshold it reaches.
+    fn bar_style(&self, value: f32) -> BarStyle {
+        let reached = |threshold: Option<(f32, Style)>| {
+            threshold.filter(|(threshold, _)| value >= *threshold)
+        };
+
+        match reached(self.critical).or_else(|| reached(self.warning)) {
+            Some((_, style)) => BarStyle {
+                filled: self.bar.filled.merge(style),
+                ..self.bar
+            },
+            None => self.bar,
+        }
+    }
+}
+
+impl<Message> Widget<Message, TuiRenderer> for Gauge {
+    fn width(&self) -> Length {
+        self.width.unwrap_or(match self.is_vertical {
+            true => Length::Shrink,
+            false => Length::Fill,
+        })
+    }
+
+    fn height(&self) -> Length {
+        self.height.unwrap_or(match self.is_vertical {
+            true => Length::Fill,
+            false => Length::Shrink,
+        })
+    }
+
+    fn layout(&self, _renderer: &TuiRenderer, limits: &layout::Limits) -> layout::Node {
+        let width = Widget::<Message, TuiRenderer>::width(self);
+        let height = Widget::<Message, TuiRenderer>::height(self);
+        let size = limits
+            .width(width)
+            .height(height)
+            .resolve(Size::new(self.label_width().max(1) as f32, 1.0));
+
+        layout::Node::new(size)
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        use std::hash::Hash;
+
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+
+        self.width.hash(state);
+        self.height.hash(state);
+        self.is_vertical.hash(state);
+        self.label_width().hash(state);
+    }
+
+    fn draw(
+        &self,
+        _renderer: &mut TuiRenderer,
+        _defaults: &Style,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+        _viewport: &Rectangle,
+    ) -> Primitive {
+        let bounds = layout.bounds();
+        let x = bounds.x.round() as u16;
+        let y = bounds.y.round() as u16;
+        let width = bounds.width.round() as u16;
+        let height = bounds.height.round() as u16;
+
+        let bar = self.style.bar_style(self.value);
+
+        let length = if self.is_vertical { height } else { width };
+        let eighths =
+            (progress_ratio(&self.range, self.value) * length as f32 * 8.0).round() as u16;
+        let full_length = eighths / 8;
+        let partial_eighths = eighths % 8;
+
+        let mut primitives = vec![Primitive::Rectangle(
+            x,
+            y,
+            width,
+            height,
+            Cell::from_char(' ').style(bar.unfilled),
+        )];
+
+        let filled = match self.is_vertical {
+            true => (x, y + height - full_length, width, full_length),
+            false => (x, y, full_length, height),
+        };
+        primitives.push(Primitive::Rectangle(
+            filled.0,
+            filled.1,
+            filled.2,
+            filled.3,
+            Cell::from_char(' ').style(bar.filled),
+        ));
+
+        if partial_eighths > 0 {
+            let partial = match self.is_vertical {
+                true => (
+                    x,
+                    y + height - full_length - 1,
+                    width,
+                    1,
+                    VERTICAL_EIGHTHS[partial_eighths as usize - 1],
+                ),
+                false => (
+                    x + full_length,
+                    y,
+                    1,
+                    height,
+                    HORIZONTAL_EIGHTHS[partial_eighths as usize - 1],
+                ),
+            };
+
+            primitives.push(Primitive::Rectangle(
+                partial.0,
+                partial.1,
+                partial.2,
+                partial.3,
+                Cell::from_char(partial.4).style(bar.text_style(false)),
+            ));
+        }
+
+        if let Some(label) = self.label.as_deref().filter(|_| height > 0) {
+            let label = truncate_text(label, width);
+            let label_x = x + width.saturating_sub(text_width(&label)) / 2;
+            let label_y = y + (height - 1) / 2;
+            let mut column = label_x;
+
+            for c in label.chars() {
+                // a cell more than half filled shows the label inverted
+                let is_filled = match self.is_vertical {
+                    true => (y + height - 1 - label_y) * 8 + 4 <= eighths,
+                    false => (column - x) * 8 + 4 <= eighths,
+                };
+
+                primitives.push(Primitive::Cell(
+                    column,
+                    label_y,
+                    Cell::from_char(c).style(bar.text_style(is_filled)),
+                ));
+                column += char_width(c);
+            }
+        }
+
+        Primitive::Group(primitives)
+    }
+}
+
+impl<'a, Message> From<Gauge> for Element<'a, Message, TuiRenderer>
+where
+    Message: 'a,
+{
+    fn from(gauge: Gauge) -> Self {
+        Element::new(gauge)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Gauge, GaugeStyle};
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::{AnsiColor, Style, TuiRenderer};
+    use iced_native::{Cache, Color, Element, Point, Size, UserInterface};
+
+    fn draw(gauge: Gauge, width: u16, height: u16) -> VirtualBuffer {
+        let mut renderer = TuiRenderer::default();
+        let gauge: Element<'_, (), TuiRenderer> = gauge.into();
+        let mut ui = UserInterface::build(
+            gauge,
+            Size::new(width as f32, height as f32),
+            Cache::default(),
+            &mut renderer,
+        );
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(width, height);
+        vbuffer.merge_primitive(&primitive);
+        vbuffer
+    }
+
+    fn lines(vbuffer: &VirtualBuffer) -> Vec<String> {
+        vbuffer
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.content.unwrap_or(' ')).collect())
+            .collect()
+    }
+
+    #[test]
+    fn it_color_thresholds() {
+        let red = Style::new().bg(Color::from(AnsiColor::Red));
+        let style = GaugeStyle::new()
+            .fg(Color::from(AnsiColor::Green))
+            .warning(50.0, Style::new().bg(Color::from(AnsiColor::Yellow)))
+            .critical(90.0, red);
+
+        let vbuffer = draw(
+            Gauge::new(0.0..=100.0, 25.0).label("cpu").style(style),
+            10,
+            1,
+        );
+        assert_eq!(lines(&vbuffer), vec!["  ▌cpu    "]);
+        assert_eq!(vbuffer.rows[0][0].style, style.bar.filled);
+
+        let vbuffer = draw(Gauge::new(0.0..=100.0, 95.0).style(style), 10, 1);
+        assert_eq!(vbuffer.rows[0][0].style, style.bar.filled.merge(red));
+    }
+
+    #[test]
+    fn it_fill_from_the_bottom() {
+        let style = GaugeStyle::new().fg(Color::from(AnsiColor::Green));
+        let vbuffer = draw(Gauge::new(0.0..=1.0, 0.6).vertical(true).style(style), 3, 3);
+
+        assert_eq!(lines(&vbuffer), vec!["   ", "▆  ", "   "]);
+        assert_eq!(vbuffer.rows[2][0].style, style.bar.filled);
+        assert_ne!(vbuffer.rows[0][0].style, style.bar.filled);
+    }
+}
@@ -0,0 +1,613 @@
+//! Describe the menus of menu bars and context menus.
+use crate::renderer::primitives::{Cell, Primitive};
+use crate::renderer::utils::{char_width, text_cells, text_width, truncate_text};
+use crate::{MenuStyle, Style, TuiRenderer};
+use iced_native::{
+    event, keyboard, layout, mouse, overlay, Clipboard, Event, Hasher, Layout, Point, Rectangle,
+    Size,
+};
+
+/// A menu of items, separators and submenus.
+///
+/// In labels, `&` marks the next character as the mnemonic of the entry: it is underlined
+/// and typing it activates the entry. Use `&&` for a literal `&`.
+#[derive(Debug, Clone)]
+pub struct Menu<Message> {
+    label: String,
+    entries: Vec<MenuEntry<Message>>,
+}
+
+impl<Message> Menu<Message> {
+    pub fn new<T>(label: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            label: label.into(),
+            entries: vec![],
+        }
+    }
+
+    pub fn push(mut self, item: MenuItem<Message>) -> Self {
+        self.entries.push(MenuEntry::Item(item));
+        self
+    }
+
+    pub fn separator(mut self) -> Self {
+        self.entries.push(MenuEntry::Separator);
+        self
+    }
+
+    pub fn submenu(mut self, submenu: Menu<Message>) -> Self {
+        self.entries.push(MenuEntry::Submenu(submenu));
+        self
+    }
+
+    pub(crate) fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub(crate) fn submenu_at(&self, index: usize) -> Option<&Menu<Message>> {
+        match self.entries.get(index) {
+            Some(MenuEntry::Submenu(submenu)) => Some(submenu),
+            _ => None,
+        }
+    }
+
+    /// Returns the index of the entry with the given mnemonic.
+    pub(crate) fn find_mnemonic(&self, c: char) -> Option<usize> {
+        self.entries.iter().position(|entry| {
+            entry
+                .label()
+                .and_then(|label| parse_mnemonic(label).1)
+                .is_some_and(|(_, mnemonic)| mnemonic.eq_ignore_ascii_case(&c))
+        })
+    }
+
+    /// Returns the index of the next entry that can be highlighted, skipping separators.
+    fn next_entry(&self, current: Option<usize>, forward: bool) -> Option<usize> {
+        let len = self.entries.len();
+
+        (1..=len)
+            .map(|step| match (current, forward) {
+                (Some(current), true) => (current + step) % len,
+                (Some(current), false) => (current + len - step % len) % len,
+                (None, true) => step - 1,
+                (None, false) => len - step,
+            })
+            .find(|index| !matches!(self.entries[*index], MenuEntry::Separator))
+    }
+
+    /// Returns the width of the dropdown showing the menu.
+    fn dropdown_width(&self) -> u16 {
+        let entries_width = self
+            .entries
+            .iter()
+            .map(|entry| match entry {
+                MenuEntry::Item(item) => label_width(&item.label),
+                // room for the arrow
+                MenuEntry::Submenu(submenu) => label_width(&submenu.label) + 2,
+                MenuEntry::Separator => 0,
+            })
+            .max()
+            .unwrap_or(0);
+
+        entries_width + 2
+    }
+}
+
+#[derive(Debug, Clone)]
+enum MenuEntry<Message> {
+    Item(MenuItem<Message>),
+    Separator,
+    Submenu(Menu<Message>),
+}
+
+impl<Message> MenuEntry<Message> {
+    fn label(&self) -> Option<&str> {
+        match self {
+            Self::Item(item) => Some(&item.label),
+            Self::Submenu(submenu) => Some(&submenu.label),
+            Self::Separator => None,
+        }
+    }
+}
+
+/// An item of a [`Menu`], producing a message when activated.
+#[derive(Debug, Clone)]
+pub struct MenuItem<Message> {
+    label: String,
+    message: Message,
+    is_enabled: bool,
+}
+
+impl<Message> MenuItem<Message> {
+    pub fn new<T>(label: T, message: Message) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            label: label.into(),
+            message,
+            is_enabled: true,
+        }
+    }
+
+    /// Sets whether the item can be activated.
+    pub fn enabled(mut self, is_enabled: bool) -> Self {
+        self.is_enabled = is_enabled;
+        self
+    }
+}
+
+/// Splits a label in its text and its mnemonic, with the index of the mnemonic in the
+/// characters of the text.
+pub(crate) fn parse_mnemonic(label: &str) -> (String, Option<(usize, char)>) {
+    let mut text = String::with_capacity(label.len());
+    let mut mnemonic = None;
+    let mut chars = label.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '&' {
+            text.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some(c) if c != '&' && mnemonic.is_none() => {
+                mnemonic = Some((text.chars().count(), c));
+                text.push(c);
+            }
+            Some(c) => text.push(c),
+            None => {}
+        }
+    }
+
+    (text, mnemonic)
+}
+
+pub(crate) fn label_width(label: &str) -> u16 {
+    text_width(&parse_mnemonic(label).0)
+}
+
+/// Returns the cells of a label, with its mnemonic underlined.
+pub(crate) fn label_cells(label: &str, x: u16, y: u16, width: u16, style: Style) -> Vec<Primitive> {
+    let (text, mnemonic) = parse_mnemonic(label);
+    let text = truncate_text(&text, width);
+    let mut primitives = vec![];
+    let mut cell_x = x;
+
+    for (index, c) in text.chars().enumerate() {
+        let cell_style = match mnemonic {
+            Some((mnemonic_index, _)) if mnemonic_index == index => style.underline(),
+            _ => style,
+        };
+
+        primitives.extend(text_cells(&c.to_string(), cell_x, y, cell_style));
+        cell_x += char_width(c);
+    }
+
+    primitives
+}
+
+/// The open menus of a menu bar or a context menu.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MenuState {
+    pub is_open: bool,
+    /// The highlighted entry of each open menu, starting with the root.
+    pub path: Vec<usize>,
+    /// The number of events handled by the open menus, not given to the widget yet.
+    pub handled_events: usize,
+}
+
+impl MenuState {
+    pub fn close(&mut self) {
+        self.is_open = false;
+        self.path.clear();
+    }
+
+    /// Returns whether the widget showing the menus keeps the keys of the next event from
+    /// its content, to be called once for each event given to the widget.
+    ///
+    /// The overlay gets the events of an update before the widget, so the keys handled by
+    /// the menus, up to the one closing them, must not reach the content too.
+    pub fn captures_keys(&mut self) -> bool {
+        let is_handled = self.handled_events > 0;
+        self.handled_events = self.handled_events.saturating_sub(1);
+
+        is_handled || self.is_open
+    }
+}
+
+/// The dropdowns of the open menus, floating over the user interface.
+///
+/// The root menu is shown at `first_level` 0 (context menus), or by the widget itself
+/// at `first_level` 1 (menu bars, whose first entries are the menus of the bar).
+pub(crate) struct MenuOverlay<'a, Message> {
+    pub root: &'a Menu<Message>,
+    pub state: &'a mut MenuState,
+    pub first_level: usize,
+    /// An area where clicks are left to the widget, e.g. the menu bar.
+    pub passthrough: Option<Rectangle>,
+    pub style: MenuStyle,
+}
+
+impl<'a, Message> MenuOverlay<'a, Message>
+where
+    Message: Clone,
+{
+    /// Returns the menu shown at the given level of the path.
+    fn menu_at(&self, level: usize) -> Option<&'a Menu<Message>> {
+        let mut menu = self.root;
+
+        for index in self.state.path.iter().take(level) {
+            menu = match menu.entries.get(*index) {
+                Some(MenuEntry::Submenu(submenu)) => submenu,
+                _ => return None,
+            };
+        }
+
+        Some(menu)
+    }
+
+    /// Returns the deepest level of the open menus.
+    fn deepest_level(&self) -> usize {
+        self.state
+            .path
+            .len()
+            .saturating_sub(1)
+            .max(self.first_level)
+    }
+
+    /// Returns the open menus with their level.
+    fn open_menus(&self) -> Vec<(usize, &'a Menu<Message>)> {
+        (self.first_level..=self.deepest_level())
+            .map_while(|level| self.menu_at(level).map(|menu| (level, menu)))
+            .collect()
+    }
+
+    fn highlight(&mut self, level: usize, index: usize) {
+        self.state.path.truncate(level);
+        self.state.path.push(index);
+    }
+
+    /// Activates an entry: produces the message of an item or opens a submenu.
+    fn activate(&mut self, level: usize, index: usize, messages: &mut Vec<Message>) {
+        let menu = match self.menu_at(level) {
+            Some(menu) => menu,
+            None => return,
+        };
+
+        match menu.entries.get(index) {
+            Some(MenuEntry::Item(item)) if item.is_enabled => {
+                messages.push(item.message.clone());
+                self.state.close();
+            }
+            Some(MenuEntry::Submenu(submenu)) => {
+                self.highlight(level, index);
+
+                if let Some(first) = submenu.next_entry(None, true) {
+                    self.state.path.push(first);
+                }
+            }
+            _ => self.highlight(level, index),
+        }
+    }
+
+    /// Moves to the next or previous menu of a menu bar.
+    fn switch_root_menu(&mut self, forward: bool) {
+        if self.first_level == 0 || self.state.path.is_empty() {
+            return;
+        }
+
+        if let Some(index) = self
+            .root
+            .next_entry(self.state.path.first().copied(), forward)
+        {
+            self.state.path = vec![index];
+        }
+    }
+
+    fn key_pressed(&mut self, key_code: keyboard::KeyCode, messages: &mut Vec<Message>) -> bool {
+        let level = self.deepest_level();
+        let menu = match self.menu_at(level) {
+            Some(menu) => menu,
+            None => return false,
+        };
+        let highlighted = self.state.path.get(level).copied();
+
+        match key_code {
+            keyboard::KeyCode::Up | keyboard::KeyCode::Down => {
+                let forward = key_code == keyboard::KeyCode::Down;
+
+                if let Some(index) = menu.next_entry(highlighted, forward) {
+                    self.highlight(level, index);
+                }
+            }
+            keyboard::KeyCode::Right => match highlighted {
+                Some(index) if matches!(menu.entries[index], MenuEntry::Submenu(_)) => {
+                    self.activate(level, index, messages)
+                }
+                _ => self.switch_root_menu(true),
+            },
+            keyboard::KeyCode::Left if level > self.first_level => self.state.path.truncate(level),
+            keyboard::KeyCode::Left => self.switch_root_menu(false),
+            keyboard::KeyCode::Enter | keyboard::KeyCode::Space => {
+                if let Some(index) = highlighted {
+                    self.activate(level, index, messages);
+                }
+            }
+            keyboard::KeyCode::Escape if level > self.first_level => {
+                self.state.path.truncate(level)
+            }
+            keyboard::KeyCode::Escape => self.state.close(),
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+impl<'a, Message> overlay::Overlay<Message, TuiRenderer> for MenuOverlay<'a, Message>
+where
+    Message: Clone,
+{
+    fn layout(&self, _renderer: &TuiRenderer, bounds: Size, position: Point) -> layout::Node {
+        let mut dropdowns: Vec<Rectangle> = vec![];
+
+        for (level, menu) in self.open_menus() {
+            let width = menu.dropdown_width() as f32;
+            let height = menu.entries.len() as f32;
+
+            // submenus are shown next to their entry, on the left when there is no room
+            let (x, y) = match dropdowns.last() {
+                Some(parent) => {
+                    let entry = self.state.path[level - 1] as f32;
+                    let x = match parent.x + parent.width + width > bounds.width {
+                        true => parent.x - width,
+                        false => parent.x + parent.width,
+                    };
+
+                    (x, parent.y + entry)
+                }
+                None => (position.x, position.y),
+            };
+
+            dropdowns.push(Rectangle {
+                x: x.min(bounds.width - width).max(0.0),
+                y: y.min(bounds.height - height).max(0.0),
+                width,
+                height,
+            });
+        }
+
+        let left = dropdowns.iter().map(|r| r.x).fold(f32::MAX, f32::min);
+        let top = dropdowns.iter().map(|r| r.y).fold(f32::MAX, f32::min);
+        let right = dropdowns.iter().map(|r| r.x + r.width).fold(0.0, f32::max);
+        let bottom = dropdowns.iter().map(|r| r.y + r.height).fold(0.0, f32::max);
+
+        let children = dropdowns
+            .iter()
+            .map(|dropdown| {
+                let mut node = layout::Node::new(dropdown.size());
+                node.move_to(Point::new(dropdown.x - left, dropdown.y - top));
+                node
+            })
+            .collect();
+
+        let mut node = layout::Node::with_children(
+            Size::new((right - left).max(0.0), (bottom - top).max(0.0)),
+            children,
+        );
+        node.move_to(Point::new(left.min(right), top.min(bottom)));
+        node
+    }
+
+    fn draw(
+        &self,
+        _renderer: &mut TuiRenderer,
+        _defaults: &Style,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+    ) -> Primitive {
+        let disabled_style = match self.style.disabled.is_empty() {
+            true => self.style.normal.dim(0.5),
+            false => self.style.normal.merge(self.style.disabled),
+        };
+        let mut primitives = vec![];
+
+        for ((level, menu), dropdown_layout) in self.open_menus().into_iter().zip(layout.children())
+        {
+            let bounds = dropdown_layout.bounds();
+            let x = bounds.x.round() as u16;
+            let y = bounds.y.round() as u16;
+            let width = bounds.width.round() as u16;
+            let height = bounds.height.round() as u16;
+
+            // hide the user interface below the menu
+            primitives.push(Primitive::Rectangle(
+                x,
+                y,
+                width,
+                height,
+                Cell::from_char(' ').style(self.style.normal),
+            ));
+
+            for (index, entry) in menu.entries.iter().enumerate() {
+                let entry_y = y + index as u16;
+                let is_highlighted = self.state.path.get(level) == Some(&index);
+
+                let style = match entry {
+                    MenuEntry::Item(item) if !item.is_enabled => disabled_style,
+                    _ => self.style.normal,
+                };
+                let style = match is_highlighted {
+                    true => style.merge(self.style.selected),
+                    false => style,
+                };
+
+                if is_highlighted {
+                    primitives.push(Primitive::Rectangle(
+                        x,
+                        entry_y,
+                        width,
+                        1,
+                        Cell {
+                            content: None,
+                            style,
+                        },
+                    ));
+                }
+
+                match entry {
+                    MenuEntry::Separator => primitives.push(Primitive::Rectangle(
+                        x,
+                        entry_y,
+                        width,
+                        1,
+                        Cell::from_char('─').style(disabled_style),
+                    )),
+                    MenuEntry::Item(item) => primitives.extend(label_cells(
+                        &item.label,
+                        x + 1,
+                        entry_y,
+                        width.saturating_sub(2),
+                        style,
+                    )),
+                    MenuEntry::Submenu(submenu) => {
+                        primitives.extend(label_cells(
+                            &submenu.label,
+                            x + 1,
+                            entry_y,
+                            width.saturating_sub(4),
+                            style,
+                        ));
+                        primitives.push(Primitive::Cell(
+                            x + width.saturating_sub(2),
+                            entry_y,
+                            Cell::from_char('▸').style(style),
+                        ));
+                    }
+                }
+            }
+
+            if let Some(shadow) = self.style.shadow {
+                primitives.push(Primitive::shadow(x, y, width, height, shadow));
+            }
+        }
+
+        Primitive::Group(primitives)
+    }
+
+    fn hash_layout(&self, state: &mut Hasher, position: Point) {
+        use std::hash::Hash;
+
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+
+        (position.x as u32).hash(state);
+        (position.y as u32).hash(state);
+        self.state.path.hash(state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        _renderer: &TuiRenderer,
+        _clipboard: &mut dyn Clipboard,
+        messages: &mut Vec<Message>,
+    ) -> event::Status {
+        // the events following the one closing the menus go to the content
+        if !self.state.is_open {
+            return event::Status::Ignored;
+        }
+
+        self.state.handled_events += 1;
+
+        // the entry under the cursor, with the level of its menu
+        let entry_under_cursor = self
+            .open_menus()
+            .into_iter()
+            .zip(layout.children())
+            .find(|(_, dropdown_layout)| dropdown_layout.bounds().contains(cursor_position))
+            .map(|((level, menu), dropdown_layout)| {
+                let index = (cursor_position.y - dropdown_layout.bounds().y) as usize;
+                (level, menu, index.min(menu.entries.len().saturating_sub(1)))
+            });
+
+        match event {
+            Event::Mouse(mouse::Event::CursorMoved { .. }) => {
+                if let Some((level, menu, index)) = entry_under_cursor {
+                    if !matches!(menu.entries.get(index), Some(MenuEntry::Separator))
+                        && self.state.path.get(level) != Some(&index)
+                    {
+                        self.highlight(level, index);
+                    }
+                }
+
+                event::Status::Ignored
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(_)) => match entry_under_cursor {
+                Some((level, _, index)) => {
+                    self.activate(level, index, messages);
+                    event::Status::Captured
+                }
+                None if self
+                    .passthrough
+                    .is_some_and(|passthrough| passthrough.contains(cursor_position)) =>
+                {
+                    event::Status::Ignored
+                }
+                None => {
+                    self.state.close();
+                    event::Status::Ignored
+                }
+            },
+            Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. }) => {
+                match self.key_pressed(key_code, messages) {
+                    true => event::Status::Captured,
+                    false => event::Status::Ignored,
+                }
+            }
+            Event::Keyboard(keyboard::Event::CharacterReceived(c)) => {
+                let level = self.deepest_level();
+
+                match self.menu_at(level).and_then(|menu| menu.find_mnemonic(c)) {
+                    Some(index) => {
+                        self.activate(level, index, messages);
+                        event::Status::Captured
+                    }
+                    None => event::Status::Ignored,
+                }
+            }
+            _ => event::Status::Ignored,
+        }
+    }
+}
+
+impl<'a, Message> MenuOverlay<'a, Message>
+where
+    Message: 'a + Clone,
+{
+    pub fn into_overlay(self, position: Point) -> overlay::Element<'a, Message, TuiRenderer> {
+        overlay::Element::new(position, Box::new(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_mnemonic;
+
+    #[test]
+    fn it_parse_mnemonic() {
+        assert_eq!(parse_mnemonic("&File"), ("File".into(), Some((0, 'F'))));
+        assert_eq!(
+            parse_mnemonic("Save &As"),
+            ("Save As".into(), Some((5, 'A')))
+        );
+        assert_eq!(parse_mnemonic("A && B"), ("A & B".into(), None));
+        assert_eq!(parse_mnemonic("Plain"), ("Plain".into(), None));
+    }
+}
@@ -0,0 +1,638 @@
+//! Show menus from a bar at the top of the user interface.
+use super::menu::{label_cells, label_width, Menu, MenuOverlay, MenuState};
+use crate::renderer::primitives::{Cell, Primitive};
+use crate::{MenuStyle, Style, TuiRenderer};
+use iced_native::{
+    event, keyboard, layout, mouse, overlay, Clipboard, Element, Event, Hasher, Layout, Length,
+    Point, Rectangle, Size, Widget,
+};
+
+/// A bar of menus opening dropdowns over the user interface, above an optional content.
+///
+/// A menu is opened by clicking it or with Alt and its mnemonic. While a menu is open, the
+/// content gets no keyboard events.
+pub struct MenuBar<'a, Message> {
+    state: &'a mut State,
+    root: Menu<Message>,
+    content: Option<Element<'a, Message, TuiRenderer>>,
+    width: Length,
+    height: Length,
+    style: MenuBarStyle,
+}
+
+impl<'a, Message> MenuBar<'a, Message>
+where
+    Message: Clone,
+{
+    pub fn new(state: &'a mut State, menus: Vec<Menu<Message>>) -> Self {
+        let root = menus
+            .into_iter()
+            .fold(Menu::new(""), |root, menu| root.submenu(menu));
+
+        Self {
+            state,
+            root,
+            content: None,
+            width: Length::Fill,
+            height: Length::Shrink,
+            style: MenuBarStyle::default(),
+        }
+    }
+
+    /// Sets the content drawn below the menu bar.
+    pub fn content<E>(mut self, content: E) -> Self
+    where
+        E: Into<Element<'a, Message, TuiRenderer>>,
+    {
+        self.content = Some(content.into());
+        self
+    }
+
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn style(mut self, style: MenuBarStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Returns the start and the width of the label of each menu, relative to the bar.
+    fn label_bounds(&self) -> Vec<(u16, u16)> {
+        let mut x = 0;
+
+        self.menus()
+            .map(|menu| {
+                let width = label_width(menu.label()) + 2;
+                x += width;
+                (x - width, width)
+            })
+            .collect()
+    }
+
+    fn menus(&self) -> impl Iterator<Item = &Menu<Message>> {
+        (0..).map_while(move |index| self.root.submenu_at(index))
+    }
+
+    fn open(&mut self, index: usize) {
+        self.state.menu.is_open = true;
+        self.state.menu.path = vec![index];
+    }
+
+    fn open_menu(&self) -> Option<usize> {
+        match self.state.menu.is_open {
+            true => self.state.menu.path.first().copied(),
+            false => None,
+        }
+    }
+
+    fn content_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &TuiRenderer,
+        clipboard: &mut dyn Clipboard,
+        messages: &mut Vec<Message>,
+    ) -> event::Status {
+        match (&mut self.content, layout.children().next()) {
+            (Some(content), Some(content_layout)) => content.on_event(
+                event,
+                content_layout,
+                cursor_position,
+                renderer,
+                clipboard,
+                messages,
+            ),
+            _ => event::Status::Ignored,
+        }
+    }
+}
+
+/// The state of a [`MenuBar`].
+#[derive(Debug, Clone, Default)]
+pub struct State {
+    menu: MenuState,
+    is_alt_pressed: bool,
+}
+
+impl State {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether a menu is open.
+    pub fn is_open(&self) -> bool {
+        self.menu.is_open
+    }
+
+    pub fn close(&mut self) {
+        self.menu.close();
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MenuBarStyle {
+    pub(crate) normal: Style,
+    pub(crate) selected: Style,
+    pub(crate) menu: MenuStyle,
+}
+
+impl MenuBarStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the style of the bar.
+    pub fn normal(mut self, normal: Style) -> Self {
+        self.normal = normal;
+        self
+    }
+
+    /// Sets the style of the label of the open menu.
+    ///
+    /// When not set, the label of the open menu is drawn in bold.
+    pub fn selected(mut self, selected: Style) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    pub fn menu(mut self, menu: MenuStyle) -> Self {
+        self.menu = menu;
+        self
+    }
+}
+
+impl<'a, Message> Widget<Message, TuiRenderer> for MenuBar<'a, Message>
+where
+    Message: Clone,
+{
+    fn width(&self) -> Length {
+        self.width
+    }
+
+    fn height(&self) -> Length {
+        self.height
+    }
+
+    fn layout(&self, renderer: &TuiRenderer, limits: &layout::Limits) -> layout::Node {
+        let limits = limits.width(self.width).height(self.height);
+
+        let content = match &self.content {
+            Some(content) => {
+                let content_limits = limits.loose().shrink(Size::new(0.0, 1.0));
+                let mut content = content.layout(renderer, &content_limits);
+                content.move_to(Point::new(0.0, 1.0));
+                content
+            }
+            None => layout::Node::new(Size::ZERO),
+        };
+
+        let bar_width: u16 = self.label_bounds().iter().map(|(_, width)| width).sum();
+        let intrinsic = Size::new(
+            content.size().width.max(bar_width as f32),
+            content.size().height + 1.0,
+        );
+
+        layout::Node::with_children(limits.resolve(intrinsic), vec![content])
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        use std::hash::Hash;
+
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+
+        self.width.hash(state);
+        self.height.hash(state);
+        self.label_bounds().hash(state);
+
+        if let Some(content) = &self.content {
+            content.hash_layout(state);
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &TuiRenderer,
+        clipboard: &mut dyn Clipboard,
+        messages: &mut Vec<Message>,
+    ) -> event::Status {
+        let captures_keys = self.state.menu.captures_keys();
+        let bounds = layout.bounds();
+        let bar_bounds = Rectangle {
+            height: 1.0,
+            ..bounds
+        };
+
+        match event {
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                self.state.is_alt_pressed = modifiers.alt;
+            }
+            // Alt and a mnemonic open a menu
+            Event::Keyboard(keyboard::Event::CharacterReceived(c)) if self.state.is_alt_pressed => {
+                if let Some(index) = self.root.find_mnemonic(c) {
+                    self.open(index);
+                    return event::Status::Captured;
+                }
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+                if bar_bounds.contains(cursor_position) =>
+            {
+                let x = (cursor_position.x - bounds.x.round()) as u16;
+                let clicked = self
+                    .label_bounds()
+                    .iter()
+                    .position(|(start, width)| x >= *start && x < start + width);
+
+                match clicked {
+                    Some(index) if self.open_menu() != Some(index) => self.open(index),
+                    _ => self.state.menu.close(),
+                }
+
+                return event::Status::Captured;
+            }
+            _ => {}
+        }
+
+        // the open menus get the keys through their overlay
+        if captures_keys && matches!(event, Event::Keyboard(_)) {
+            return event::Status::Captured;
+        }
+
+        self.content_event(
+            event,
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            messages,
+        )
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut TuiRenderer,
+        defaults: &Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) -> Primitive {
+        let bounds = layout.bounds();
+        let x = bounds.x.round() as u16;
+        let y = bounds.y.round() as u16;
+        let width = bounds.width.round() as u16;
+
+        let mut primitives = vec![Primitive::Rectangle(
+            x,
+            y,
+            width,
+            1,
+            Cell {
+                content: None,
+                style: self.style.normal,
+            },
+        )];
+
+        let selected_style = match self.style.selected.is_empty() {
+            true => self.style.normal.merge(Style::new().bold()),
+            false => self.style.normal.merge(self.style.selected),
+        };
+
+        for (index, (menu, (start, label_width))) in
+            self.menus().zip(self.label_bounds()).enumerate()
+        {
+            if start >= width {
+                break;
+            }
+
+            let style = match self.open_menu() == Some(index) {
+                true => selected_style,
+                false => self.style.normal,
+            };
+
+            if style != self.style.normal {
+                primitives.push(Primitive::Rectangle(
+                    x + start,
+                    y,
+                    label_width.min(width - start),
+                    1,
+                    Cell {
+                        content: None,
+                        style,
+                    },
+                ));
+            }
+
+            primitives.extend(label_cells(
+                menu.label(),
+                x + start + 1,
+                y,
+                (label_width - 2).min(width.saturating_sub(start + 1)),
+                style,
+            ));
+        }
+
+        if let (Some(content), Some(content_layout)) = (&self.content, layout.children().next()) {
+            primitives.push(content.draw(
+                renderer,
+                defaults,
+                content_layout,
+                cursor_position,
+                viewport,
+            ));
+        }
+
+        Primitive::Group(primitives)
+    }
+
+    fn overlay(
+        &mut self,
+        layout: Layout<'_>,
+    ) -> Option<overlay::Element<'_, Message, TuiRenderer>> {
+        let bounds = layout.bounds();
+
+        let open_menu = match self.open_menu() {
+            Some(open_menu) => open_menu,
+            None => {
+                let content = self.content.as_mut()?;
+                return content.overlay(layout.children().next()?);
+            }
+        };
+
+        let (start, _) = self.label_bounds().get(open_menu).copied()?;
+        let position = Point::new(bounds.x.round() + start as f32, bounds.y.round() + 1.0);
+
+        Some(
+            MenuOverlay {
+                root: &self.root,
+                state: &mut self.state.menu,
+                first_level: 1,
+                passthrough: Some(Rectangle {
+                    height: 1.0,
+                    ..bounds
+                }),
+                style: self.style.menu,
+            }
+            .into_overlay(position),
+        )
+    }
+}
+
+impl<'a, Message> From<MenuBar<'a, Message>> for Element<'a, Message, TuiRenderer>
+where
+    Message: 'a + Clone,
+{
+    fn from(menu_bar: MenuBar<'a, Message>) -> Self {
+        Element::new(menu_bar)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MenuBar, State};
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::{Menu, MenuItem, TuiRenderer};
+    use iced_native::{
+        clipboard, keyboard, text_input, Cache, Element, Event, Point, Size, TextInput,
+        UserInterface,
+    };
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Message {
+        Open,
+        Save,
+        Recent(usize),
+        About,
+        Input(String),
+    }
+
+    fn menus() -> Vec<Menu<Message>> {
+        vec![
+            Menu::new("&File")
+                .push(MenuItem::new("&Open", Message::Open))
+                .push(MenuItem::new("&Save", Message::Save).enabled(false))
+                .separator()
+                .submenu(
+                    Menu::new("&Recent")
+                        .push(MenuItem::new("a.txt", Message::Recent(0)))
+                        .push(MenuItem::new("b.txt", Message::Recent(1))),
+                ),
+            Menu::new("&Help").push(MenuItem::new("&About", Message::About)),
+        ]
+    }
+
+    fn key_events(key_code: keyboard::KeyCode) -> [Event; 1] {
+        [Event::Keyboard(keyboard::Event::KeyPressed {
+            key_code,
+            modifiers: keyboard::Modifiers::default(),
+        })]
+    }
+
+    fn update(
+        ui: &mut UserInterface<'_, Message, TuiRenderer>,
+        renderer: &TuiRenderer,
+        events: &[Event],
+        messages: &mut Vec<Message>,
+    ) {
+        ui.update(
+            events,
+            Point::new(-1., -1.),
+            renderer,
+            &mut clipboard::Null,
+            messages,
+        );
+    }
+
+    #[test]
+    fn it_open_menus_with_accelerators() {
+        let mut renderer = TuiRenderer::default();
+        let mut state = State::new();
+        let mut messages = vec![];
+        let alt = keyboard::Modifiers {
+            alt: true,
+            ..keyboard::Modifiers::default()
+        };
+
+        let menu_bar: Element<'_, Message, TuiRenderer> = MenuBar::new(&mut state, menus()).into();
+        let mut ui = UserInterface::build(
+            menu_bar,
+            Size::new(20., 6.),
+            Cache::default(),
+            &mut renderer,
+        );
+
+        update(
+            &mut ui,
+            &renderer,
+            &[
+                Event::Keyboard(keyboard::Event::ModifiersChanged(alt)),
+                Event::Keyboard(keyboard::Event::CharacterReceived('f')),
+                Event::Keyboard(keyboard::Event::ModifiersChanged(
+                    keyboard::Modifiers::default(),
+                )),
+            ],
+            &mut messages,
+        );
+
+        // the separator is skipped, the submenu opens on its first item
+        update(
+            &mut ui,
+            &renderer,
+            &key_events(keyboard::KeyCode::Up),
+            &mut messages,
+        );
+        update(
+            &mut ui,
+            &renderer,
+            &key_events(keyboard::KeyCode::Right),
+            &mut messages,
+        );
+        update(
+            &mut ui,
+            &renderer,
+            &key_events(keyboard::KeyCode::Down),
+            &mut messages,
+        );
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(20, 6);
+        vbuffer.merge_primitive(&primitive);
+
+        let lines: Vec<String> = vbuffer
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.content.unwrap_or(' ')).collect())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                " File  Help         ",
+                " Open               ",
+                " Save               ",
+                "──────────          ",
+                " Recent ▸  a.txt    ",
+                "           b.txt    ",
+            ]
+        );
+        assert!(vbuffer.rows[0][1].style.is_underlined);
+        assert!(vbuffer.rows[4][1].style.is_underlined);
+
+        update(
+            &mut ui,
+            &renderer,
+            &key_events(keyboard::KeyCode::Enter),
+            &mut messages,
+        );
+
+        // disabled items can't be activated
+        update(
+            &mut ui,
+            &renderer,
+            &[
+                Event::Keyboard(keyboard::Event::ModifiersChanged(alt)),
+                Event::Keyboard(keyboard::Event::CharacterReceived('f')),
+                Event::Keyboard(keyboard::Event::ModifiersChanged(
+                    keyboard::Modifiers::default(),
+                )),
+            ],
+            &mut messages,
+        );
+        update(
+            &mut ui,
+            &renderer,
+            &[Event::Keyboard(keyboard::Event::CharacterReceived('s'))],
+            &mut messages,
+        );
+        update(
+            &mut ui,
+            &renderer,
+            &[Event::Keyboard(keyboard::Event::CharacterReceived('o'))],
+            &mut messages,
+        );
+
+        drop(ui);
+
+        assert_eq!(messages, vec![Message::Recent(1), Message::Open]);
+        assert!(!state.is_open());
+    }
+
+    #[test]
+    fn it_keep_the_key_closing_a_menu_from_the_content() {
+        let mut renderer = TuiRenderer::default();
+        let mut state = State::new();
+        let mut input = text_input::State::focused();
+        let mut messages = vec![];
+        let alt = keyboard::Modifiers {
+            alt: true,
+            ..keyboard::Modifiers::default()
+        };
+        let open_file = [
+            Event::Keyboard(keyboard::Event::ModifiersChanged(alt)),
+            Event::Keyboard(keyboard::Event::CharacterReceived('f')),
+            Event::Keyboard(keyboard::Event::ModifiersChanged(
+                keyboard::Modifiers::default(),
+            )),
+        ];
+
+        let menu_bar: Element<'_, Message, TuiRenderer> = MenuBar::new(&mut state, menus())
+            .content(TextInput::new(&mut input, "", "", Message::Input))
+            .into();
+        let mut ui = UserInterface::build(
+            menu_bar,
+            Size::new(20., 6.),
+            Cache::default(),
+            &mut renderer,
+        );
+
+        // closed by activating an item
+        update(&mut ui, &renderer, &open_file, &mut messages);
+        update(
+            &mut ui,
+            &renderer,
+            &[Event::Keyboard(keyboard::Event::CharacterReceived('o'))],
+            &mut messages,
+        );
+
+        // closed with escape, the keys after it go to the content
+        update(&mut ui, &renderer, &open_file, &mut messages);
+        let [escape] = key_events(keyboard::KeyCode::Escape);
+        update(
+            &mut ui,
+            &renderer,
+            &[
+                escape,
+                Event::Keyboard(keyboard::Event::CharacterReceived('y')),
+            ],
+            &mut messages,
+        );
+
+        // the content gets the keys again once the menus are closed
+        update(
+            &mut ui,
+            &renderer,
+            &[Event::Keyboard(keyboard::Event::CharacterReceived('x'))],
+            &mut messages,
+        );
+
+        drop(ui);
+
+        assert_eq!(
+            messages,
+            vec![
+                Message::Open,
+                Message::Input(String::from("y")),
+                Message::Input(String::from("yx"))
+            ]
+        );
+    }
+}
@@ -0,0 +1,31 @@
+pub mod bar_chart;
+pub mod canvas;
+pub mod chart;
+pub mod context_menu;
+pub mod gauge;
+pub mod menu;
+pub mod menu_bar;
+pub mod modal;
+pub mod sparkline;
+pub mod spinner;
+pub mod table;
+pub mod tabs;
+pub mod text_editor;
+pub mod tree;
+pub mod virtual_list;
+
+pub use bar_chart::BarChart;
+pub use canvas::{Canvas, Resolution, Shape};
+pub use chart::ChartStyle;
+pub use context_menu::ContextMenu;
+pub use gauge::{Gauge, GaugeStyle};
+pub use menu::{Menu, MenuItem};
+pub use menu_bar::{MenuBar, MenuBarStyle};
+pub use modal::{Modal, ModalStyle};
+pub use sparkline::Sparkline;
+pub use spinner::{Spinner, SpinnerFrames};
+pub use table::{ColumnWidth, SortOrder, Table, TableColumn, TableStyle};
+pub use tabs::{Tabs, TabsStyle};
+pub use text_editor::{TextEditor, TextEditorStyle};
+pub use tree::{Tree, TreeNode, TreeStyle};
+pub use virtual_list::{VirtualList, VirtualListStyle};
@@ -0,0 +1,507 @@
+//! Show a dialog over the rest of the user interface.
+use crate::renderer::primitives::{Cell, Primitive};
+use crate::{Border, ButtonStyle, ContainerStyle, Style, TuiRenderer};
+use iced_native::{
+    button, event, keyboard, layout, overlay, Button, Clipboard, Column, Container, Element, Event,
+    Hasher, Layout, Length, Point, Rectangle, Row, Size, Text, Widget,
+};
+
+/// A dialog drawn centered over a base view, with a dimmed backdrop.
+///
+/// While the dialog is shown, the base view gets no events and the keyboard focus stays
+/// in the dialog. Escape closes it with the message set by [`Modal::on_close`].
+pub struct Modal<'a, Message> {
+    state: &'a mut State,
+    base: Element<'a, Message, TuiRenderer>,
+    content: Option<Element<'a, Message, TuiRenderer>>,
+    confirm: Option<(String, Message)>,
+    cancel: Option<(String, Message)>,
+    on_close: Option<Message>,
+    style: ModalStyle,
+}
+
+impl<'a, Message> Modal<'a, Message>
+where
+    Message: 'a + Clone,
+{
+    pub fn new<E>(state: &'a mut State, base: E) -> Self
+    where
+        E: Into<Element<'a, Message, TuiRenderer>>,
+    {
+        Self {
+            state,
+            base: base.into(),
+            content: None,
+            confirm: None,
+            cancel: None,
+            on_close: None,
+            style: ModalStyle::default(),
+        }
+    }
+
+    /// Shows a dialog with the given content over the base view.
+    pub fn dialog<E>(mut self, content: E) -> Self
+    where
+        E: Into<Element<'a, Message, TuiRenderer>>,
+    {
+        self.content = Some(content.into());
+        self
+    }
+
+    /// Adds a confirm button below the content of the dialog.
+    pub fn confirm<T>(mut self, label: T, message: Message) -> Self
+    where
+        T: Into<String>,
+    {
+        self.confirm = Some((label.into(), message));
+        self
+    }
+
+    /// Adds a cancel button below the content of the dialog.
+    ///
+    /// Its message also closes the dialog with Escape, unless set by [`Modal::on_close`].
+    pub fn cancel<T>(mut self, label: T, message: Message) -> Self
+    where
+        T: Into<String>,
+    {
+        self.cancel = Some((label.into(), message));
+        self
+    }
+
+    /// Sets the message produced when Escape is pressed.
+    pub fn on_close(mut self, message: Message) -> Self {
+        self.on_close = Some(message);
+        self
+    }
+
+    pub fn style(mut self, style: ModalStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Puts the content and the buttons of the dialog in a box.
+    fn build_dialog(
+        content: Element<'a, Message, TuiRenderer>,
+        state: &'a mut State,
+        confirm: Option<(String, Message)>,
+        cancel: Option<(String, Message)>,
+        style: ModalStyle,
+    ) -> Element<'a, Message, TuiRenderer> {
+        let mut column = Column::new().spacing(1).push(content);
+
+        if confirm.is_some() || cancel.is_some() {
+            let mut buttons = Row::new().spacing(2);
+
+            if let Some((label, message)) = confirm {
+                buttons = buttons.push(
+                    Button::new(&mut state.confirm, Text::new(label))
+                        .on_press(message)
+                        .style(style.button),
+                );
+            }
+
+            if let Some((label, message)) = cancel {
+                buttons = buttons.push(
+                    Button::new(&mut state.cancel, Text::new(label))
+                        .on_press(message)
+                        .style(style.button),
+                );
+            }
+
+            column = column.push(buttons);
+        }
+
+        // the border is drawn over the padding, so the content stays where it was laid out
+        Container::new(column)
+            .padding(style.container.border.width())
+            .style(style.container)
+            .into()
+    }
+}
+
+/// The state of the buttons of a [`Modal`].
+#[derive(Debug, Clone, Default)]
+pub struct State {
+    confirm: button::State,
+    cancel: button::State,
+}
+
+impl State {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ModalStyle {
+    pub(crate) container: ContainerStyle,
+    pub(crate) button: ButtonStyle,
+    pub(crate) backdrop: f32,
+}
+
+impl Default for ModalStyle {
+    fn default() -> Self {
+        Self {
+            container: ContainerStyle::new().border(Border::Light),
+            button: ButtonStyle::default(),
+            backdrop: 0.5,
+        }
+    }
+}
+
+impl ModalStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the style of the box of the dialog.
+    ///
+    /// Without a background color, the dimmed colors of the base view show through.
+    pub fn container(mut self, container: ContainerStyle) -> Self {
+        self.container = container;
+        self
+    }
+
+    pub fn button(mut self, button: ButtonStyle) -> Self {
+        self.button = button;
+        self
+    }
+
+    /// Sets how much the base view is darkened, from `0.0` (unchanged) to `1.0` (black).
+    pub fn backdrop(mut self, backdrop: f32) -> Self {
+        self.backdrop = backdrop;
+        self
+    }
+}
+
+/// The widget of a [`Modal`], with its dialog built.
+struct ModalWidget<'a, Message> {
+    base: Element<'a, Message, TuiRenderer>,
+    dialog: Option<Element<'a, Message, TuiRenderer>>,
+    on_close: Option<Message>,
+    backdrop: f32,
+}
+
+impl<'a, Message> Widget<Message, TuiRenderer> for ModalWidget<'a, Message>
+where
+    Message: Clone,
+{
+    fn width(&self) -> Length {
+        self.base.width()
+    }
+
+    fn height(&self) -> Length {
+        self.base.height()
+    }
+
+    fn layout(&self, renderer: &TuiRenderer, limits: &layout::Limits) -> layout::Node {
+        self.base.layout(renderer, limits)
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        use std::hash::Hash;
+
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+
+        self.base.hash_layout(state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &TuiRenderer,
+        clipboard: &mut dyn Clipboard,
+        messages: &mut Vec<Message>,
+    ) -> event::Status {
+        // the dialog gets the events through its overlay
+        if self.dialog.is_some() {
+            return event::Status::Ignored;
+        }
+
+        self.base.on_event(
+            event,
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            messages,
+        )
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut TuiRenderer,
+        defaults: &Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) -> Primitive {
+        self.base
+            .draw(renderer, defaults, layout, cursor_position, viewport)
+    }
+
+    fn overlay(
+        &mut self,
+        layout: Layout<'_>,
+    ) -> Option<overlay::Element<'_, Message, TuiRenderer>> {
+        match &mut self.dialog {
+            Some(dialog) => Some(overlay::Element::new(
+                Point::ORIGIN,
+                Box::new(ModalOverlay {
+                    dialog,
+                    on_close: self.on_close.clone(),
+                    backdrop: self.backdrop,
+                }),
+            )),
+            None => self.base.overlay(layout),
+        }
+    }
+}
+
+/// The overlay covering the screen with the backdrop and the dialog.
+struct ModalOverlay<'a, 'b, Message> {
+    dialog: &'b mut Element<'a, Message, TuiRenderer>,
+    on_close: Option<Message>,
+    backdrop: f32,
+}
+
+impl<'a, 'b, Message> overlay::Overlay<Message, TuiRenderer> for ModalOverlay<'a, 'b, Message>
+where
+    Message: Clone,
+{
+    fn layout(&self, renderer: &TuiRenderer, bounds: Size, _position: Point) -> layout::Node {
+        let limits = layout::Limits::new(Size::ZERO, bounds);
+        let mut dialog = self.dialog.layout(renderer, &limits);
+
+        let size = dialog.size();
+        dialog.move_to(Point::new(
+            ((bounds.width - size.width) / 2.0).round(),
+            ((bounds.height - size.height) / 2.0).round(),
+        ));
+
+        layout::Node::with_children(bounds, vec![dialog])
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut TuiRenderer,
+        defaults: &Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+    ) -> Primitive {
+        let bounds = layout.bounds();
+        let dialog_layout = layout.children().next().unwrap();
+        let dialog_bounds = dialog_layout.bounds();
+
+        renderer.begin_focus_trap();
+        let dialog = self
+            .dialog
+            .draw(renderer, defaults, dialog_layout, cursor_position, &bounds);
+        renderer.end_focus_trap();
+
+        Primitive::Group(vec![
+            Primitive::Dim(
+                bounds.x as u16,
+                bounds.y as u16,
+                bounds.width as u16,
+                bounds.height as u16,
+                self.backdrop,
+            ),
+            // hide the base view below the dialog
+            Primitive::Rectangle(
+                dialog_bounds.x as u16,
+                dialog_bounds.y as u16,
+                dialog_bounds.width as u16,
+                dialog_bounds.height as u16,
+                Cell::from_char(' '),
+            ),
+            dialog,
+        ])
+    }
+
+    fn hash_layout(&self, state: &mut Hasher, _position: Point) {
+        use std::hash::Hash;
+
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+
+        self.dialog.hash_layout(state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &TuiRenderer,
+        clipboard: &mut dyn Clipboard,
+        messages: &mut Vec<Message>,
+    ) -> event::Status {
+        let status = self.dialog.on_event(
+            event.clone(),
+            layout.children().next().unwrap(),
+            cursor_position,
+            renderer,
+            clipboard,
+            messages,
+        );
+
+        match event {
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code: keyboard::KeyCode::Escape,
+                ..
+            }) if status == event::Status::Ignored => match &self.on_close {
+                Some(on_close) => {
+                    messages.push(on_close.clone());
+                    event::Status::Captured
+                }
+                None => event::Status::Ignored,
+            },
+            _ => status,
+        }
+    }
+}
+
+impl<'a, Message> From<Modal<'a, Message>> for Element<'a, Message, TuiRenderer>
+where
+    Message: 'a + Clone,
+{
+    fn from(modal: Modal<'a, Message>) -> Self {
+        let Modal {
+            state,
+            base,
+            content,
+            confirm,
+            cancel,
+            on_close,
+            style,
+        } = modal;
+
+        let on_close = on_close.or_else(|| cancel.as_ref().map(|(_, message)| message.clone()));
+        let backdrop = style.backdrop;
+
+        let dialog =
+            content.map(move |content| Modal::build_dialog(content, state, confirm, cancel, style));
+
+        Element::new(ModalWidget {
+            base,
+            dialog,
+            on_close,
+            backdrop,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Modal, ModalStyle, State};
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::{Style, TuiRenderer};
+    use iced_native::{
+        clipboard, keyboard, mouse, Cache, Color, Element, Event, Point, Size, Text, UserInterface,
+    };
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Message {
+        Confirm,
+        Cancel,
+    }
+
+    #[test]
+    fn it_draw_dialog_over_base() {
+        let mut renderer = TuiRenderer::default();
+        let mut state = State::new();
+
+        let modal: Element<'_, Message, TuiRenderer> =
+            Modal::new(&mut state, Text::new("base view text.."))
+                .dialog(Text::new("Quit?"))
+                .confirm("Yes", Message::Confirm)
+                .cancel("No", Message::Cancel)
+                .into();
+        let mut ui =
+            UserInterface::build(modal, Size::new(16., 7.), Cache::default(), &mut renderer);
+
+        renderer.begin_frame();
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        renderer.end_frame();
+
+        let mut vbuffer = VirtualBuffer::from_size(16, 7);
+        vbuffer.merge_primitive(&primitive);
+
+        let lines: Vec<String> = vbuffer
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.content.unwrap_or(' ')).collect())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "base view text..",
+                "    ┌───────┐   ",
+                "    │Quit?  │   ",
+                "    │       │   ",
+                "    │Yes  No│   ",
+                "    └───────┘   ",
+                "                ",
+            ]
+        );
+
+        // the base view is dimmed
+        assert!(vbuffer.rows[0][0].style.fg_color.is_some());
+
+        // the focus only goes through the buttons, escape cancels
+        let mut messages = vec![];
+        let tab = Event::Keyboard(keyboard::Event::KeyPressed {
+            key_code: keyboard::KeyCode::Tab,
+            modifiers: keyboard::Modifiers::default(),
+        });
+        renderer
+            .focus_chain
+            .split_events(vec![tab.clone(), tab.clone(), tab], Point::ORIGIN);
+        let focused = renderer.focus_chain.focused().unwrap();
+        assert_eq!(focused.bounds.position(), Point::new(5., 4.));
+
+        ui.update(
+            &[
+                Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+                Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)),
+                Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::Escape,
+                    modifiers: keyboard::Modifiers::default(),
+                }),
+            ],
+            focused.bounds.center(),
+            &renderer,
+            &mut clipboard::Null,
+            &mut messages,
+        );
+
+        assert_eq!(messages, vec![Message::Confirm, Message::Cancel]);
+    }
+
+    #[test]
+    fn it_darken_base_by_backdrop() {
+        let mut renderer = TuiRenderer::default();
+        let mut state = State::new();
+
+        let base = Text::new("base").font(Style::new().fg(Color::from_rgb8(200, 100, 0)));
+        let modal: Element<'_, Message, TuiRenderer> = Modal::new(&mut state, base)
+            .dialog(Text::new("Quit?"))
+            .style(ModalStyle::new().backdrop(0.75))
+            .into();
+        let mut ui =
+            UserInterface::build(modal, Size::new(16., 9.), Cache::default(), &mut renderer);
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(16, 9);
+        vbuffer.merge_primitive(&primitive);
+
+        assert_eq!(
+            vbuffer.rows[0][0].style.fg_color,
+            Style::new().fg(Color::from_rgb8(50, 25, 0)).fg_color
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Show the trend of a series of values in a compact chart.
+use super::chart::{bar_block, ChartStyle, Scale};
+use crate::renderer::primitives::{Cell, Primitive};
+use crate::{Style, TuiRenderer};
+use iced_native::{layout, Element, Hasher, Layout, Length, Point, Rectangle, Size, Widget};
+
+/// A chart with a column of `▁▂▃▄▅▆▇█` blocks per value.
+///
+/// When the values don't fit in the width, the latest ones are shown. Each value is at
+/// least drawn as `▁`, so the line doesn't break on the minimum.
+pub struct Sparkline {
+    values: Vec<f64>,
+    min: Option<f64>,
+    max: Option<f64>,
+    width: Length,
+    height: Length,
+    style: ChartStyle,
+}
+
+impl Sparkline {
+    pub fn new<I>(values: I) -> Self
+    where
+        I: IntoIterator<Item = f64>,
+    {
+        Self {
+            values: values.into_iter().collect(),
+            min: None,
+            max: None,
+            width: Length::Shrink,
+            height: Length::Shrink,
+            style: ChartStyle::default(),
+        }
+    }
+
+    /// Sets the value at the bottom of the chart, the minimum of the values by default.
+    pub fn min(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// Sets the value at the top of the chart, the maximum of the values by default.
+    pub fn max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn style(mut self, style: ChartStyle) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+impl<Message> Widget<Message, TuiRenderer> for Sparkline {
+    fn width(&self) -> Length {
+        self.width
+    }
+
+    fn height(&self) -> Length {
+        self.height
+    }
+
+    fn layout(&self, _renderer: &TuiRenderer, limits: &layout::Limits) -> layout::Node {
+        let size = limits
+            .width(self.width)
+            .height(self.height)
+            .resolve(Size::new(self.values.len() as f32, 1.0));
+
+        layout::Node::new(size)
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        use std::hash::Hash;
+
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+
+        self.width.hash(state);
+        self.height.hash(state);
+        self.values.len().hash(state);
+    }
+
+    fn draw(
+        &self,
+        _renderer: &mut TuiRenderer,
+        _defaults: &Style,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+        _viewport: &Rectangle,
+    ) -> Primitive {
+        let bounds = layout.bounds();
+        let x = bounds.x.round() as u16;
+        let y = bounds.y.round() as u16;
+        let width = bounds.width.round() as usize;
+        let height = bounds.height.round() as u16;
+
+        let scale = Scale::new(&self.values, self.min, self.max);
+        let values = &self.values[self.values.len().saturating_sub(width)..];
+        let mut primitives = vec![];
+
+        for (column, value) in values.iter().enumerate() {
+            let eighths = scale.eighths(*value, height).max(1);
+            let style = self.style.value_style(*value);
+
+            for row in 0..height {
+                if let Some(block) = bar_block(eighths, row) {
+                    primitives.push(Primitive::Cell(
+                        x + column as u16,
+                        y + height - 1 - row,
+                        Cell::from_char(block).style(style),
+                    ));
+                }
+            }
+        }
+
+        Primitive::Group(primitives)
+    }
+}
+
+impl<'a, Message> From<Sparkline> for Element<'a, Message, TuiRenderer>
+where
+    Message: 'a,
+{
+    fn from(sparkline: Sparkline) -> Self {
+        Element::new(sparkline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Sparkline;
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::TuiRenderer;
+    use iced_native::{Cache, Element, Length, Point, Size, UserInterface};
+
+    fn draw(sparkline: Sparkline, width: u16, height: u16) -> Vec<String> {
+        let mut renderer = TuiRenderer::default();
+        let sparkline: Element<'_, (), TuiRenderer> = sparkline.into();
+        let mut ui = UserInterface::build(
+            sparkline,
+            Size::new(width as f32, height as f32),
+            Cache::default(),
+            &mut renderer,
+        );
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(width, height);
+        vbuffer.merge_primitive(&primitive);
+
+        vbuffer
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.content.unwrap_or(' ')).collect())
+            .collect()
+    }
+
+    #[test]
+    fn it_draw_sparkline() {
+        let values = (0..=8).map(|value| value as f64);
+
+        assert_eq!(
+            draw(Sparkline::new(values.clone()), 10, 1),
+            vec!["▁▁▂▃▄▅▆▇█ "]
+        );
+        // only the latest values fit
+        assert_eq!(draw(Sparkline::new(values.clone()), 4, 1), vec!["▅▆▇█"]);
+        assert_eq!(
+            draw(
+                Sparkline::new(values.map(|value| value * 2.0))
+                    .max(16.0)
+                    .height(Length::Fill),
+                9,
+                2
+            ),
+            vec!["     ▂▄▆█", "▁▂▄▆█████"]
+        );
+    }
+}
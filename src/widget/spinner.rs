@@ -0,0 +1,207 @@
+//! Show that some work is in progress with an animation.
+use crate::renderer::primitives::Primitive;
+use crate::renderer::utils::{text_cells, text_width};
+use crate::{Style, TuiRenderer};
+use iced_native::{layout, Element, Hasher, Layout, Length, Point, Rectangle, Size, Widget};
+use std::time::Duration;
+
+/// An animated activity indicator.
+///
+/// The runtime draws the user interface again at each frame of the spinners being
+/// shown, so a spinner animates on its own and stops ticking once it's gone.
+pub struct Spinner {
+    frames: SpinnerFrames,
+    interval: Duration,
+    style: Style,
+}
+
+impl Spinner {
+    pub fn new() -> Self {
+        Self {
+            frames: SpinnerFrames::Braille,
+            interval: Duration::from_millis(100),
+            style: Style::default(),
+        }
+    }
+
+    pub fn frames(mut self, frames: SpinnerFrames) -> Self {
+        self.frames = frames;
+        self
+    }
+
+    /// Sets the duration of each frame.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Returns the frame to show after the elapsed time.
+    fn frame(&self, elapsed: Duration) -> &str {
+        let frames = self.frames.frames();
+        let interval = self.interval.as_millis().max(1);
+
+        match frames.len() {
+            0 => "",
+            len => frames[(elapsed.as_millis() / interval) as usize % len],
+        }
+    }
+}
+
+impl Default for Spinner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The frames of a [`Spinner`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpinnerFrames {
+    /// `⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏`
+    Braille,
+    /// Dots appearing one after the other.
+    Dots,
+    /// `|/-\`
+    Line,
+    /// `◜◠◝◞◡◟`
+    Arc,
+    Custom(Vec<&'static str>),
+}
+
+impl SpinnerFrames {
+    fn frames(&self) -> &[&'static str] {
+        match self {
+            SpinnerFrames::Braille => &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+            SpinnerFrames::Dots => &["   ", ".  ", ".. ", "..."],
+            SpinnerFrames::Line => &["|", "/", "-", "\\"],
+            SpinnerFrames::Arc => &["◜", "◠", "◝", "◞", "◡", "◟"],
+            SpinnerFrames::Custom(frames) => frames,
+        }
+    }
+
+    /// Returns the width of the widest frame.
+    fn width(&self) -> u16 {
+        self.frames()
+            .iter()
+            .map(|frame| text_width(frame))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl<Message> Widget<Message, TuiRenderer> for Spinner {
+    fn width(&self) -> Length {
+        Length::Shrink
+    }
+
+    fn height(&self) -> Length {
+        Length::Shrink
+    }
+
+    fn layout(&self, _renderer: &TuiRenderer, limits: &layout::Limits) -> layout::Node {
+        let size = limits
+            .width(Length::Shrink)
+            .height(Length::Shrink)
+            .resolve(Size::new(self.frames.width() as f32, 1.0));
+
+        layout::Node::new(size)
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        use std::hash::Hash;
+
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+
+        self.frames.width().hash(state);
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut TuiRenderer,
+        _defaults: &Style,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+        viewport: &Rectangle,
+    ) -> Primitive {
+        let bounds = layout.bounds();
+
+        // a spinner scrolled out of sight doesn't keep the runtime ticking
+        if bounds.width < 1.0 || bounds.intersection(viewport).is_none() {
+            return Primitive::Group(vec![]);
+        }
+
+        renderer.request_animation(self.interval);
+
+        let frame = self.frame(renderer.animation_time());
+
+        Primitive::Group(text_cells(
+            frame,
+            bounds.x.round() as u16,
+            bounds.y.round() as u16,
+            self.style,
+        ))
+    }
+}
+
+impl<'a, Message> From<Spinner> for Element<'a, Message, TuiRenderer>
+where
+    Message: 'a,
+{
+    fn from(spinner: Spinner) -> Self {
+        Element::new(spinner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Spinner, SpinnerFrames};
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::TuiRenderer;
+    use iced_native::{Cache, Column, Element, Point, Size, Text, UserInterface};
+    use std::time::Duration;
+
+    fn draw(renderer: &mut TuiRenderer, show_spinner: bool, elapsed: u64) -> String {
+        let mut column = Column::new().push(Text::new("loading"));
+
+        if show_spinner {
+            column = column.push(Spinner::new().frames(SpinnerFrames::Line));
+        }
+
+        let column: Element<'_, (), TuiRenderer> = column.into();
+        let mut ui = UserInterface::build(column, Size::new(8., 2.), Cache::default(), renderer);
+
+        renderer.begin_frame();
+        renderer.animation_time = Duration::from_millis(elapsed);
+        let primitive = ui.draw(renderer, Point::new(-1., -1.));
+        renderer.end_frame();
+
+        let mut vbuffer = VirtualBuffer::from_size(8, 2);
+        vbuffer.merge_primitive(&primitive);
+
+        vbuffer.rows[1]
+            .iter()
+            .map(|cell| cell.content.unwrap_or(' '))
+            .collect()
+    }
+
+    #[test]
+    fn it_tick_while_visible() {
+        let mut renderer = TuiRenderer::default();
+
+        assert_eq!(draw(&mut renderer, true, 0), "|       ");
+        assert_eq!(draw(&mut renderer, true, 150), "/       ");
+        assert_eq!(draw(&mut renderer, true, 420), "|       ");
+        assert_eq!(
+            renderer.animation_interval(),
+            Some(Duration::from_millis(100))
+        );
+
+        assert_eq!(draw(&mut renderer, false, 500), "        ");
+        assert_eq!(renderer.animation_interval(), None);
+    }
+}
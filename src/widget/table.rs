@@ -0,0 +1,795 @@
+//! Display rows of data in columns.
+use crate::renderer::focus::FocusKind;
+use crate::renderer::primitives::{Cell, Primitive};
+use crate::renderer::utils::{text_cells, text_width, truncate_text};
+use crate::{Style, TuiRenderer};
+use iced_native::{
+    event, keyboard, layout, mouse, Clipboard, Element, Event, Hasher, HorizontalAlignment, Layout,
+    Length, Point, Rectangle, Size, Widget,
+};
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher as _};
+use std::rc::Rc;
+
+/// A table of text rows, with a header and selectable rows.
+///
+/// Only the rows visible in the table are drawn, so it can display a large number of rows.
+#[allow(clippy::type_complexity)]
+pub struct Table<'a, Message> {
+    state: &'a mut State,
+    columns: Vec<TableColumn>,
+    rows: &'a [Vec<String>],
+    on_select: Option<Box<dyn Fn(usize) -> Message + 'a>>,
+    on_sort: Option<Box<dyn Fn(usize, SortOrder) -> Message + 'a>>,
+    width: Length,
+    height: Length,
+    column_spacing: u16,
+    style: TableStyle,
+}
+
+impl<'a, Message> Table<'a, Message> {
+    pub fn new(state: &'a mut State, columns: Vec<TableColumn>, rows: &'a [Vec<String>]) -> Self {
+        Self {
+            state,
+            columns,
+            rows,
+            on_select: None,
+            on_sort: None,
+            width: Length::Fill,
+            height: Length::Fill,
+            column_spacing: 1,
+            style: TableStyle::default(),
+        }
+    }
+
+    /// Sets the message produced with the index of the selected row (in the given rows).
+    pub fn on_select<F>(mut self, on_select: F) -> Self
+    where
+        F: 'a + Fn(usize) -> Message,
+    {
+        self.on_select = Some(Box::new(on_select));
+        self
+    }
+
+    /// Sets the message produced when the rows are sorted by clicking a header.
+    pub fn on_sort<F>(mut self, on_sort: F) -> Self
+    where
+        F: 'a + Fn(usize, SortOrder) -> Message,
+    {
+        self.on_sort = Some(Box::new(on_sort));
+        self
+    }
+
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn column_spacing(mut self, column_spacing: u16) -> Self {
+        self.column_spacing = column_spacing;
+        self
+    }
+
+    pub fn style(mut self, style: TableStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Returns the indexes of the rows, in their displayed order.
+    ///
+    /// The order is kept in the state, and only sorted again when the sort or the cells of
+    /// the sorted column changed.
+    fn display_order(&self) -> Rc<[usize]> {
+        let mut cache = self.state.order.borrow_mut();
+        let key = self.sort_key();
+
+        if let Some(sorted) = &*cache {
+            if sorted.sort == self.state.sort && sorted.key == key {
+                return sorted.order.clone();
+            }
+        }
+
+        let order: Rc<[usize]> = self.sort_rows().into();
+        *cache = Some(SortedRows {
+            sort: self.state.sort,
+            key,
+            order: order.clone(),
+        });
+        order
+    }
+
+    /// Returns a hash of the number of rows and of the cells of the sorted column.
+    fn sort_key(&self) -> u64 {
+        let mut hasher = Hasher::default();
+        self.rows.len().hash(&mut hasher);
+
+        if let Some((column, _)) = self.state.sort {
+            for row in self.rows {
+                row.get(column).hash(&mut hasher);
+            }
+        }
+
+        hasher.finish()
+    }
+
+    fn sort_rows(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.rows.len()).collect();
+
+        if let Some((column, sort_order)) = self.state.sort {
+            order.sort_by(|a, b| {
+                let ordering = compare_cells(
+                    self.rows[*a].get(column).map(String::as_str).unwrap_or(""),
+                    self.rows[*b].get(column).map(String::as_str).unwrap_or(""),
+                );
+
+                match sort_order {
+                    SortOrder::Ascending => ordering,
+                    SortOrder::Descending => ordering.reverse(),
+                }
+            });
+        }
+
+        order
+    }
+
+    /// Returns the start and width of each column in the given width.
+    fn column_bounds(&self, width: u16) -> Vec<(u16, u16)> {
+        let spacing = self.column_spacing * self.columns.len().saturating_sub(1) as u16;
+        let available = width.saturating_sub(spacing);
+
+        let mut widths: Vec<u16> = self
+            .columns
+            .iter()
+            .map(|column| match column.width {
+                ColumnWidth::Fixed(width) => width,
+                ColumnWidth::Percent(percent) => (available as u32 * percent as u32 / 100) as u16,
+                ColumnWidth::Fill(_) => 0,
+            })
+            .collect();
+
+        let remaining = available.saturating_sub(widths.iter().sum());
+        let portions: u16 = self
+            .columns
+            .iter()
+            .map(|column| match column.width {
+                ColumnWidth::Fill(portion) => portion,
+                _ => 0,
+            })
+            .sum();
+
+        if portions > 0 {
+            let mut distributed = 0;
+            let mut filled_portions = 0;
+
+            for (column, width) in self.columns.iter().zip(widths.iter_mut()) {
+                if let ColumnWidth::Fill(portion) = column.width {
+                    // the rounding errors go to the last fill columns
+                    filled_portions += portion;
+                    let end = (remaining as u32 * filled_portions as u32 / portions as u32) as u16;
+                    *width = end - distributed;
+                    distributed = end;
+                }
+            }
+        }
+
+        let mut x = 0;
+
+        widths
+            .into_iter()
+            .map(|column_width| {
+                let start = x.min(width);
+                let column_width = column_width.min(width - start);
+                x = start + column_width + self.column_spacing;
+                (start, column_width)
+            })
+            .collect()
+    }
+
+    fn select(&mut self, order: &[usize], display_index: usize, messages: &mut Vec<Message>) {
+        let row = match order.get(display_index) {
+            Some(row) => *row,
+            None => return,
+        };
+
+        if self.state.selected != Some(row) {
+            self.state.selected = Some(row);
+
+            if let Some(on_select) = &self.on_select {
+                messages.push(on_select(row));
+            }
+        }
+    }
+
+    /// Scrolls just enough to show the selected row.
+    fn scroll_to_selected(&mut self, order: &[usize], height: usize) {
+        let display_index = match self.state.selected {
+            Some(selected) => order.iter().position(|row| *row == selected),
+            None => None,
+        };
+
+        if let Some(display_index) = display_index {
+            if display_index < self.state.scroll {
+                self.state.scroll = display_index;
+            } else if height > 0 && display_index >= self.state.scroll + height {
+                self.state.scroll = display_index + 1 - height;
+            }
+        }
+
+        self.state.scroll = self.state.scroll.min(order.len().saturating_sub(height));
+    }
+}
+
+/// A column of a [`Table`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableColumn {
+    title: String,
+    width: ColumnWidth,
+    alignment: HorizontalAlignment,
+    sortable: bool,
+}
+
+impl TableColumn {
+    pub fn new<T>(title: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            title: title.into(),
+            width: ColumnWidth::Fill(1),
+            alignment: HorizontalAlignment::Left,
+            sortable: true,
+        }
+    }
+
+    pub fn width(mut self, width: ColumnWidth) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn alignment(mut self, alignment: HorizontalAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Sets whether clicking the header of the column sorts the rows.
+    pub fn sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+}
+
+/// The width of a [`TableColumn`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnWidth {
+    /// A fixed number of cells.
+    Fixed(u16),
+    /// A percentage of the width of the table.
+    Percent(u16),
+    /// A portion of the width left by the other columns.
+    Fill(u16),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// The state of a [`Table`].
+#[derive(Debug, Clone, Default)]
+pub struct State {
+    selected: Option<usize>,
+    sort: Option<(usize, SortOrder)>,
+    scroll: usize,
+    order: RefCell<Option<SortedRows>>,
+}
+
+/// The display order of the rows, with the sort and the hash of the sorted cells it is for.
+#[derive(Debug, Clone)]
+struct SortedRows {
+    sort: Option<(usize, SortOrder)>,
+    key: u64,
+    order: Rc<[usize]>,
+}
+
+impl State {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the index of the selected row, in the rows given to the table.
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    pub fn select(&mut self, selected: Option<usize>) {
+        self.selected = selected;
+    }
+
+    /// Returns the column the rows are sorted by, and their order.
+    pub fn sort(&self) -> Option<(usize, SortOrder)> {
+        self.sort
+    }
+
+    pub fn sort_by(&mut self, column: usize, order: SortOrder) {
+        self.sort = Some((column, order));
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TableStyle {
+    pub(crate) normal: Style,
+    pub(crate) header: Style,
+    pub(crate) selected: Style,
+    pub(crate) focused_selected: Style,
+}
+
+impl Default for TableStyle {
+    fn default() -> Self {
+        Self {
+            normal: Style::default(),
+            header: Style::new().bold(),
+            selected: Style::default(),
+            focused_selected: Style::default(),
+        }
+    }
+}
+
+impl TableStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn normal(mut self, normal: Style) -> Self {
+        self.normal = normal;
+        self
+    }
+
+    pub fn header(mut self, header: Style) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Sets the style of the selected row.
+    ///
+    /// When not set, the selected row is drawn in bold.
+    pub fn selected(mut self, selected: Style) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Sets the style of the selected row when the table has the keyboard focus.
+    pub fn focused_selected(mut self, focused_selected: Style) -> Self {
+        self.focused_selected = focused_selected;
+        self
+    }
+}
+
+impl<'a, Message> Widget<Message, TuiRenderer> for Table<'a, Message> {
+    fn width(&self) -> Length {
+        self.width
+    }
+
+    fn height(&self) -> Length {
+        self.height
+    }
+
+    fn layout(&self, _renderer: &TuiRenderer, limits: &layout::Limits) -> layout::Node {
+        let limits = limits.width(self.width).height(self.height);
+        let intrinsic = Size::new(0.0, self.rows.len() as f32 + 1.0);
+
+        layout::Node::new(limits.resolve(intrinsic))
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        use std::hash::Hash;
+
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+
+        self.width.hash(state);
+        self.height.hash(state);
+        self.rows.len().hash(state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &TuiRenderer,
+        _clipboard: &mut dyn Clipboard,
+        messages: &mut Vec<Message>,
+    ) -> event::Status {
+        let bounds = layout.bounds();
+        let height = (bounds.height.round() as usize).saturating_sub(1);
+        let order = self.display_order();
+
+        let status = match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+                if bounds.contains(cursor_position) =>
+            {
+                let x = (cursor_position.x - bounds.x.round()) as u16;
+                let y = (cursor_position.y - bounds.y.round()) as usize;
+
+                if y == 0 {
+                    self.header_clicked(x, bounds.width.round() as u16, messages);
+                } else {
+                    self.select(&order, self.state.scroll + y - 1, messages);
+                }
+
+                event::Status::Captured
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta })
+                if bounds.contains(cursor_position) =>
+            {
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y,
+                };
+                let max_scroll = order.len().saturating_sub(height);
+                let scroll = self.state.scroll as isize - lines.round() as isize;
+                self.state.scroll = scroll.clamp(0, max_scroll as isize) as usize;
+
+                // scrolling doesn't move the selection
+                return event::Status::Captured;
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. })
+                if renderer.is_focused(bounds) && !order.is_empty() =>
+            {
+                let last = order.len() - 1;
+                let current = self
+                    .state
+                    .selected
+                    .and_then(|selected| order.iter().position(|row| *row == selected));
+                let page = height.max(1);
+
+                let target = match (key_code, current) {
+                    (keyboard::KeyCode::Up, Some(current)) => Some(current.saturating_sub(1)),
+                    (keyboard::KeyCode::Down, Some(current)) => Some((current + 1).min(last)),
+                    (keyboard::KeyCode::PageUp, Some(current)) => {
+                        Some(current.saturating_sub(page))
+                    }
+                    (keyboard::KeyCode::PageDown, Some(current)) => {
+                        Some((current + page).min(last))
+                    }
+                    (keyboard::KeyCode::Up, None) | (keyboard::KeyCode::PageUp, None) => Some(last),
+                    (keyboard::KeyCode::Down, None) | (keyboard::KeyCode::PageDown, None) => {
+                        Some(0)
+                    }
+                    (keyboard::KeyCode::Home, _) => Some(0),
+                    (keyboard::KeyCode::End, _) => Some(last),
+                    _ => None,
+                };
+
+                match target {
+                    Some(target) => {
+                        self.select(&order, target, messages);
+                        event::Status::Captured
+                    }
+                    None => event::Status::Ignored,
+                }
+            }
+            _ => event::Status::Ignored,
+        };
+
+        if status == event::Status::Captured {
+            // a click on the header may have sorted the rows again
+            let order = self.display_order();
+            self.scroll_to_selected(&order, height);
+        }
+
+        status
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut TuiRenderer,
+        _defaults: &Style,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+        _viewport: &Rectangle,
+    ) -> Primitive {
+        let bounds = layout.bounds();
+        let is_focused = renderer.register_focusable(FocusKind::Table, bounds, false);
+
+        let x = bounds.x.round() as u16;
+        let y = bounds.y.round() as u16;
+        let width = bounds.width.round() as u16;
+        let height = bounds.height.round() as u16;
+        let column_bounds = self.column_bounds(width);
+
+        let mut primitives = vec![Primitive::Rectangle(
+            x,
+            y,
+            width,
+            height,
+            Cell {
+                content: None,
+                style: self.style.normal,
+            },
+        )];
+
+        if height == 0 {
+            return Primitive::Group(primitives);
+        }
+
+        // header
+        let header_style = self.style.normal.merge(self.style.header);
+        primitives.push(Primitive::Rectangle(
+            x,
+            y,
+            width,
+            1,
+            Cell {
+                content: None,
+                style: header_style,
+            },
+        ));
+
+        for (index, (column, (start, column_width))) in
+            self.columns.iter().zip(&column_bounds).enumerate()
+        {
+            let indicator = match self.state.sort {
+                Some((sorted_column, SortOrder::Ascending)) if sorted_column == index => " ▲",
+                Some((sorted_column, SortOrder::Descending)) if sorted_column == index => " ▼",
+                _ => "",
+            };
+            // the title is truncated to keep the indicator visible
+            let title_width = column_width.saturating_sub(text_width(indicator));
+            let title = format!("{}{}", truncate_text(&column.title, title_width), indicator);
+
+            primitives.extend(aligned_cells(
+                &title,
+                x + start,
+                y,
+                *column_width,
+                column.alignment,
+                header_style,
+            ));
+        }
+
+        // only the visible rows are drawn
+        let order = self.display_order();
+        let selected_style = match (is_focused, self.style.focused_selected.is_empty()) {
+            (true, false) => self.style.focused_selected,
+            _ if self.style.selected.is_empty() => Style::new().bold(),
+            _ => self.style.selected,
+        };
+
+        for (row_y, row_index) in order
+            .iter()
+            .skip(self.state.scroll)
+            .take(height as usize - 1)
+            .enumerate()
+        {
+            let row_y = y + 1 + row_y as u16;
+            let row_style = match self.state.selected == Some(*row_index) {
+                true => self.style.normal.merge(selected_style),
+                false => self.style.normal,
+            };
+
+            if row_style != self.style.normal {
+                primitives.push(Primitive::Rectangle(
+                    x,
+                    row_y,
+                    width,
+                    1,
+                    Cell {
+                        content: None,
+                        style: row_style,
+                    },
+                ));
+            }
+
+            let row = &self.rows[*row_index];
+
+            for ((column, (start, column_width)), text) in
+                self.columns.iter().zip(&column_bounds).zip(row)
+            {
+                primitives.extend(aligned_cells(
+                    text,
+                    x + start,
+                    row_y,
+                    *column_width,
+                    column.alignment,
+                    row_style,
+                ));
+            }
+        }
+
+        Primitive::Group(primitives)
+    }
+}
+
+impl<'a, Message> Table<'a, Message> {
+    /// Sorts the rows by the clicked column, reversing the order when already sorted by it.
+    fn header_clicked(&mut self, x: u16, width: u16, messages: &mut Vec<Message>) {
+        let column = self
+            .column_bounds(width)
+            .iter()
+            .position(|(start, column_width)| x >= *start && x < start + column_width);
+
+        let column = match column {
+            Some(column) if self.columns[column].sortable => column,
+            _ => return,
+        };
+
+        let order = match self.state.sort {
+            Some((sorted_column, SortOrder::Ascending)) if sorted_column == column => {
+                SortOrder::Descending
+            }
+            _ => SortOrder::Ascending,
+        };
+
+        self.state.sort = Some((column, order));
+
+        if let Some(on_sort) = &self.on_sort {
+            messages.push(on_sort(column, order));
+        }
+    }
+}
+
+impl<'a, Message> From<Table<'a, Message>> for Element<'a, Message, TuiRenderer>
+where
+    Message: 'a,
+{
+    fn from(table: Table<'a, Message>) -> Self {
+        Element::new(table)
+    }
+}
+
+/// Compares cells as numbers when they both are numbers.
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => a.cmp(b),
+    }
+}
+
+fn aligned_cells(
+    text: &str,
+    x: u16,
+    y: u16,
+    width: u16,
+    alignment: HorizontalAlignment,
+    style: Style,
+) -> Vec<Primitive> {
+    let text = truncate_text(text, width);
+    let free_width = width - text_width(&text);
+
+    let x = match alignment {
+        HorizontalAlignment::Left => x,
+        HorizontalAlignment::Center => x + free_width / 2,
+        HorizontalAlignment::Right => x + free_width,
+    };
+
+    text_cells(&text, x, y, style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColumnWidth, SortOrder, State, Table, TableColumn};
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::TuiRenderer;
+    use iced_native::{
+        clipboard, mouse, Cache, Element, Event, HorizontalAlignment, Length, Point, Size,
+        UserInterface,
+    };
+    use std::rc::Rc;
+
+    fn rows() -> Vec<Vec<String>> {
+        [("init", "1"), ("kthreadd", "2"), ("bash", "10")]
+            .iter()
+            .map(|(name, pid)| vec![name.to_string(), pid.to_string()])
+            .collect()
+    }
+
+    fn columns() -> Vec<TableColumn> {
+        vec![
+            TableColumn::new("Name"),
+            TableColumn::new("PID")
+                .width(ColumnWidth::Fixed(4))
+                .alignment(HorizontalAlignment::Right),
+        ]
+    }
+
+    #[test]
+    fn it_size_columns() {
+        let mut state = State::new();
+        let rows = rows();
+        let table: Table<'_, ()> = Table::new(
+            &mut state,
+            vec![
+                TableColumn::new("A").width(ColumnWidth::Fixed(4)),
+                TableColumn::new("B").width(ColumnWidth::Percent(50)),
+                TableColumn::new("C").width(ColumnWidth::Fill(1)),
+                TableColumn::new("D").width(ColumnWidth::Fill(2)),
+            ],
+            &rows,
+        );
+
+        assert_eq!(
+            table.column_bounds(23),
+            vec![(0, 4), (5, 10), (16, 2), (19, 4)]
+        );
+
+        // the display order is only sorted again when the sort changes
+        let order = table.display_order();
+        assert!(Rc::ptr_eq(&order, &table.display_order()));
+        table.state.sort_by(1, SortOrder::Descending);
+        assert_eq!(&*table.display_order(), &[2, 1, 0]);
+    }
+
+    #[test]
+    fn it_sort_replaced_rows_again() {
+        let mut state = State::new();
+        state.sort_by(0, SortOrder::Ascending);
+
+        let rows = rows();
+        {
+            let table: Table<'_, ()> = Table::new(&mut state, columns(), &rows);
+            assert_eq!(&*table.display_order(), &[2, 0, 1]);
+        }
+
+        // the same number of rows, with other contents
+        let rows: Vec<Vec<String>> = ["zsh", "cron", "sshd"]
+            .iter()
+            .map(|name| vec![name.to_string(), String::new()])
+            .collect();
+        let table: Table<'_, ()> = Table::new(&mut state, columns(), &rows);
+        assert_eq!(&*table.display_order(), &[1, 2, 0]);
+    }
+
+    #[test]
+    fn it_draw_and_sort_rows() {
+        let mut renderer = TuiRenderer::default();
+        let mut state = State::new();
+        let rows = rows();
+
+        let table: Element<'_, (usize, SortOrder), TuiRenderer> =
+            Table::new(&mut state, columns(), &rows)
+                .on_sort(|column, order| (column, order))
+                .width(Length::Units(12))
+                .into();
+        let mut ui =
+            UserInterface::build(table, Size::new(12., 3.), Cache::default(), &mut renderer);
+
+        let mut messages = vec![];
+        let click = [Event::Mouse(mouse::Event::ButtonPressed(
+            mouse::Button::Left,
+        ))];
+
+        for _ in 0..2 {
+            ui.update(
+                &click,
+                Point::new(10., 0.),
+                &renderer,
+                &mut clipboard::Null,
+                &mut messages,
+            );
+        }
+
+        assert_eq!(
+            messages,
+            vec![(1, SortOrder::Ascending), (1, SortOrder::Descending)]
+        );
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(12, 3);
+        vbuffer.merge_primitive(&primitive);
+
+        let lines: Vec<String> = vbuffer
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.content.unwrap_or(' ')).collect())
+            .collect();
+        assert_eq!(lines, vec!["Name    P… ▼", "bash      10", "kthrea…    2"]);
+    }
+}
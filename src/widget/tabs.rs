@@ -0,0 +1,550 @@
+//! Switch between views with a tab bar.
+use crate::renderer::primitives::{Cell, Primitive};
+use crate::renderer::utils::{text_cells, text_width, truncate_text};
+use crate::{Style, TuiRenderer};
+use iced_native::{
+    event, keyboard, layout, mouse, Clipboard, Element, Event, Hasher, Layout, Length, Point,
+    Rectangle, Size, Widget,
+};
+
+/// A tab bar above the content of the active tab.
+///
+/// The tabs are switched by clicking them or with Ctrl+PageUp and Ctrl+PageDown. When
+/// they don't fit in the bar, arrows show that tabs are hidden and the bar scrolls to
+/// keep the active tab visible.
+#[allow(clippy::type_complexity)]
+pub struct Tabs<'a, Message> {
+    titles: Vec<String>,
+    active: usize,
+    on_select: Box<dyn Fn(usize) -> Message + 'a>,
+    content: Option<Element<'a, Message, TuiRenderer>>,
+    width: Length,
+    height: Length,
+    style: TabsStyle,
+}
+
+impl<'a, Message> Tabs<'a, Message> {
+    /// Creates a tab bar with the given titles and index of the active tab, producing a
+    /// message with the index of the tab to activate.
+    pub fn new<T, F>(titles: Vec<T>, active: usize, on_select: F) -> Self
+    where
+        T: Into<String>,
+        F: 'a + Fn(usize) -> Message,
+    {
+        Self {
+            titles: titles.into_iter().map(Into::into).collect(),
+            active,
+            on_select: Box::new(on_select),
+            content: None,
+            width: Length::Fill,
+            height: Length::Shrink,
+            style: TabsStyle::default(),
+        }
+    }
+
+    /// Sets the content drawn below the tab bar, usually the view of the active tab.
+    pub fn content<E>(mut self, content: E) -> Self
+    where
+        E: Into<Element<'a, Message, TuiRenderer>>,
+    {
+        self.content = Some(content.into());
+        self
+    }
+
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn style(mut self, style: TabsStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn tab_width(&self, index: usize) -> u16 {
+        text_width(&self.titles[index]) + self.style.padding * 2
+    }
+
+    /// Returns the width of the bar needed to show all the tabs.
+    fn bar_width(&self) -> u16 {
+        let separators = self.titles.len().saturating_sub(1) as u16;
+
+        (0..self.titles.len())
+            .map(|index| self.tab_width(index))
+            .sum::<u16>()
+            + separators
+    }
+
+    /// Returns the tabs shown in a bar of the given width, as their index, start and width,
+    /// and whether tabs are hidden before and after them.
+    fn visible_tabs(&self, width: u16) -> (Vec<(usize, u16, u16)>, bool, bool) {
+        if self.titles.is_empty() {
+            return (vec![], false, false);
+        }
+
+        let active = self.active.min(self.titles.len() - 1);
+        let fits = self.bar_width() <= width;
+
+        // one cell is kept for each arrow when the tabs overflow
+        let (start, available) = match fits {
+            true => (0, width),
+            false => (1, width.saturating_sub(2)),
+        };
+
+        // otherwise show as many tabs as possible before the active one
+        let mut first = match fits {
+            true => 0,
+            false => active,
+        };
+        let mut used = self.tab_width(active);
+
+        while !fits && first > 0 && used + 1 + self.tab_width(first - 1) <= available {
+            first -= 1;
+            used += 1 + self.tab_width(first);
+        }
+
+        let mut tabs = vec![];
+        let mut x = 0;
+
+        for index in first..self.titles.len() {
+            // only the first tab is truncated when it doesn't fit
+            let tab_width = match index == first {
+                true => self.tab_width(index).min(available),
+                false => self.tab_width(index),
+            };
+
+            if index != first && x + tab_width > available {
+                break;
+            }
+
+            tabs.push((index, start + x, tab_width));
+            x += tab_width + 1;
+
+            if x >= available {
+                break;
+            }
+        }
+
+        let last = tabs.last().map(|(index, _, _)| *index).unwrap_or(0);
+
+        (tabs, first > 0, last + 1 < self.titles.len())
+    }
+
+    fn select(&self, index: usize, messages: &mut Vec<Message>) {
+        if index != self.active && index < self.titles.len() {
+            messages.push((self.on_select)(index));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TabsStyle {
+    pub(crate) normal: Style,
+    pub(crate) active: Style,
+    pub(crate) separator: char,
+    pub(crate) padding: u16,
+}
+
+impl Default for TabsStyle {
+    fn default() -> Self {
+        Self {
+            normal: Style::default(),
+            active: Style::default(),
+            separator: '│',
+            padding: 1,
+        }
+    }
+}
+
+impl TabsStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the style of the tab bar and of the inactive tabs.
+    pub fn normal(mut self, normal: Style) -> Self {
+        self.normal = normal;
+        self
+    }
+
+    /// Sets the style of the active tab.
+    ///
+    /// When not set, the active tab is drawn in bold.
+    pub fn active(mut self, active: Style) -> Self {
+        self.active = active;
+        self
+    }
+
+    /// Sets the character drawn between the tabs.
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Sets the number of spaces around the titles.
+    pub fn padding(mut self, padding: u16) -> Self {
+        self.padding = padding;
+        self
+    }
+}
+
+impl<'a, Message> Widget<Message, TuiRenderer> for Tabs<'a, Message> {
+    fn width(&self) -> Length {
+        self.width
+    }
+
+    fn height(&self) -> Length {
+        self.height
+    }
+
+    fn layout(&self, renderer: &TuiRenderer, limits: &layout::Limits) -> layout::Node {
+        let limits = limits.width(self.width).height(self.height);
+
+        let content = match &self.content {
+            Some(content) => {
+                let content_limits = limits.loose().shrink(Size::new(0.0, 1.0));
+                let mut content = content.layout(renderer, &content_limits);
+                content.move_to(Point::new(0.0, 1.0));
+                content
+            }
+            None => layout::Node::new(Size::ZERO),
+        };
+
+        let intrinsic = Size::new(
+            content.size().width.max(self.bar_width() as f32),
+            content.size().height + 1.0,
+        );
+
+        layout::Node::with_children(limits.resolve(intrinsic), vec![content])
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        use std::hash::Hash;
+
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+
+        self.width.hash(state);
+        self.height.hash(state);
+        self.titles.hash(state);
+
+        if let Some(content) = &self.content {
+            content.hash_layout(state);
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &TuiRenderer,
+        clipboard: &mut dyn Clipboard,
+        messages: &mut Vec<Message>,
+    ) -> event::Status {
+        let bounds = layout.bounds();
+
+        if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event {
+            if bounds.contains(cursor_position) && cursor_position.y < bounds.y.round() + 1.0 {
+                let width = bounds.width.round() as u16;
+                let x = (cursor_position.x - bounds.x.round()) as u16;
+                let (tabs, has_previous, has_next) = self.visible_tabs(width);
+
+                if has_previous && x == 0 {
+                    self.select(self.active.saturating_sub(1), messages);
+                } else if has_next && x + 1 == width {
+                    self.select(self.active + 1, messages);
+                } else if let Some((index, _, _)) = tabs
+                    .iter()
+                    .find(|(_, start, tab_width)| x >= *start && x < start + tab_width)
+                {
+                    self.select(*index, messages);
+                }
+
+                return event::Status::Captured;
+            }
+        }
+
+        // the content sees the keys first, so nested tabs are switched before their parents
+        let status = self.content_event(
+            event.clone(),
+            layout,
+            cursor_position,
+            renderer,
+            clipboard,
+            messages,
+        );
+
+        if status == event::Status::Captured || self.titles.is_empty() {
+            return status;
+        }
+
+        match event {
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code: key_code @ (keyboard::KeyCode::PageUp | keyboard::KeyCode::PageDown),
+                modifiers,
+            }) if modifiers.control
+                && (bounds.contains(cursor_position) || renderer.has_focus_within(bounds)) =>
+            {
+                let last = self.titles.len() - 1;
+
+                let target = match key_code {
+                    keyboard::KeyCode::PageUp => self.active.checked_sub(1).unwrap_or(last),
+                    _ if self.active >= last => 0,
+                    _ => self.active + 1,
+                };
+
+                self.select(target, messages);
+                event::Status::Captured
+            }
+            _ => status,
+        }
+    }
+    fn draw(
+        &self,
+        renderer: &mut TuiRenderer,
+        defaults: &Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) -> Primitive {
+        let bounds = layout.bounds();
+        let x = bounds.x.round() as u16;
+        let y = bounds.y.round() as u16;
+        let width = bounds.width.round() as u16;
+
+        let mut primitives = vec![Primitive::Rectangle(
+            x,
+            y,
+            width,
+            1,
+            Cell {
+                content: None,
+                style: self.style.normal,
+            },
+        )];
+
+        let active_style = match self.style.active.is_empty() {
+            true => self.style.normal.merge(Style::new().bold()),
+            false => self.style.normal.merge(self.style.active),
+        };
+        let (tabs, has_previous, has_next) = self.visible_tabs(width);
+
+        for (position, (index, start, tab_width)) in tabs.iter().enumerate() {
+            let style = match *index == self.active {
+                true => active_style,
+                false => self.style.normal,
+            };
+
+            if *index == self.active {
+                primitives.push(Primitive::Rectangle(
+                    x + start,
+                    y,
+                    *tab_width,
+                    1,
+                    Cell {
+                        content: None,
+                        style,
+                    },
+                ));
+            }
+
+            let padding = self.style.padding.min(*tab_width);
+            let title = truncate_text(&self.titles[*index], tab_width.saturating_sub(padding * 2));
+            primitives.extend(text_cells(&title, x + start + padding, y, style));
+
+            if position + 1 < tabs.len() {
+                primitives.push(Primitive::Cell(
+                    x + start + tab_width,
+                    y,
+                    Cell {
+                        content: Some(self.style.separator),
+                        style: self.style.normal,
+                    },
+                ));
+            }
+        }
+
+        if has_previous {
+            primitives.push(arrow_cell('‹', x, y, self.style.normal));
+        }
+
+        if has_next {
+            primitives.push(arrow_cell('›', x + width - 1, y, self.style.normal));
+        }
+
+        if let (Some(content), Some(content_layout)) = (&self.content, layout.children().next()) {
+            primitives.push(content.draw(
+                renderer,
+                defaults,
+                content_layout,
+                cursor_position,
+                viewport,
+            ));
+        }
+
+        Primitive::Group(primitives)
+    }
+}
+
+impl<'a, Message> Tabs<'a, Message> {
+    fn content_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &TuiRenderer,
+        clipboard: &mut dyn Clipboard,
+        messages: &mut Vec<Message>,
+    ) -> event::Status {
+        match (&mut self.content, layout.children().next()) {
+            (Some(content), Some(content_layout)) => content.on_event(
+                event,
+                content_layout,
+                cursor_position,
+                renderer,
+                clipboard,
+                messages,
+            ),
+            _ => event::Status::Ignored,
+        }
+    }
+}
+
+fn arrow_cell(arrow: char, x: u16, y: u16, style: Style) -> Primitive {
+    Primitive::Cell(
+        x,
+        y,
+        Cell {
+            content: Some(arrow),
+            style,
+        },
+    )
+}
+
+impl<'a, Message> From<Tabs<'a, Message>> for Element<'a, Message, TuiRenderer>
+where
+    Message: 'a,
+{
+    fn from(tabs: Tabs<'a, Message>) -> Self {
+        Element::new(tabs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tabs;
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::TuiRenderer;
+    use iced_native::{
+        clipboard, keyboard, mouse, Cache, Element, Event, Point, Size, Text, UserInterface,
+    };
+
+    fn titles() -> Vec<&'static str> {
+        vec!["Files", "Search", "Git", "Debug"]
+    }
+
+    fn draw(active: usize, width: u16) -> String {
+        let mut renderer = TuiRenderer::default();
+        let tabs: Element<'_, usize, TuiRenderer> = Tabs::new(titles(), active, |index| index)
+            .content(Text::new("content"))
+            .into();
+        let mut ui = UserInterface::build(
+            tabs,
+            Size::new(width as f32, 2.),
+            Cache::default(),
+            &mut renderer,
+        );
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(width, 2);
+        vbuffer.merge_primitive(&primitive);
+
+        vbuffer.rows[0]
+            .iter()
+            .map(|cell| cell.content.unwrap_or(' '))
+            .collect()
+    }
+
+    #[test]
+    fn it_draw_tab_bar() {
+        assert_eq!(draw(0, 30), " Files │ Search │ Git │ Debug ");
+        assert_eq!(draw(0, 20), "  Files │ Search   ›");
+        assert_eq!(draw(3, 20), "‹ Git │ Debug       ");
+    }
+
+    #[test]
+    fn it_switch_tabs() {
+        let mut renderer = TuiRenderer::default();
+        let tabs: Element<'_, usize, TuiRenderer> = Tabs::new(titles(), 3, |index| index).into();
+        let mut ui =
+            UserInterface::build(tabs, Size::new(30., 1.), Cache::default(), &mut renderer);
+
+        let mut messages = vec![];
+        let ctrl = keyboard::Modifiers {
+            control: true,
+            ..keyboard::Modifiers::default()
+        };
+
+        ui.update(
+            &[
+                Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::PageDown,
+                    modifiers: ctrl,
+                }),
+                Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code: keyboard::KeyCode::PageUp,
+                    modifiers: ctrl,
+                }),
+                Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+            ],
+            Point::new(10., 0.),
+            &renderer,
+            &mut clipboard::Null,
+            &mut messages,
+        );
+
+        assert_eq!(messages, vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn it_switch_only_the_innermost_tabs() {
+        let mut renderer = TuiRenderer::default();
+        let inner = Tabs::new(titles(), 0, |index| 10 + index).content(Text::new("content"));
+        let tabs: Element<'_, usize, TuiRenderer> =
+            Tabs::new(titles(), 0, |index| index).content(inner).into();
+        let mut ui =
+            UserInterface::build(tabs, Size::new(30., 3.), Cache::default(), &mut renderer);
+
+        let mut messages = vec![];
+        let page_down = Event::Keyboard(keyboard::Event::KeyPressed {
+            key_code: keyboard::KeyCode::PageDown,
+            modifiers: keyboard::Modifiers {
+                control: true,
+                ..keyboard::Modifiers::default()
+            },
+        });
+
+        ui.update(
+            std::slice::from_ref(&page_down),
+            Point::new(5., 2.),
+            &renderer,
+            &mut clipboard::Null,
+            &mut messages,
+        );
+        ui.update(
+            &[page_down],
+            Point::new(-1., -1.),
+            &renderer,
+            &mut clipboard::Null,
+            &mut messages,
+        );
+
+        assert_eq!(messages, vec![11]);
+    }
+}
@@ -0,0 +1,1220 @@
+//! Edit multi-line text.
+use crate::renderer::focus::FocusKind;
+use crate::renderer::primitives::{Cell, Primitive};
+use crate::renderer::utils::{char_width, wrap_line};
+use crate::{AnsiColor, ClickKind, CursorStyle, Style, TuiRenderer};
+use iced_native::{
+    event, keyboard, layout, mouse, Clipboard, Element, Event, Hasher, Layout, Length, Point,
+    Rectangle, Size, Widget,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The maximum number of edits that can be undone.
+const UNDO_LIMIT: usize = 100;
+
+/// A multi-line text editor, soft wrapping the lines to its width.
+///
+/// The text is kept in the [`State`] of the editor.
+pub struct TextEditor<'a, Message> {
+    state: &'a mut State,
+    on_change: Option<Box<dyn Fn(String) -> Message + 'a>>,
+    width: Length,
+    height: Length,
+    line_numbers: bool,
+    style: TextEditorStyle,
+}
+
+impl<'a, Message> TextEditor<'a, Message> {
+    pub fn new(state: &'a mut State) -> Self {
+        Self {
+            state,
+            on_change: None,
+            width: Length::Fill,
+            height: Length::Fill,
+            line_numbers: false,
+            style: TextEditorStyle::default(),
+        }
+    }
+
+    /// Sets the message produced with the new text whenever it is edited.
+    pub fn on_change<F>(mut self, on_change: F) -> Self
+    where
+        F: 'a + Fn(String) -> Message,
+    {
+        self.on_change = Some(Box::new(on_change));
+        self
+    }
+
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+
+    /// Sets whether the line numbers are drawn in a gutter on the left.
+    pub fn line_numbers(mut self, line_numbers: bool) -> Self {
+        self.line_numbers = line_numbers;
+        self
+    }
+
+    pub fn style(mut self, style: TextEditorStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn gutter_width(&self) -> u16 {
+        if self.line_numbers {
+            self.state.lines.len().to_string().len() as u16 + 1
+        } else {
+            0
+        }
+    }
+
+    /// Returns the bounds of the text, next to the gutter.
+    fn text_bounds(&self, layout: Layout<'_>) -> Rectangle {
+        let bounds = layout.bounds();
+        let gutter_width = self.gutter_width() as f32;
+
+        Rectangle {
+            x: bounds.x.round() + gutter_width,
+            y: bounds.y.round(),
+            width: (bounds.width.round() - gutter_width).max(0.0),
+            height: bounds.height.round(),
+        }
+    }
+
+    fn notify_change(&self, messages: &mut Vec<Message>) {
+        if let Some(on_change) = &self.on_change {
+            messages.push(on_change(self.state.text()));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextEditorStyle {
+    pub(crate) normal: Style,
+    pub(crate) focused: Style,
+    pub(crate) selection: Style,
+    pub(crate) line_number: Style,
+    pub(crate) cursor: CursorStyle,
+}
+
+impl TextEditorStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn normal(mut self, normal: Style) -> Self {
+        self.normal = normal;
+        self
+    }
+
+    pub fn focused(mut self, focused: Style) -> Self {
+        self.focused = focused;
+        self
+    }
+
+    /// Sets the style of the selected text.
+    ///
+    /// When not set, the selected text is drawn in white on blue.
+    pub fn selection(mut self, selection: Style) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    pub fn line_number(mut self, line_number: Style) -> Self {
+        self.line_number = line_number;
+        self
+    }
+
+    pub fn cursor(mut self, cursor: CursorStyle) -> Self {
+        self.cursor = cursor;
+        self
+    }
+}
+
+/// A position in the text, as a line and a character index in the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditKind {
+    Insert,
+    Delete,
+    Other,
+}
+
+/// An edit of the text, undone by replacing the inserted text with the removed one.
+#[derive(Debug, Clone, PartialEq)]
+struct Edit {
+    start: Position,
+    removed: String,
+    inserted: String,
+    cursor_before: Position,
+    anchor_before: Option<Position>,
+    cursor_after: Position,
+}
+
+impl Edit {
+    /// Extends the edit with the next one when it continues it (typing, or deleting
+    /// backward or forward), returning whether it was merged.
+    fn merge(&mut self, next: &Edit) -> bool {
+        let is_typing = self.removed.is_empty() && next.removed.is_empty();
+        let is_deleting = self.inserted.is_empty() && next.inserted.is_empty();
+
+        if is_typing && next.start == end_of(self.start, &self.inserted) {
+            self.inserted.push_str(&next.inserted);
+        } else if is_deleting && end_of(next.start, &next.removed) == self.start {
+            self.start = next.start;
+            self.removed.insert_str(0, &next.removed);
+        } else if is_deleting && next.start == self.start {
+            self.removed.push_str(&next.removed);
+        } else {
+            return false;
+        }
+
+        self.cursor_after = next.cursor_after;
+        true
+    }
+}
+
+/// A row of the wrapped text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct VisualRow {
+    line: usize,
+    start: usize,
+    end: usize,
+}
+
+/// The rows of the text wrapped last, with the width and the revision they are for.
+#[derive(Debug, Clone)]
+struct WrappedRows {
+    width: u16,
+    revision: u64,
+    rows: Rc<[VisualRow]>,
+}
+
+/// The state of a [`TextEditor`], holding its text.
+#[derive(Debug, Clone)]
+pub struct State {
+    lines: Vec<String>,
+    cursor: Position,
+    anchor: Option<Position>,
+    /// The column of the cursor (in cells) kept when moving up and down.
+    preferred_x: Option<u16>,
+    scroll: usize,
+    is_focused: bool,
+    is_dragging: bool,
+    undo_stack: Vec<Edit>,
+    redo_stack: Vec<Edit>,
+    last_edit: Option<EditKind>,
+    /// Incremented whenever the text changes.
+    revision: u64,
+    rows: RefCell<Option<WrappedRows>>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            lines: vec![String::new()],
+            cursor: Position::default(),
+            anchor: None,
+            preferred_x: None,
+            scroll: 0,
+            is_focused: false,
+            is_dragging: false,
+            undo_stack: vec![],
+            redo_stack: vec![],
+            last_edit: None,
+            revision: 0,
+            rows: RefCell::new(None),
+        }
+    }
+}
+
+impl State {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_text(text: &str) -> Self {
+        let mut state = Self::default();
+        state.set_text(text);
+        state
+    }
+
+    /// Returns the text of the editor, with its lines joined by `\n`.
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Replaces the text of the editor, moving the cursor to the start and forgetting
+    /// the edit history.
+    pub fn set_text(&mut self, text: &str) {
+        self.lines = text
+            .split('\n')
+            .map(|line| line.replace('\r', ""))
+            .collect();
+        self.cursor = Position::default();
+        self.anchor = None;
+        self.preferred_x = None;
+        self.scroll = 0;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.last_edit = None;
+        self.revision += 1;
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.is_focused
+    }
+
+    pub fn focus(&mut self) {
+        self.is_focused = true;
+    }
+
+    pub fn unfocus(&mut self) {
+        self.is_focused = false;
+        self.is_dragging = false;
+    }
+
+    pub fn cursor(&self) -> Position {
+        self.cursor
+    }
+
+    pub fn move_cursor_to(&mut self, position: Position) {
+        self.cursor = self.clamp(position);
+        self.anchor = None;
+        self.preferred_x = None;
+        self.last_edit = None;
+    }
+
+    /// Returns the ordered bounds of the selection, if any.
+    pub fn selection(&self) -> Option<(Position, Position)> {
+        match self.anchor {
+            Some(anchor) if anchor != self.cursor => {
+                Some((anchor.min(self.cursor), anchor.max(self.cursor)))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn selected_text(&self) -> Option<String> {
+        let (start, end) = self.selection()?;
+        Some(self.text_between(start, end))
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = Some(Position::default());
+        self.cursor = self.end();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn undo(&mut self) {
+        if let Some(edit) = self.undo_stack.pop() {
+            self.replace(
+                edit.start,
+                end_of(edit.start, &edit.inserted),
+                &edit.removed,
+            );
+            self.cursor = edit.cursor_before;
+            self.anchor = edit.anchor_before;
+            self.preferred_x = None;
+            self.last_edit = None;
+            self.redo_stack.push(edit);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(edit) = self.redo_stack.pop() {
+            self.replace(
+                edit.start,
+                end_of(edit.start, &edit.removed),
+                &edit.inserted,
+            );
+            self.cursor = edit.cursor_after;
+            self.anchor = None;
+            self.preferred_x = None;
+            self.last_edit = None;
+            self.undo_stack.push(edit);
+        }
+    }
+
+    /// Inserts text at the cursor, replacing the selection.
+    pub fn insert(&mut self, text: &str) {
+        // typed characters are undone at once, unlike new lines and pastes
+        let kind = if text.chars().count() == 1 && text != "\n" {
+            EditKind::Insert
+        } else {
+            EditKind::Other
+        };
+        let (start, end) = self.selection().unwrap_or((self.cursor, self.cursor));
+
+        self.edit(kind, start, end, &text.replace('\r', ""));
+    }
+
+    /// Deletes the selection, or the character before the cursor.
+    pub fn backspace(&mut self) {
+        let Position { line, column } = self.cursor;
+
+        let start = match self.selection() {
+            Some((start, end)) => return self.edit(EditKind::Delete, start, end, ""),
+            None if column > 0 => Position::new(line, column - 1),
+            None if line > 0 => Position::new(line - 1, self.line_len(line - 1)),
+            // nothing to delete at the start of the text
+            None => return,
+        };
+
+        self.edit(EditKind::Delete, start, self.cursor, "");
+    }
+
+    /// Deletes the selection, or the character after the cursor.
+    pub fn delete(&mut self) {
+        let Position { line, column } = self.cursor;
+
+        let end = match self.selection() {
+            Some((start, end)) => return self.edit(EditKind::Delete, start, end, ""),
+            None if column < self.line_len(line) => Position::new(line, column + 1),
+            None if line + 1 < self.lines.len() => Position::new(line + 1, 0),
+            // nothing to delete at the end of the text
+            None => return,
+        };
+
+        self.edit(EditKind::Delete, self.cursor, end, "");
+    }
+
+    /// Replaces the text between two positions, moving the cursor after it and saving the
+    /// edit to undo it. Consecutive insertions or deletions are undone at once.
+    fn edit(&mut self, kind: EditKind, start: Position, end: Position, text: &str) {
+        let is_continued =
+            kind != EditKind::Other && self.last_edit == Some(kind) && self.selection().is_none();
+        let cursor_before = self.cursor;
+        let anchor_before = self.anchor;
+
+        let removed = self.replace(start, end, text);
+        self.cursor = end_of(start, text);
+        self.anchor = None;
+        self.preferred_x = None;
+        self.last_edit = Some(kind);
+        self.redo_stack.clear();
+
+        let edit = Edit {
+            start,
+            removed,
+            inserted: text.to_string(),
+            cursor_before,
+            anchor_before,
+            cursor_after: self.cursor,
+        };
+
+        if is_continued
+            && self
+                .undo_stack
+                .last_mut()
+                .is_some_and(|last| last.merge(&edit))
+        {
+            return;
+        }
+
+        self.undo_stack.push(edit);
+
+        if self.undo_stack.len() > UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    /// Replaces the text between two positions, returning the removed text.
+    fn replace(&mut self, start: Position, end: Position, text: &str) -> String {
+        let removed = self.text_between(start, end);
+
+        let tail = slice(&self.lines[end.line], end.column, usize::MAX).to_string();
+        let line = &mut self.lines[start.line];
+        line.truncate(byte_index(line, start.column));
+        self.lines.drain(start.line + 1..=end.line);
+
+        let mut inserted_lines = text.split('\n');
+        let mut line_index = start.line;
+        self.lines[line_index].push_str(inserted_lines.next().unwrap_or_default());
+
+        for inserted_line in inserted_lines {
+            line_index += 1;
+            self.lines.insert(line_index, inserted_line.to_string());
+        }
+
+        self.lines[line_index].push_str(&tail);
+        self.revision += 1;
+        removed
+    }
+
+    fn text_between(&self, start: Position, end: Position) -> String {
+        if start.line == end.line {
+            return slice(&self.lines[start.line], start.column, end.column).to_string();
+        }
+
+        let mut text = slice(&self.lines[start.line], start.column, usize::MAX).to_string();
+
+        for line in &self.lines[start.line + 1..end.line] {
+            text.push('\n');
+            text.push_str(line);
+        }
+
+        text.push('\n');
+        text.push_str(slice(&self.lines[end.line], 0, end.column));
+        text
+    }
+
+    fn line_len(&self, line: usize) -> usize {
+        self.lines[line].chars().count()
+    }
+
+    fn end(&self) -> Position {
+        let line = self.lines.len() - 1;
+        Position::new(line, self.line_len(line))
+    }
+
+    fn clamp(&self, position: Position) -> Position {
+        let line = position.line.min(self.lines.len() - 1);
+        Position::new(line, position.column.min(self.line_len(line)))
+    }
+
+    /// Moves the cursor, extending the selection when `select` is set.
+    fn move_to(&mut self, position: Position, select: bool) {
+        if select {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+
+        self.cursor = self.clamp(position);
+        self.last_edit = None;
+    }
+
+    fn left(&self, word: bool) -> Position {
+        let Position { line, column } = self.cursor;
+
+        if column == 0 {
+            return match line {
+                0 => self.cursor,
+                _ => Position::new(line - 1, self.line_len(line - 1)),
+            };
+        }
+
+        if !word {
+            return Position::new(line, column - 1);
+        }
+
+        let chars: Vec<char> = self.lines[line].chars().collect();
+        let mut column = column;
+
+        while column > 0 && !is_word_char(chars[column - 1]) {
+            column -= 1;
+        }
+
+        while column > 0 && is_word_char(chars[column - 1]) {
+            column -= 1;
+        }
+
+        Position::new(line, column)
+    }
+
+    fn right(&self, word: bool) -> Position {
+        let Position { line, column } = self.cursor;
+        let len = self.line_len(line);
+
+        if column >= len {
+            return match line + 1 < self.lines.len() {
+                true => Position::new(line + 1, 0),
+                false => self.cursor,
+            };
+        }
+
+        if !word {
+            return Position::new(line, column + 1);
+        }
+
+        let chars: Vec<char> = self.lines[line].chars().collect();
+        let mut column = column;
+
+        while column < len && !is_word_char(chars[column]) {
+            column += 1;
+        }
+
+        while column < len && is_word_char(chars[column]) {
+            column += 1;
+        }
+
+        Position::new(line, column)
+    }
+
+    fn select_word(&mut self) {
+        let chars: Vec<char> = self.lines[self.cursor.line].chars().collect();
+        let mut start = self.cursor.column;
+        let mut end = self.cursor.column;
+
+        while start > 0 && is_word_char(chars[start - 1]) {
+            start -= 1;
+        }
+
+        while end < chars.len() && is_word_char(chars[end]) {
+            end += 1;
+        }
+
+        self.anchor = Some(Position::new(self.cursor.line, start));
+        self.cursor.column = end;
+    }
+
+    fn select_line(&mut self) {
+        let line = self.cursor.line;
+        self.anchor = Some(Position::new(line, 0));
+        self.cursor = match line + 1 < self.lines.len() {
+            true => Position::new(line + 1, 0),
+            false => Position::new(line, self.line_len(line)),
+        };
+    }
+
+    /// Returns the rows of the lines wrapped to the width, only wrapping them again once
+    /// the text or the width changed.
+    fn visual_rows(&self, width: u16) -> Rc<[VisualRow]> {
+        let mut cache = self.rows.borrow_mut();
+
+        if let Some(wrapped) = &*cache {
+            if wrapped.width == width && wrapped.revision == self.revision {
+                return wrapped.rows.clone();
+            }
+        }
+
+        let rows: Rc<[VisualRow]> = self.wrap_lines(width).into();
+        *cache = Some(WrappedRows {
+            width,
+            revision: self.revision,
+            rows: rows.clone(),
+        });
+        rows
+    }
+
+    fn wrap_lines(&self, width: u16) -> Vec<VisualRow> {
+        let mut rows = vec![];
+
+        for (line_index, line) in self.lines.iter().enumerate() {
+            let starts = wrap_line(line, width);
+            let len = line.chars().count();
+
+            for (row_index, start) in starts.iter().enumerate() {
+                rows.push(VisualRow {
+                    line: line_index,
+                    start: *start,
+                    end: starts.get(row_index + 1).copied().unwrap_or(len),
+                });
+            }
+        }
+
+        rows
+    }
+
+    /// Returns the row and the column (in cells) of a position in the wrapped text.
+    fn visual_position(&self, rows: &[VisualRow], position: Position) -> (usize, u16) {
+        let row_index = rows
+            .iter()
+            .rposition(|row| row.line == position.line && row.start <= position.column)
+            .unwrap_or(0);
+        let row = rows[row_index];
+
+        let x = self.lines[row.line]
+            .chars()
+            .skip(row.start)
+            .take(position.column - row.start)
+            .map(char_width)
+            .sum();
+
+        (row_index, x)
+    }
+
+    /// Returns the position at a column (in cells) of a row of the wrapped text.
+    fn position_at(&self, rows: &[VisualRow], row_index: usize, x: u16) -> Position {
+        let row = rows[row_index.min(rows.len() - 1)];
+        let is_last_row = rows
+            .get(row_index + 1)
+            .is_none_or(|next_row| next_row.line != row.line);
+        let mut column = row.start;
+        let mut column_x = 0;
+
+        for c in self.lines[row.line]
+            .chars()
+            .skip(row.start)
+            .take(row.end - row.start)
+        {
+            let width = char_width(c);
+
+            if column_x + width > x {
+                break;
+            }
+
+            column += 1;
+            column_x += width;
+        }
+
+        // the end of a wrapped row is the start of the next one
+        if !is_last_row && column == row.end && column > row.start {
+            column -= 1;
+        }
+
+        Position::new(row.line, column)
+    }
+
+    fn vertical_move(&mut self, rows: &[VisualRow], delta: isize, select: bool) {
+        let (row_index, x) = self.visual_position(rows, self.cursor);
+        let x = *self.preferred_x.get_or_insert(x);
+        let target_row = (row_index as isize + delta).clamp(0, rows.len() as isize - 1) as usize;
+
+        let position = if delta < 0 && row_index == 0 {
+            Position::new(0, 0)
+        } else if delta > 0 && row_index + 1 == rows.len() {
+            self.end()
+        } else {
+            self.position_at(rows, target_row, x)
+        };
+
+        let preferred_x = self.preferred_x;
+        self.move_to(position, select);
+        self.preferred_x = preferred_x;
+    }
+
+    /// Scrolls just enough to show the cursor.
+    fn scroll_to_cursor(&mut self, rows: &[VisualRow], height: usize) {
+        let (row_index, _) = self.visual_position(rows, self.cursor);
+
+        if row_index < self.scroll {
+            self.scroll = row_index;
+        } else if height > 0 && row_index >= self.scroll + height {
+            self.scroll = row_index + 1 - height;
+        }
+
+        self.scroll = self.scroll.min(rows.len().saturating_sub(height.max(1)));
+    }
+}
+
+impl<'a, Message> Widget<Message, TuiRenderer> for TextEditor<'a, Message> {
+    fn width(&self) -> Length {
+        self.width
+    }
+
+    fn height(&self) -> Length {
+        self.height
+    }
+
+    fn layout(&self, _renderer: &TuiRenderer, limits: &layout::Limits) -> layout::Node {
+        let limits = limits.width(self.width).height(self.height);
+
+        layout::Node::new(limits.resolve(Size::ZERO))
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        use std::hash::Hash;
+
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+
+        self.width.hash(state);
+        self.height.hash(state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &TuiRenderer,
+        clipboard: &mut dyn Clipboard,
+        messages: &mut Vec<Message>,
+    ) -> event::Status {
+        let bounds = layout.bounds();
+        let text_bounds = self.text_bounds(layout);
+        let height = text_bounds.height as usize;
+        let rows = self.state.visual_rows(text_bounds.width as u16);
+        let revision_before = self.state.revision;
+
+        let status = match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                if !bounds.contains(cursor_position) {
+                    self.state.unfocus();
+                    return event::Status::Ignored;
+                }
+
+                let row_index = self.state.scroll + (cursor_position.y - text_bounds.y) as usize;
+                let x = (cursor_position.x - text_bounds.x).max(0.0) as u16;
+                let position = self.state.position_at(&rows, row_index, x);
+
+                self.state.is_focused = true;
+                self.state.is_dragging = true;
+                self.state.preferred_x = None;
+
+                match renderer.last_click().map(|click| click.kind()) {
+                    Some(ClickKind::Double) => {
+                        self.state.move_to(position, false);
+                        self.state.select_word();
+                    }
+                    Some(ClickKind::Triple) => {
+                        self.state.move_to(position, false);
+                        self.state.select_line();
+                    }
+                    _ => self.state.move_to(position, false),
+                }
+
+                event::Status::Captured
+            }
+            Event::Mouse(mouse::Event::CursorMoved { .. }) if self.state.is_dragging => {
+                let y = (cursor_position.y - text_bounds.y).max(0.0) as usize;
+                let x = (cursor_position.x - text_bounds.x).max(0.0) as u16;
+                let position = self.state.position_at(&rows, self.state.scroll + y, x);
+
+                self.state.preferred_x = None;
+                self.state.move_to(position, true);
+
+                event::Status::Captured
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+                if self.state.is_dragging =>
+            {
+                self.state.is_dragging = false;
+                event::Status::Captured
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta })
+                if bounds.contains(cursor_position) =>
+            {
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y,
+                };
+                let max_scroll = rows.len().saturating_sub(height.max(1));
+                let scroll = self.state.scroll as isize - lines.round() as isize;
+                self.state.scroll = scroll.clamp(0, max_scroll as isize) as usize;
+
+                // scrolling doesn't move the cursor
+                return event::Status::Captured;
+            }
+            // pasted line breaks are received as characters
+            Event::Keyboard(keyboard::Event::CharacterReceived(c))
+                if self.state.is_focused && (!c.is_control() || c == '\n') =>
+            {
+                self.state.insert(&c.to_string());
+                event::Status::Captured
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code,
+                modifiers,
+            }) if self.state.is_focused => {
+                self.key_pressed(key_code, modifiers, &rows, height, clipboard)
+            }
+            Event::Keyboard(keyboard::Event::KeyReleased { .. }) if self.state.is_focused => {
+                event::Status::Captured
+            }
+            _ => event::Status::Ignored,
+        };
+
+        if status == event::Status::Captured {
+            let rows = self
+                .state
+                .visual_rows(self.text_bounds(layout).width as u16);
+            self.state.scroll_to_cursor(&rows, height);
+
+            if self.state.revision != revision_before {
+                self.notify_change(messages);
+            }
+        }
+
+        status
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut TuiRenderer,
+        _defaults: &Style,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+        _viewport: &Rectangle,
+    ) -> Primitive {
+        let bounds = layout.bounds();
+        let text_bounds = self.text_bounds(layout);
+        let is_focused = self.state.is_focused;
+        renderer.register_focusable(FocusKind::TextEditor, bounds, is_focused);
+
+        let style = match is_focused {
+            true => self.style.normal.merge(self.style.focused),
+            false => self.style.normal,
+        };
+        let selection_style = if self.style.selection.is_empty() {
+            Style::new().fg(AnsiColor::White).bg(AnsiColor::Blue)
+        } else {
+            self.style.selection
+        };
+
+        let x = bounds.x.round() as u16;
+        let text_x = text_bounds.x as u16;
+        let y = text_bounds.y as u16;
+        let height = text_bounds.height as usize;
+        let gutter_width = self.gutter_width();
+
+        let mut primitives = vec![Primitive::Rectangle(
+            x,
+            y,
+            bounds.width.round() as u16,
+            bounds.height.round() as u16,
+            Cell {
+                content: None,
+                style,
+            },
+        )];
+
+        let rows = self.state.visual_rows(text_bounds.width as u16);
+        let selection = self.state.selection();
+
+        for (row_y, row) in rows.iter().skip(self.state.scroll).take(height).enumerate() {
+            let row_y = y + row_y as u16;
+
+            if gutter_width > 0 && row.start == 0 {
+                let number = format!("{:>1$}", row.line + 1, gutter_width as usize - 1);
+                let number_style = style.merge(self.style.line_number);
+
+                for (index, c) in number.chars().enumerate() {
+                    primitives.push(Primitive::Cell(
+                        x + index as u16,
+                        row_y,
+                        Cell::from_char(c).style(number_style),
+                    ));
+                }
+            }
+
+            let mut column_x = 0;
+
+            for (index, c) in self.state.lines[row.line]
+                .chars()
+                .enumerate()
+                .skip(row.start)
+                .take(row.end - row.start)
+            {
+                let position = Position::new(row.line, index);
+                let is_selected =
+                    selection.is_some_and(|(start, end)| start <= position && position < end);
+                let cell_style = match is_selected {
+                    true => style.merge(selection_style),
+                    false => style,
+                };
+
+                primitives.push(Primitive::Cell(
+                    text_x + column_x,
+                    row_y,
+                    Cell::from_char(c).style(cell_style),
+                ));
+
+                // the second cell of wide characters is left empty
+                if char_width(c) == 2 {
+                    primitives.push(Primitive::Cell(
+                        text_x + column_x + 1,
+                        row_y,
+                        Cell {
+                            content: None,
+                            style: cell_style,
+                        },
+                    ));
+                }
+
+                column_x += char_width(c);
+            }
+        }
+
+        if is_focused {
+            let (row_index, cursor_x) = self.state.visual_position(&rows, self.state.cursor);
+
+            if row_index >= self.state.scroll && row_index < self.state.scroll + height {
+                primitives.push(Primitive::CursorPosition(
+                    text_x + cursor_x,
+                    y + (row_index - self.state.scroll) as u16,
+                    self.style.cursor,
+                ));
+            }
+        }
+
+        Primitive::Group(primitives)
+    }
+}
+
+impl<'a, Message> TextEditor<'a, Message> {
+    fn key_pressed(
+        &mut self,
+        key_code: keyboard::KeyCode,
+        modifiers: keyboard::Modifiers,
+        rows: &[VisualRow],
+        height: usize,
+        clipboard: &mut dyn Clipboard,
+    ) -> event::Status {
+        let state = &mut *self.state;
+        let select = modifiers.shift;
+        let command = modifiers.is_command_pressed();
+
+        match key_code {
+            keyboard::KeyCode::Enter => state.insert("\n"),
+            keyboard::KeyCode::Backspace => state.backspace(),
+            keyboard::KeyCode::Delete => state.delete(),
+            keyboard::KeyCode::Left => match state.selection() {
+                Some((start, _)) if !select && !command => state.move_to(start, false),
+                _ => state.move_to(state.left(command), select),
+            },
+            keyboard::KeyCode::Right => match state.selection() {
+                Some((_, end)) if !select && !command => state.move_to(end, false),
+                _ => state.move_to(state.right(command), select),
+            },
+            keyboard::KeyCode::Up => state.vertical_move(rows, -1, select),
+            keyboard::KeyCode::Down => state.vertical_move(rows, 1, select),
+            keyboard::KeyCode::PageUp => {
+                state.vertical_move(rows, -(height.max(1) as isize), select)
+            }
+            keyboard::KeyCode::PageDown => {
+                state.vertical_move(rows, height.max(1) as isize, select)
+            }
+            keyboard::KeyCode::Home if command => state.move_to(Position::default(), select),
+            keyboard::KeyCode::End if command => state.move_to(state.end(), select),
+            keyboard::KeyCode::Home => state.move_to(Position::new(state.cursor.line, 0), select),
+            keyboard::KeyCode::End => {
+                let line = state.cursor.line;
+                state.move_to(Position::new(line, state.line_len(line)), select)
+            }
+            keyboard::KeyCode::A if command => state.select_all(),
+            keyboard::KeyCode::C if command => {
+                if let Some(text) = state.selected_text() {
+                    clipboard.write(text);
+                }
+            }
+            keyboard::KeyCode::X if command => {
+                if let Some(text) = state.selected_text() {
+                    clipboard.write(text);
+                    state.delete();
+                }
+            }
+            keyboard::KeyCode::V if command => {
+                if let Some(text) = clipboard.read() {
+                    state.insert(&text);
+                }
+            }
+            keyboard::KeyCode::Z if command && modifiers.shift => state.redo(),
+            keyboard::KeyCode::Z if command => state.undo(),
+            keyboard::KeyCode::Y if command => state.redo(),
+            keyboard::KeyCode::Escape => state.unfocus(),
+            // leave the focus traversal keys to the runtime
+            keyboard::KeyCode::Tab => return event::Status::Ignored,
+            _ => {}
+        }
+
+        event::Status::Captured
+    }
+}
+
+impl<'a, Message> From<TextEditor<'a, Message>> for Element<'a, Message, TuiRenderer>
+where
+    Message: 'a,
+{
+    fn from(text_editor: TextEditor<'a, Message>) -> Self {
+        Element::new(text_editor)
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Returns the byte index of a character index in a line.
+fn byte_index(line: &str, column: usize) -> usize {
+    line.char_indices()
+        .nth(column)
+        .map(|(index, _)| index)
+        .unwrap_or(line.len())
+}
+
+/// Returns the position at the end of a text inserted at `start`.
+fn end_of(start: Position, text: &str) -> Position {
+    match text.rsplit_once('\n') {
+        Some((head, last_line)) => Position::new(
+            start.line + head.matches('\n').count() + 1,
+            last_line.chars().count(),
+        ),
+        None => Position::new(start.line, start.column + text.chars().count()),
+    }
+}
+
+fn slice(line: &str, start: usize, end: usize) -> &str {
+    &line[byte_index(line, start)..byte_index(line, end)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Position, State, TextEditor};
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::TuiRenderer;
+    use iced_native::{
+        clipboard, keyboard, Cache, Element, Event, Length, Point, Size, UserInterface,
+    };
+
+    #[test]
+    fn it_edit_lines() {
+        let mut state = State::with_text("hello\nworld");
+        state.move_cursor_to(Position::new(0, 5));
+
+        state.insert("!");
+        state.insert("\n");
+        state.insert("new");
+        assert_eq!(state.text(), "hello!\nnew\nworld");
+
+        state.move_cursor_to(Position::new(2, 0));
+        state.backspace();
+        assert_eq!(state.text(), "hello!\nnewworld");
+        assert_eq!(state.cursor(), Position::new(1, 3));
+
+        state.anchor = Some(Position::new(0, 2));
+        assert_eq!(state.selected_text(), Some("llo!\nnew".to_string()));
+        state.delete();
+        assert_eq!(state.text(), "heworld");
+    }
+
+    #[test]
+    fn it_undo_and_redo() {
+        let mut state = State::new();
+
+        for c in "abc".chars() {
+            state.insert(&c.to_string());
+        }
+        state.insert("\n");
+        state.insert("d");
+        assert_eq!(state.text(), "abc\nd");
+
+        state.undo();
+        assert_eq!(state.text(), "abc\n");
+        state.undo();
+        assert_eq!(state.text(), "abc");
+        // consecutive characters are undone at once
+        state.undo();
+        assert_eq!(state.text(), "");
+        assert!(!state.can_undo());
+
+        state.redo();
+        state.redo();
+        assert_eq!(state.text(), "abc\n");
+
+        state.insert("e");
+        assert!(!state.can_redo());
+
+        // deleting nothing isn't an edit
+        let mut state = State::with_text("ab\ncd");
+        state.backspace();
+        state.move_cursor_to(Position::new(1, 2));
+        state.delete();
+        assert!(!state.can_undo());
+
+        // consecutive deletions are undone at once, restoring the cursor
+        state.backspace();
+        state.backspace();
+        state.backspace();
+        assert_eq!(state.text(), "ab");
+        state.undo();
+        assert_eq!(state.text(), "ab\ncd");
+        assert_eq!(state.cursor(), Position::new(1, 2));
+        assert!(!state.can_undo());
+
+        state.redo();
+        assert_eq!(state.text(), "ab");
+        assert_eq!(state.cursor(), Position::new(0, 2));
+    }
+
+    #[test]
+    fn it_move_through_wrapped_rows() {
+        let mut state = State::with_text("abcdefgh\nij");
+        let rows = state.visual_rows(5);
+        assert_eq!(rows.len(), 3);
+
+        // the lines are only wrapped again once edited or resized
+        assert!(std::rc::Rc::ptr_eq(&rows, &state.visual_rows(5)));
+        assert!(!std::rc::Rc::ptr_eq(&rows, &state.visual_rows(6)));
+
+        state.move_cursor_to(Position::new(0, 2));
+        state.vertical_move(&rows, 1, false);
+        assert_eq!(state.cursor(), Position::new(0, 7));
+        state.vertical_move(&rows, 1, true);
+        assert_eq!(state.cursor(), Position::new(1, 2));
+        assert_eq!(state.selected_text(), Some("h\nij".to_string()));
+    }
+
+    #[test]
+    fn it_draw_wrapped_text_with_line_numbers() {
+        let mut renderer = TuiRenderer::default();
+        let mut state = State::with_text("abcdefgh\nij");
+        state.focus();
+
+        let mut ui = UserInterface::build(
+            Element::<'_, String, TuiRenderer>::from(
+                TextEditor::new(&mut state)
+                    .on_change(|text| text)
+                    .width(Length::Units(7))
+                    .height(Length::Units(3))
+                    .line_numbers(true),
+            ),
+            Size::new(7., 3.),
+            Cache::default(),
+            &mut renderer,
+        );
+
+        let mut messages = vec![];
+        ui.update(
+            &[Event::Keyboard(keyboard::Event::CharacterReceived('x'))],
+            Point::new(-1., -1.),
+            &renderer,
+            &mut clipboard::Null,
+            &mut messages,
+        );
+        assert_eq!(messages, vec!["xabcdefgh\nij".to_string()]);
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(7, 3);
+        vbuffer.merge_primitive(&primitive);
+
+        let rows: Vec<String> = vbuffer
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.content.unwrap_or(' ')).collect())
+            .collect();
+        assert_eq!(rows, vec!["1 xabcd", "  efgh ", "2 ij   "]);
+        assert_eq!(
+            vbuffer.cursor_position.map(|(x, y, _)| (x, y)),
+            Some((3, 0))
+        );
+
+        // the line breaks of a paste are received as characters
+        let mut messages = vec![];
+        ui.update(
+            &[Event::Keyboard(keyboard::Event::CharacterReceived('\n'))],
+            Point::new(-1., -1.),
+            &renderer,
+            &mut clipboard::Null,
+            &mut messages,
+        );
+        assert_eq!(messages, vec!["x\nabcdefgh\nij".to_string()]);
+    }
+}
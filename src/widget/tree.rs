@@ -0,0 +1,752 @@
+//! Display a hierarchy of expandable nodes.
+use crate::renderer::focus::FocusKind;
+use crate::renderer::primitives::{Cell, Primitive};
+use crate::renderer::utils::{text_cells, text_width, truncate_text};
+use crate::{Style, TuiRenderer};
+use iced_native::{
+    event, keyboard, layout, mouse, Clipboard, Element, Event, Hasher, Layout, Length, Point,
+    Rectangle, Size, Widget,
+};
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// A tree of text nodes drawn with guide lines, with expandable and selectable nodes.
+///
+/// The children of a node can be loaded lazily: mark the node as [expandable] and load
+/// its children when receiving the message set by [`Tree::on_expand`].
+///
+/// [expandable]: TreeNode::expandable
+#[allow(clippy::type_complexity)]
+pub struct Tree<'a, Message, Id> {
+    state: &'a mut State<Id>,
+    nodes: &'a [TreeNode<Id>],
+    on_select: Option<Box<dyn Fn(Id) -> Message + 'a>>,
+    on_expand: Option<Box<dyn Fn(Id) -> Message + 'a>>,
+    width: Length,
+    height: Length,
+    style: TreeStyle,
+}
+
+impl<'a, Message, Id> Tree<'a, Message, Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    pub fn new(state: &'a mut State<Id>, nodes: &'a [TreeNode<Id>]) -> Self {
+        Self {
+            state,
+            nodes,
+            on_select: None,
+            on_expand: None,
+            width: Length::Fill,
+            height: Length::Shrink,
+            style: TreeStyle::default(),
+        }
+    }
+
+    /// Sets the message produced with the id of the selected node.
+    pub fn on_select<F>(mut self, on_select: F) -> Self
+    where
+        F: 'a + Fn(Id) -> Message,
+    {
+        self.on_select = Some(Box::new(on_select));
+        self
+    }
+
+    /// Sets the message produced with the id of an expanded node, to load its children.
+    pub fn on_expand<F>(mut self, on_expand: F) -> Self
+    where
+        F: 'a + Fn(Id) -> Message,
+    {
+        self.on_expand = Some(Box::new(on_expand));
+        self
+    }
+
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn style(mut self, style: TreeStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Returns the nodes shown by the tree, the children of the expanded nodes included.
+    fn visible_nodes(&self) -> Vec<VisibleNode<'a, Id>> {
+        let mut visible_nodes = vec![];
+        self.push_visible_nodes(self.nodes, 0, "", None, &mut visible_nodes);
+        visible_nodes
+    }
+
+    fn push_visible_nodes(
+        &self,
+        nodes: &'a [TreeNode<Id>],
+        depth: u16,
+        guides: &str,
+        parent: Option<usize>,
+        visible_nodes: &mut Vec<VisibleNode<'a, Id>>,
+    ) {
+        for (index, node) in nodes.iter().enumerate() {
+            let is_last = index + 1 == nodes.len();
+            let is_expanded = self.state.is_expanded(&node.id);
+
+            // the roots have no guides, the children are connected below the marker of
+            // their parent
+            let (connector, child_guides) = match (depth, is_last) {
+                (0, _) => ("", String::new()),
+                (_, false) => ("├─", format!("{}│ ", guides)),
+                (_, true) => ("└─", format!("{}  ", guides)),
+            };
+            let marker = match (node.has_children(), is_expanded, depth) {
+                (true, true, _) => '▾',
+                (true, false, _) => '▸',
+                (false, _, 0) => ' ',
+                (false, _, _) => '─',
+            };
+
+            visible_nodes.push(VisibleNode {
+                node,
+                depth,
+                guides: format!("{}{}{} ", guides, connector, marker),
+                parent,
+            });
+
+            if is_expanded {
+                let node_index = visible_nodes.len() - 1;
+                self.push_visible_nodes(
+                    &node.children,
+                    depth + 1,
+                    &child_guides,
+                    Some(node_index),
+                    visible_nodes,
+                );
+            }
+        }
+    }
+
+    fn select(&mut self, node: &TreeNode<Id>, messages: &mut Vec<Message>) {
+        if self.state.selected.as_ref() != Some(&node.id) {
+            self.state.selected = Some(node.id.clone());
+
+            if let Some(on_select) = &self.on_select {
+                messages.push(on_select(node.id.clone()));
+            }
+        }
+    }
+
+    fn toggle(&mut self, node: &TreeNode<Id>, messages: &mut Vec<Message>) {
+        if self.state.is_expanded(&node.id) {
+            self.state.collapse(&node.id);
+        } else if node.has_children() {
+            self.state.expand(node.id.clone());
+
+            if let Some(on_expand) = &self.on_expand {
+                messages.push(on_expand(node.id.clone()));
+            }
+        }
+    }
+
+    /// Scrolls just enough to show the selected node.
+    fn scroll_to_selected(&mut self, height: usize) {
+        let visible_nodes = self.visible_nodes();
+        let position = self.selected_position(&visible_nodes);
+
+        if let Some(position) = position {
+            if position < self.state.scroll {
+                self.state.scroll = position;
+            } else if height > 0 && position >= self.state.scroll + height {
+                self.state.scroll = position + 1 - height;
+            }
+        }
+
+        self.state.scroll = self
+            .state
+            .scroll
+            .min(visible_nodes.len().saturating_sub(height));
+    }
+
+    fn selected_position(&self, visible_nodes: &[VisibleNode<'a, Id>]) -> Option<usize> {
+        let selected = self.state.selected.as_ref()?;
+
+        visible_nodes
+            .iter()
+            .position(|visible_node| &visible_node.node.id == selected)
+    }
+}
+
+/// A node shown in a [`Tree`], with its indentation.
+struct VisibleNode<'a, Id> {
+    node: &'a TreeNode<Id>,
+    depth: u16,
+    /// The guide lines and the marker drawn before the label.
+    guides: String,
+    parent: Option<usize>,
+}
+
+impl<'a, Id> VisibleNode<'a, Id> {
+    /// Returns the column of the expand marker, relative to the tree.
+    fn marker_column(&self) -> u16 {
+        self.depth * 2
+    }
+}
+
+/// A node of a [`Tree`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeNode<Id> {
+    id: Id,
+    label: String,
+    children: Vec<TreeNode<Id>>,
+    is_expandable: bool,
+}
+
+impl<Id> TreeNode<Id> {
+    pub fn new<T>(id: Id, label: T) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            id,
+            label: label.into(),
+            children: vec![],
+            is_expandable: false,
+        }
+    }
+
+    pub fn children(mut self, children: Vec<TreeNode<Id>>) -> Self {
+        self.children = children;
+        self
+    }
+
+    pub fn push(mut self, child: TreeNode<Id>) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Sets whether the node can be expanded before its children are loaded.
+    pub fn expandable(mut self, is_expandable: bool) -> Self {
+        self.is_expandable = is_expandable;
+        self
+    }
+
+    pub fn id(&self) -> &Id {
+        &self.id
+    }
+
+    fn has_children(&self) -> bool {
+        self.is_expandable || !self.children.is_empty()
+    }
+}
+
+/// The state of a [`Tree`].
+#[derive(Debug, Clone)]
+pub struct State<Id> {
+    expanded: HashSet<Id>,
+    selected: Option<Id>,
+    scroll: usize,
+}
+
+impl<Id> Default for State<Id> {
+    fn default() -> Self {
+        Self {
+            expanded: HashSet::new(),
+            selected: None,
+            scroll: 0,
+        }
+    }
+}
+
+impl<Id> State<Id>
+where
+    Id: Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of the selected node.
+    pub fn selected(&self) -> Option<&Id> {
+        self.selected.as_ref()
+    }
+
+    pub fn select(&mut self, selected: Option<Id>) {
+        self.selected = selected;
+    }
+
+    pub fn is_expanded(&self, id: &Id) -> bool {
+        self.expanded.contains(id)
+    }
+
+    pub fn expand(&mut self, id: Id) {
+        self.expanded.insert(id);
+    }
+
+    pub fn collapse(&mut self, id: &Id) {
+        self.expanded.remove(id);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TreeStyle {
+    pub(crate) normal: Style,
+    pub(crate) guide: Style,
+    pub(crate) selected: Style,
+    pub(crate) focused_selected: Style,
+}
+
+impl TreeStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn normal(mut self, normal: Style) -> Self {
+        self.normal = normal;
+        self
+    }
+
+    /// Sets the style of the guide lines and of the expand markers.
+    pub fn guide(mut self, guide: Style) -> Self {
+        self.guide = guide;
+        self
+    }
+
+    /// Sets the style of the selected node.
+    ///
+    /// When not set, the selected node is drawn in bold.
+    pub fn selected(mut self, selected: Style) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Sets the style of the selected node when the tree has the keyboard focus.
+    pub fn focused_selected(mut self, focused_selected: Style) -> Self {
+        self.focused_selected = focused_selected;
+        self
+    }
+}
+
+impl<'a, Message, Id> Widget<Message, TuiRenderer> for Tree<'a, Message, Id>
+where
+    Id: Clone + Eq + Hash,
+{
+    fn width(&self) -> Length {
+        self.width
+    }
+
+    fn height(&self) -> Length {
+        self.height
+    }
+
+    fn layout(&self, _renderer: &TuiRenderer, limits: &layout::Limits) -> layout::Node {
+        let limits = limits.width(self.width).height(self.height);
+        let intrinsic = Size::new(0.0, self.visible_nodes().len() as f32);
+
+        layout::Node::new(limits.resolve(intrinsic))
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+
+        self.width.hash(state);
+        self.height.hash(state);
+        self.visible_nodes().len().hash(state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &TuiRenderer,
+        _clipboard: &mut dyn Clipboard,
+        messages: &mut Vec<Message>,
+    ) -> event::Status {
+        let bounds = layout.bounds();
+        let height = bounds.height.round() as usize;
+        let visible_nodes = self.visible_nodes();
+
+        let status = match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left))
+                if bounds.contains(cursor_position) =>
+            {
+                let x = (cursor_position.x - bounds.x.round()) as u16;
+                let y = (cursor_position.y - bounds.y.round()) as usize;
+
+                if let Some(visible_node) = visible_nodes.get(self.state.scroll + y) {
+                    self.select(visible_node.node, messages);
+
+                    if x == visible_node.marker_column() {
+                        self.toggle(visible_node.node, messages);
+                    }
+                }
+
+                event::Status::Captured
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta })
+                if bounds.contains(cursor_position) =>
+            {
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y,
+                };
+                let max_scroll = visible_nodes.len().saturating_sub(height);
+                let scroll = self.state.scroll as isize - lines.round() as isize;
+                self.state.scroll = scroll.clamp(0, max_scroll as isize) as usize;
+
+                // scrolling doesn't move the selection
+                return event::Status::Captured;
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. })
+                if renderer.is_focused(bounds) && !visible_nodes.is_empty() =>
+            {
+                let last = visible_nodes.len() - 1;
+                let current = self.selected_position(&visible_nodes);
+                let page = height.max(1);
+
+                let target = match (key_code, current) {
+                    (keyboard::KeyCode::Up, Some(current)) => Some(current.saturating_sub(1)),
+                    (keyboard::KeyCode::Down, Some(current)) => Some((current + 1).min(last)),
+                    (keyboard::KeyCode::PageUp, Some(current)) => {
+                        Some(current.saturating_sub(page))
+                    }
+                    (keyboard::KeyCode::PageDown, Some(current)) => {
+                        Some((current + page).min(last))
+                    }
+                    (keyboard::KeyCode::Up, None) | (keyboard::KeyCode::PageUp, None) => Some(last),
+                    (keyboard::KeyCode::Down, None) | (keyboard::KeyCode::PageDown, None) => {
+                        Some(0)
+                    }
+                    (keyboard::KeyCode::Home, _) => Some(0),
+                    (keyboard::KeyCode::End, _) => Some(last),
+                    // expand the node, then move to its first child
+                    (keyboard::KeyCode::Right, Some(current)) => {
+                        let node = visible_nodes[current].node;
+
+                        if !self.state.is_expanded(&node.id) {
+                            self.toggle(node, messages);
+                            Some(current)
+                        } else {
+                            Some((current + 1).min(last))
+                        }
+                    }
+                    // collapse the node, or move to its parent
+                    (keyboard::KeyCode::Left, Some(current)) => {
+                        let visible_node = &visible_nodes[current];
+
+                        if self.state.is_expanded(&visible_node.node.id) {
+                            self.toggle(visible_node.node, messages);
+                            Some(current)
+                        } else {
+                            Some(visible_node.parent.unwrap_or(current))
+                        }
+                    }
+                    (keyboard::KeyCode::Enter, Some(current)) => {
+                        self.toggle(visible_nodes[current].node, messages);
+                        Some(current)
+                    }
+                    _ => None,
+                };
+
+                match target {
+                    Some(target) => {
+                        self.select(visible_nodes[target].node, messages);
+                        event::Status::Captured
+                    }
+                    None => event::Status::Ignored,
+                }
+            }
+            _ => event::Status::Ignored,
+        };
+
+        if status == event::Status::Captured {
+            self.scroll_to_selected(height);
+        }
+
+        status
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut TuiRenderer,
+        _defaults: &Style,
+        layout: Layout<'_>,
+        _cursor_position: Point,
+        viewport: &Rectangle,
+    ) -> Primitive {
+        let bounds = layout.bounds();
+        let is_focused = renderer.register_focusable(FocusKind::Tree, bounds, false);
+
+        let x = bounds.x.round() as u16;
+        let y = bounds.y.round() as u16;
+        let width = bounds.width.round() as u16;
+        let height = bounds.height.round() as u16;
+
+        let mut primitives = vec![Primitive::Rectangle(
+            x,
+            y,
+            width,
+            height,
+            Cell {
+                content: None,
+                style: self.style.normal,
+            },
+        )];
+
+        let selected_style = match (is_focused, self.style.focused_selected.is_empty()) {
+            (true, false) => self.style.focused_selected,
+            _ if self.style.selected.is_empty() => Style::new().bold(),
+            _ => self.style.selected,
+        };
+        let guide_style = self.style.normal.merge(self.style.guide);
+
+        // only the nodes visible in the tree and in the viewport are drawn
+        let first_y = viewport.y.round().max(bounds.y.round()) as u16;
+        let last_y = (viewport.y + viewport.height).round() as u16;
+
+        for (row_y, visible_node) in self
+            .visible_nodes()
+            .iter()
+            .skip(self.state.scroll)
+            .take(height as usize)
+            .enumerate()
+        {
+            let row_y = y + row_y as u16;
+
+            if row_y < first_y || row_y >= last_y {
+                continue;
+            }
+
+            let guides = truncate_text(&visible_node.guides, width);
+            let guides_width = text_width(&guides);
+            primitives.extend(text_cells(&guides, x, row_y, guide_style));
+
+            let label_style = match self.state.selected.as_ref() == Some(&visible_node.node.id) {
+                true => self.style.normal.merge(selected_style),
+                false => self.style.normal,
+            };
+            let label = truncate_text(&visible_node.node.label, width - guides_width);
+            primitives.extend(text_cells(&label, x + guides_width, row_y, label_style));
+        }
+
+        Primitive::Group(primitives)
+    }
+}
+
+impl<'a, Message, Id> From<Tree<'a, Message, Id>> for Element<'a, Message, TuiRenderer>
+where
+    Message: 'a,
+    Id: 'a + Clone + Eq + Hash,
+{
+    fn from(tree: Tree<'a, Message, Id>) -> Self {
+        Element::new(tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{State, Tree, TreeNode};
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::TuiRenderer;
+    use iced_native::{
+        clipboard, keyboard, scrollable, Cache, Element, Event, Length, Point, Rectangle,
+        Scrollable, Size, UserInterface,
+    };
+
+    fn nodes() -> Vec<TreeNode<&'static str>> {
+        vec![
+            TreeNode::new("src", "src")
+                .push(TreeNode::new("main.rs", "main.rs"))
+                .push(
+                    TreeNode::new("widget", "widget")
+                        .push(TreeNode::new("tree.rs", "tree.rs"))
+                        .push(TreeNode::new("table.rs", "table.rs")),
+                )
+                .push(TreeNode::new("lib.rs", "lib.rs")),
+            TreeNode::new("target", "target").expandable(true),
+            TreeNode::new("README.md", "README.md"),
+        ]
+    }
+
+    fn draw(state: &mut State<&'static str>, nodes: &[TreeNode<&'static str>]) -> Vec<String> {
+        let mut renderer = TuiRenderer::default();
+        let tree: Element<'_, (), TuiRenderer> = Tree::new(state, nodes).into();
+        let mut ui =
+            UserInterface::build(tree, Size::new(14., 8.), Cache::default(), &mut renderer);
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(14, 8);
+        vbuffer.merge_primitive(&primitive);
+
+        vbuffer
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.content.unwrap_or(' ')).collect())
+            .collect()
+    }
+
+    #[test]
+    fn it_draw_guide_lines() {
+        let nodes = nodes();
+        let mut state = State::new();
+        state.expand("src");
+        state.expand("widget");
+
+        assert_eq!(
+            draw(&mut state, &nodes),
+            vec![
+                "▾ src         ",
+                "├── main.rs   ",
+                "├─▾ widget    ",
+                "│ ├── tree.rs ",
+                "│ └── table.rs",
+                "└── lib.rs    ",
+                "▸ target      ",
+                "  README.md   ",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_expand_and_collapse_with_keys() {
+        let nodes = nodes();
+        let mut state = State::new();
+        let mut renderer = TuiRenderer::default();
+
+        let tree: Element<'_, &'static str, TuiRenderer> = Tree::new(&mut state, &nodes)
+            .on_expand(|id| id)
+            .height(Length::Fill)
+            .into();
+        let mut ui =
+            UserInterface::build(tree, Size::new(14., 8.), Cache::default(), &mut renderer);
+
+        renderer.begin_frame();
+        ui.draw(&mut renderer, Point::new(-1., -1.));
+        renderer.end_frame();
+        renderer
+            .focus_chain
+            .split_events(vec![tab()], Point::new(-1., -1.));
+
+        let mut messages = vec![];
+
+        for key_code in [
+            keyboard::KeyCode::Down,
+            keyboard::KeyCode::Right,
+            keyboard::KeyCode::Right,
+            keyboard::KeyCode::Down,
+            keyboard::KeyCode::Left,
+            keyboard::KeyCode::Left,
+            keyboard::KeyCode::Down,
+            keyboard::KeyCode::Right,
+            keyboard::KeyCode::Left,
+        ] {
+            ui.update(
+                &[Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code,
+                    modifiers: keyboard::Modifiers::default(),
+                })],
+                Point::new(-1., -1.),
+                &renderer,
+                &mut clipboard::Null,
+                &mut messages,
+            );
+        }
+
+        drop(ui);
+
+        // expanding the lazy node asks for its children
+        assert_eq!(messages, vec!["src", "target"]);
+        assert_eq!(state.selected(), Some(&"target"));
+        assert!(!state.is_expanded(&"src"));
+        assert!(!state.is_expanded(&"target"));
+    }
+
+    #[test]
+    fn it_toggle_selected_node_with_enter() {
+        let nodes = nodes();
+        let mut state = State::new();
+        state.select(Some("src"));
+        let mut renderer = TuiRenderer::default();
+
+        let tree: Element<'_, &'static str, TuiRenderer> =
+            Tree::new(&mut state, &nodes).on_expand(|id| id).into();
+        let mut ui =
+            UserInterface::build(tree, Size::new(14., 8.), Cache::default(), &mut renderer);
+
+        renderer.begin_frame();
+        ui.draw(&mut renderer, Point::new(-1., -1.));
+        renderer.end_frame();
+        renderer
+            .focus_chain
+            .split_events(vec![tab()], Point::new(-1., -1.));
+
+        // enter goes to the tree instead of clicking its center
+        let (events, clicks) = renderer.focus_chain.split_events(
+            vec![Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code: keyboard::KeyCode::Enter,
+                modifiers: keyboard::Modifiers::default(),
+            })],
+            Point::new(-1., -1.),
+        );
+        assert!(clicks.is_empty());
+
+        let mut messages = vec![];
+        ui.update(
+            &events,
+            Point::new(-1., -1.),
+            &renderer,
+            &mut clipboard::Null,
+            &mut messages,
+        );
+        drop(ui);
+
+        assert_eq!(messages, vec!["src"]);
+        assert!(state.is_expanded(&"src"));
+    }
+
+    #[test]
+    fn it_draw_scrolled_rows() {
+        let nodes = nodes();
+        let mut state = State::new();
+        state.expand("src");
+        state.expand("widget");
+        let mut scrollable_state = scrollable::State::new();
+        scrollable_state.scroll_to(
+            1.0,
+            Rectangle::new(Point::ORIGIN, Size::new(14., 3.)),
+            Rectangle::new(Point::ORIGIN, Size::new(14., 8.)),
+        );
+        let mut renderer = TuiRenderer::default();
+
+        let scrollable: Element<'_, (), TuiRenderer> = Scrollable::new(&mut scrollable_state)
+            .height(Length::Units(3))
+            .push(Tree::new(&mut state, &nodes))
+            .into();
+        let mut ui = UserInterface::build(
+            scrollable,
+            Size::new(14., 3.),
+            Cache::default(),
+            &mut renderer,
+        );
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(14, 3);
+        vbuffer.merge_primitive(&primitive);
+
+        assert_eq!(
+            vbuffer.to_plain_text(),
+            "└── lib.rs\n▸ target\n  README.md\n"
+        );
+    }
+
+    fn tab() -> Event {
+        Event::Keyboard(keyboard::Event::KeyPressed {
+            key_code: keyboard::KeyCode::Tab,
+            modifiers: keyboard::Modifiers::default(),
+        })
+    }
+}
@@ -0,0 +1,555 @@
+//! Display a large number of rows, building only the visible ones.
+use crate::renderer::focus::FocusKind;
+use crate::renderer::primitives::{Cell, Primitive};
+use crate::{Style, TuiRenderer};
+use iced_native::{
+    event, keyboard, layout, mouse, Clipboard, Element, Event, Hasher, Layout, Length, Point,
+    Rectangle, Size, Vector, Widget,
+};
+
+/// A list of rows of the same height, built on demand by a closure.
+///
+/// Only the rows visible in the list are built, laid out and drawn, so it can display a
+/// very large number of rows. The rows are rebuilt for each event, so they can't keep any
+/// widget state.
+#[allow(clippy::type_complexity)]
+pub struct VirtualList<'a, Message> {
+    state: &'a mut State,
+    count: usize,
+    row_builder: Box<dyn Fn(usize) -> Element<'a, Message, TuiRenderer> + 'a>,
+    on_select: Option<Box<dyn Fn(usize) -> Message + 'a>>,
+    row_height: u16,
+    width: Length,
+    height: Length,
+    style: VirtualListStyle,
+}
+
+impl<'a, Message> VirtualList<'a, Message> {
+    pub fn new<F>(state: &'a mut State, count: usize, row_builder: F) -> Self
+    where
+        F: 'a + Fn(usize) -> Element<'a, Message, TuiRenderer>,
+    {
+        Self {
+            state,
+            count,
+            row_builder: Box::new(row_builder),
+            on_select: None,
+            row_height: 1,
+            width: Length::Fill,
+            height: Length::Fill,
+            style: VirtualListStyle::default(),
+        }
+    }
+
+    /// Sets the message produced with the index of the selected row.
+    pub fn on_select<F>(mut self, on_select: F) -> Self
+    where
+        F: 'a + Fn(usize) -> Message,
+    {
+        self.on_select = Some(Box::new(on_select));
+        self
+    }
+
+    pub fn row_height(mut self, row_height: u16) -> Self {
+        self.row_height = row_height.max(1);
+        self
+    }
+
+    pub fn width(mut self, width: Length) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn height(mut self, height: Length) -> Self {
+        self.height = height;
+        self
+    }
+
+    pub fn style(mut self, style: VirtualListStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Returns the number of rows fully visible in the bounds.
+    fn page_size(&self, bounds: Rectangle) -> usize {
+        (bounds.height.round() as usize / self.row_height as usize).max(1)
+    }
+
+    /// Returns the index of the first visible row, accounting for a selection made
+    /// through the state since the last event.
+    fn first_row(&self, bounds: Rectangle) -> usize {
+        let page_size = self.page_size(bounds);
+        let mut state = self.state.clone();
+
+        if state.scroll_to_selected {
+            state.show_selected(page_size);
+        }
+
+        state.scroll.min(self.count.saturating_sub(page_size))
+    }
+
+    /// Returns the bounds of the visible row at the given index.
+    fn row_bounds(&self, bounds: Rectangle, index: usize) -> Rectangle {
+        let first = self.first_row(bounds);
+
+        Rectangle {
+            x: bounds.x.round(),
+            y: bounds.y.round() + ((index - first) * self.row_height as usize) as f32,
+            width: bounds.width.round(),
+            height: self.row_height as f32,
+        }
+    }
+
+    /// Returns the indexes of the visible rows.
+    fn visible_rows(&self, bounds: Rectangle) -> std::ops::Range<usize> {
+        let visible = (bounds.height.round() as usize).div_ceil(self.row_height as usize);
+        let first = self.first_row(bounds);
+
+        first..(first + visible).min(self.count)
+    }
+
+    fn layout_row(
+        &self,
+        row: &Element<'a, Message, TuiRenderer>,
+        renderer: &TuiRenderer,
+        bounds: Rectangle,
+    ) -> layout::Node {
+        let limits = layout::Limits::new(Size::ZERO, bounds.size())
+            .width(Length::Fill)
+            .height(Length::Units(self.row_height));
+
+        let mut node = row.layout(renderer, &limits);
+        node.move_to(bounds.position());
+        node
+    }
+
+    fn select(&mut self, index: usize, messages: &mut Vec<Message>) {
+        if index >= self.count || self.state.selected == Some(index) {
+            return;
+        }
+
+        self.state.selected = Some(index);
+
+        if let Some(on_select) = &self.on_select {
+            messages.push(on_select(index));
+        }
+    }
+}
+
+/// The state of a [`VirtualList`].
+#[derive(Debug, Clone, Default)]
+pub struct State {
+    selected: Option<usize>,
+    scroll: usize,
+    scroll_to_selected: bool,
+}
+
+impl State {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Selects a row, scrolling the list to show it.
+    pub fn select(&mut self, selected: Option<usize>) {
+        self.selected = selected;
+        self.scroll_to_selected = true;
+    }
+
+    /// Returns the index of the first visible row.
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    pub fn scroll_to(&mut self, index: usize) {
+        self.scroll = index;
+        self.scroll_to_selected = false;
+    }
+
+    /// Scrolls just enough to show the selected row.
+    fn show_selected(&mut self, page_size: usize) {
+        if let Some(selected) = self.selected {
+            if selected < self.scroll {
+                self.scroll = selected;
+            } else if selected >= self.scroll + page_size {
+                self.scroll = selected + 1 - page_size;
+            }
+        }
+
+        self.scroll_to_selected = false;
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VirtualListStyle {
+    pub(crate) normal: Style,
+    pub(crate) selected: Style,
+    pub(crate) focused_selected: Style,
+}
+
+impl VirtualListStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn normal(mut self, normal: Style) -> Self {
+        self.normal = normal;
+        self
+    }
+
+    /// Sets the style of the selected row.
+    ///
+    /// When not set, the selected row is drawn in bold.
+    pub fn selected(mut self, selected: Style) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Sets the style of the selected row when the list has the keyboard focus.
+    pub fn focused_selected(mut self, focused_selected: Style) -> Self {
+        self.focused_selected = focused_selected;
+        self
+    }
+}
+
+impl<'a, Message> Widget<Message, TuiRenderer> for VirtualList<'a, Message> {
+    fn width(&self) -> Length {
+        self.width
+    }
+
+    fn height(&self) -> Length {
+        self.height
+    }
+
+    fn layout(&self, _renderer: &TuiRenderer, limits: &layout::Limits) -> layout::Node {
+        let limits = limits.width(self.width).height(self.height);
+        let intrinsic = Size::new(0.0, (self.count * self.row_height as usize) as f32);
+
+        layout::Node::new(limits.resolve(intrinsic))
+    }
+
+    fn hash_layout(&self, state: &mut Hasher) {
+        use std::hash::Hash;
+
+        struct Marker;
+        std::any::TypeId::of::<Marker>().hash(state);
+
+        self.width.hash(state);
+        self.height.hash(state);
+        self.count.hash(state);
+        self.row_height.hash(state);
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        renderer: &TuiRenderer,
+        clipboard: &mut dyn Clipboard,
+        messages: &mut Vec<Message>,
+    ) -> event::Status {
+        let bounds = layout.bounds();
+        let page_size = self.page_size(bounds);
+        self.state.scroll = self.first_row(bounds);
+        self.state.scroll_to_selected = false;
+
+        let row_under_cursor = bounds
+            .contains(cursor_position)
+            .then(|| {
+                let offset = (cursor_position.y - bounds.y.round()) as usize;
+                self.state.scroll + offset / self.row_height as usize
+            })
+            .filter(|index| self.visible_rows(bounds).contains(index));
+
+        // the row under the cursor gets the mouse events first, and the selected row the
+        // keyboard events
+        let event_row = match event {
+            Event::Keyboard(_) => self
+                .state
+                .selected
+                .filter(|index| self.visible_rows(bounds).contains(index)),
+            _ => row_under_cursor,
+        };
+
+        if let Some(index) = event_row {
+            let mut row = (self.row_builder)(index);
+            let node = self.layout_row(&row, renderer, self.row_bounds(bounds, index));
+            let status = row.on_event(
+                event.clone(),
+                Layout::new(&node),
+                cursor_position,
+                renderer,
+                clipboard,
+                messages,
+            );
+
+            if status == event::Status::Captured {
+                return status;
+            }
+        }
+
+        if let Some(index) = row_under_cursor {
+            if let Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event {
+                self.select(index, messages);
+                return event::Status::Captured;
+            }
+        }
+
+        let max_scroll = self.count.saturating_sub(page_size);
+
+        match event {
+            Event::Mouse(mouse::Event::WheelScrolled { delta })
+                if bounds.contains(cursor_position) =>
+            {
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y,
+                };
+                let scroll = self.state.scroll as isize - lines.round() as isize;
+                self.state.scroll = scroll.clamp(0, max_scroll as isize) as usize;
+
+                event::Status::Captured
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed { key_code, .. })
+                if renderer.is_focused(bounds) && self.count > 0 =>
+            {
+                let last = self.count - 1;
+
+                let target = match (key_code, self.state.selected) {
+                    (keyboard::KeyCode::Up, Some(current)) => current.saturating_sub(1),
+                    (keyboard::KeyCode::Down, Some(current)) => (current + 1).min(last),
+                    (keyboard::KeyCode::PageUp, Some(current)) => current.saturating_sub(page_size),
+                    (keyboard::KeyCode::PageDown, Some(current)) => (current + page_size).min(last),
+                    (keyboard::KeyCode::Up, None) | (keyboard::KeyCode::PageUp, None) => {
+                        self.state.scroll.min(last)
+                    }
+                    (keyboard::KeyCode::Down, None) | (keyboard::KeyCode::PageDown, None) => {
+                        self.state.scroll.min(last)
+                    }
+                    (keyboard::KeyCode::Home, _) => 0,
+                    (keyboard::KeyCode::End, _) => last,
+                    _ => return event::Status::Ignored,
+                };
+
+                self.select(target, messages);
+                self.state.show_selected(page_size);
+                self.state.scroll = self.state.scroll.min(max_scroll);
+
+                event::Status::Captured
+            }
+            _ => event::Status::Ignored,
+        }
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut TuiRenderer,
+        defaults: &Style,
+        layout: Layout<'_>,
+        cursor_position: Point,
+        viewport: &Rectangle,
+    ) -> Primitive {
+        let bounds = layout.bounds();
+        let is_focused = renderer.register_focusable(FocusKind::List, bounds, false);
+
+        let x = bounds.x.round() as u16;
+        let y = bounds.y.round() as u16;
+        let width = bounds.width.round() as u16;
+        let height = bounds.height.round() as u16;
+
+        let mut primitives = vec![Primitive::Rectangle(
+            x,
+            y,
+            width,
+            height,
+            Cell {
+                content: None,
+                style: self.style.normal,
+            },
+        )];
+
+        let selected_style = match (is_focused, self.style.focused_selected.is_empty()) {
+            (true, false) => self.style.focused_selected,
+            _ if self.style.selected.is_empty() => Style::new().bold(),
+            _ => self.style.selected,
+        };
+
+        for index in self.visible_rows(bounds) {
+            let row_bounds = self.row_bounds(bounds, index);
+            let row = (self.row_builder)(index);
+            let node = self.layout_row(&row, renderer, row_bounds);
+
+            if self.state.selected == Some(index) {
+                let selection = Primitive::Rectangle(
+                    x,
+                    row_bounds.y as u16,
+                    width,
+                    self.row_height,
+                    Cell {
+                        content: None,
+                        style: selected_style,
+                    },
+                );
+                primitives.push(selection.crop(x, y, width, height));
+            }
+
+            // the last row may only be partially visible
+            let row = row.draw(
+                renderer,
+                defaults,
+                Layout::with_offset(Vector::new(0.0, 0.0), &node),
+                cursor_position,
+                viewport,
+            );
+            primitives.push(row.crop(x, y, width, height));
+        }
+
+        Primitive::Group(primitives)
+    }
+}
+
+impl<'a, Message> From<VirtualList<'a, Message>> for Element<'a, Message, TuiRenderer>
+where
+    Message: 'a,
+{
+    fn from(virtual_list: VirtualList<'a, Message>) -> Self {
+        Element::new(virtual_list)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{State, VirtualList};
+    use crate::renderer::virtual_buffer::VirtualBuffer;
+    use crate::TuiRenderer;
+    use iced_native::{
+        clipboard, keyboard, mouse, Cache, Column, Element, Event, Point, Size, Text, UserInterface,
+    };
+    use std::cell::Cell;
+
+    fn lines(
+        renderer: &mut TuiRenderer,
+        ui: &mut UserInterface<'_, usize, TuiRenderer>,
+    ) -> Vec<String> {
+        renderer.begin_frame();
+        let primitive = ui.draw(renderer, Point::new(-1., -1.));
+        renderer.end_frame();
+
+        let mut vbuffer = VirtualBuffer::from_size(6, 3);
+        vbuffer.merge_primitive(&primitive);
+
+        vbuffer
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.content.unwrap_or(' ')).collect())
+            .collect()
+    }
+
+    #[test]
+    fn it_build_only_visible_rows() {
+        let mut renderer = TuiRenderer::default();
+        let mut state = State::new();
+        state.scroll_to(500);
+        let built = Cell::new(0);
+
+        let list: Element<'_, usize, TuiRenderer> =
+            VirtualList::new(&mut state, 1_000_000, |index| {
+                built.set(built.get() + 1);
+                Text::new(format!("{}", index)).into()
+            })
+            .into();
+        let mut ui = UserInterface::build(list, Size::new(6., 3.), Cache::default(), &mut renderer);
+
+        assert_eq!(
+            lines(&mut renderer, &mut ui),
+            vec!["500   ", "501   ", "502   "]
+        );
+        assert_eq!(built.get(), 3);
+    }
+
+    #[test]
+    fn it_page_through_selection() {
+        let mut renderer = TuiRenderer::default();
+        let mut state = State::new();
+
+        let list: Element<'_, usize, TuiRenderer> = VirtualList::new(&mut state, 10, |index| {
+            Text::new(format!("{}", index)).into()
+        })
+        .on_select(|index| index)
+        .into();
+        let mut ui = UserInterface::build(list, Size::new(6., 3.), Cache::default(), &mut renderer);
+        lines(&mut renderer, &mut ui);
+
+        let mut messages = vec![];
+        let click = [Event::Mouse(mouse::Event::ButtonPressed(
+            mouse::Button::Left,
+        ))];
+        let (events, _) = renderer
+            .focus_chain
+            .split_events(click.to_vec(), Point::new(0., 1.));
+        ui.update(
+            &events,
+            Point::new(0., 1.),
+            &renderer,
+            &mut clipboard::Null,
+            &mut messages,
+        );
+
+        for key_code in [
+            keyboard::KeyCode::PageDown,
+            keyboard::KeyCode::End,
+            keyboard::KeyCode::Up,
+            keyboard::KeyCode::Home,
+        ] {
+            ui.update(
+                &[Event::Keyboard(keyboard::Event::KeyPressed {
+                    key_code,
+                    modifiers: keyboard::Modifiers::default(),
+                })],
+                Point::new(-1., -1.),
+                &renderer,
+                &mut clipboard::Null,
+                &mut messages,
+            );
+
+            if key_code == keyboard::KeyCode::Up {
+                assert_eq!(
+                    lines(&mut renderer, &mut ui),
+                    vec!["7     ", "8     ", "9     "]
+                );
+            }
+        }
+
+        assert_eq!(messages, vec![1, 4, 9, 8, 0]);
+        assert_eq!(
+            lines(&mut renderer, &mut ui),
+            vec!["0     ", "1     ", "2     "]
+        );
+    }
+
+    #[test]
+    fn it_crop_partially_visible_row() {
+        let mut renderer = TuiRenderer::default();
+        let mut state = State::new();
+
+        let list: Element<'_, usize, TuiRenderer> = VirtualList::new(&mut state, 10, |index| {
+            Column::new()
+                .push(Text::new(format!("{}", index)))
+                .push(Text::new("-"))
+                .into()
+        })
+        .row_height(2)
+        .into();
+        let mut ui = UserInterface::build(list, Size::new(6., 3.), Cache::default(), &mut renderer);
+
+        let primitive = ui.draw(&mut renderer, Point::new(-1., -1.));
+        let mut vbuffer = VirtualBuffer::from_size(6, 4);
+        vbuffer.merge_primitive(&primitive);
+
+        assert_eq!(vbuffer.to_plain_text(), "0\n-\n1\n\n");
+    }
+}